@@ -33,11 +33,11 @@ fn main() {
             &[
                 GradientStop {
                     offset: 0.0,
-                    rgba: 0xFFFFFFFFFFFFFFFF,
+                    rgba: Rgba64(0xFFFFFFFFFFFFFFFF),
                 },
                 GradientStop {
                     offset: 1.0,
-                    rgba: 0xFFFFFFFF6F6F3F3F,
+                    rgba: Rgba64(0xFFFFFFFF6F6F3F3F),
                 },
             ],
             None,
@@ -82,11 +82,11 @@ fn main() {
             &[
                 GradientStop {
                     offset: 0.0,
-                    rgba: 0xFFFFFFFFFFFFFFFF,
+                    rgba: Rgba64(0xFFFFFFFFFFFFFFFF),
                 },
                 GradientStop {
                     offset: 1.0,
-                    rgba: 0xFFFF3F3F9F9FFFFF,
+                    rgba: Rgba64(0xFFFF3F3F9F9FFFFF),
                 },
             ],
             None,
@@ -0,0 +1,41 @@
+use blend2d::{
+    font::FontFace,
+    geometry::PointD,
+    prelude::*,
+    text_layout::{TextAlignment, TextLayout, TextLayoutOptions},
+};
+
+fn main() {
+    let mut img = Image::new(480, 480, ImageFormat::PRgb32).expect("Unable to create image");
+    let ctx = Context::new(&mut img).expect("Unable to attach rendering context");
+    let render = |mut ctx: Context| {
+        ctx.set_comp_op(CompOp::SrcCopy);
+        ctx.fill_all()?;
+        ctx.set_fill_style_rgba32(0xFFFFFFFF);
+        let font_face = FontFace::from_path("assets/NotoSans-Regular.ttf", DataAccessFlags::READ)?;
+        let font = font_face.create_font(20.0)?;
+
+        let text = "Hello Blend2D!\nI'm a text layout example that breaks long lines to fit \
+                    into a maximum width, aligns them and cuts off whatever doesn't fit into \
+                    the maximum number of lines.";
+        let options = TextLayoutOptions {
+            max_width: 440.0,
+            alignment: TextAlignment::Justify,
+            max_lines: Some(4),
+            ..Default::default()
+        };
+        let layout = TextLayout::with_options(text, &font, &options)?;
+        ctx.fill_text_layout(PointD { x: 20.0, y: 190.0 }, &font, &layout)?;
+
+        ctx.end()
+    };
+    render(ctx).expect("Rendering to context failed");
+
+    img.write_to_file(
+        "bl-text-layout.bmp",
+        ImageCodec::built_in_codecs()
+            .find_codec_by_name("BMP")
+            .unwrap(),
+    )
+    .expect("Writing to file failed");
+}
@@ -13,6 +13,14 @@ use crate::variant::WrappedBlCore;
 /// This is an array managed by blend2d, unless required one should use [`Vec`]
 /// instead. Its api tries to mimic [`Vec`] as close as possible.
 ///
+/// Like a [`Vec`] an array is `Send` and `Sync` if its items are, so an array
+/// of paths can't be shared between threads:
+///
+/// ```compile_fail
+/// fn assert_sync<T: Sync>() {}
+/// assert_sync::<blend2d::array::Array<blend2d::path::Path>>();
+/// ```
+///
 /// [`String`]: std/vec/struct.Vec.html
 #[repr(transparent)]
 pub struct Array<T: ArrayType> {
@@ -0,0 +1,476 @@
+//! Bidirectional text and script itemization.
+//!
+//! [`itemize`] splits a text into runs of a single script and embedding
+//! level as resolved by the
+//! [Unicode Bidirectional Algorithm](https://www.unicode.org/reports/tr9/),
+//! ordered visually from left to right. A [`BidiText`] shapes every run with
+//! a [`Font`] and lays them out next to each other, so that the glyph run of
+//! every [`ShapedRun`] can be drawn at its offset with
+//! [`Context::fill_glyph_run`](../context/struct.Context.html#method.fill_glyph_run),
+//! or the whole text at once with
+//! [`Context::fill_bidi_text`](../context/struct.Context.html#method.fill_bidi_text).
+//!
+//! blend2d shapes text left to right, the glyphs of right-to-left runs are
+//! therefore reversed after shaping, keeping marks at the glyphs they are
+//! attached to. Characters with a mirrored counterpart, like brackets, are
+//! replaced by it.
+//! Contextual forms, like those of the Arabic script, are only applied as far
+//! as blend2d supports them.
+use std::ops::Range;
+
+use unicode_bidi::{BidiInfo, Level};
+use unicode_script::UnicodeScript;
+
+use crate::error::Result;
+use crate::font::Font;
+use crate::font_defs::{GlyphPlacement, GlyphPlacementType, GlyphRun, TextDirection};
+use crate::glyph_buffer::{GlyphBuffer, GlyphId};
+
+pub use unicode_script::Script;
+
+/// A run of text with a single script and embedding level.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct ScriptRun {
+    /// The byte range of the run in the text.
+    pub range: Range<usize>,
+    /// The embedding level, odd levels are right-to-left.
+    pub level: u8,
+    pub script: Script,
+}
+
+impl ScriptRun {
+    /// Returns the direction of the run, given by its embedding level.
+    #[inline]
+    pub fn direction(&self) -> TextDirection {
+        if self.level % 2 == 1 {
+            TextDirection::Rtl
+        } else {
+            TextDirection::Ltr
+        }
+    }
+}
+
+/// Splits the text into runs of a single script and embedding level, in
+/// visual order.
+///
+/// The base direction of every paragraph of the text is given by
+/// `direction`, or by its first strong character if `direction` is `None`.
+/// Characters that are common to several scripts, like punctuation, belong
+/// to the script of the preceding character.
+pub fn itemize(text: &str, direction: Option<TextDirection>) -> Vec<ScriptRun> {
+    let base_level = direction.map(|direction| match direction {
+        TextDirection::Ltr => Level::ltr(),
+        TextDirection::Rtl => Level::rtl(),
+    });
+    let bidi = BidiInfo::new(text, base_level);
+    let mut runs = Vec::new();
+    for paragraph in &bidi.paragraphs {
+        let (levels, level_runs) = bidi.visual_runs(paragraph, paragraph.range.clone());
+        for level_run in level_runs {
+            let level = levels[level_run.start];
+            let start = runs.len();
+            runs.extend(
+                script_runs(text, level_run)
+                    .into_iter()
+                    .map(|(range, script)| ScriptRun {
+                        range,
+                        level: level.number(),
+                        script,
+                    }),
+            );
+            if level.is_rtl() {
+                runs[start..].reverse();
+            }
+        }
+    }
+    runs
+}
+
+/// Splits the range of the text into runs of a single script, in logical
+/// order.
+fn script_runs(text: &str, range: Range<usize>) -> Vec<(Range<usize>, Script)> {
+    let is_common = |script| matches!(script, Script::Common | Script::Inherited | Script::Unknown);
+    let mut runs: Vec<(Range<usize>, Script)> = Vec::new();
+    for (offset, c) in text[range.clone()].char_indices() {
+        let start = range.start + offset;
+        let end = start + c.len_utf8();
+        let script = c.script();
+        match runs.last_mut() {
+            Some((last, last_script)) if is_common(script) || *last_script == script => {
+                last.end = end
+            },
+            // Leading common characters belong to the first script.
+            Some((last, last_script)) if is_common(*last_script) => {
+                *last_script = script;
+                last.end = end;
+            },
+            _ => runs.push((start..end, script)),
+        }
+    }
+    runs
+}
+
+/// A shaped [`ScriptRun`] of a [`BidiText`].
+#[derive(Clone, Debug)]
+pub struct ShapedRun {
+    run: ScriptRun,
+    glyphs: Vec<GlyphId>,
+    placements: Vec<GlyphPlacement>,
+    placement_type: GlyphPlacementType,
+    clusters: Vec<usize>,
+    offset: f64,
+    width: f64,
+}
+
+impl ShapedRun {
+    /// Returns the run of text this run was shaped from.
+    #[inline]
+    pub fn script_run(&self) -> &ScriptRun {
+        &self.run
+    }
+
+    /// Returns the horizontal offset of this run from the start of the text.
+    #[inline]
+    pub fn offset(&self) -> f64 {
+        self.offset
+    }
+
+    /// Returns the advance width of this run.
+    #[inline]
+    pub fn width(&self) -> f64 {
+        self.width
+    }
+
+    /// Returns the byte offset into the text of the cluster of every glyph,
+    /// in the visual order of the glyphs.
+    #[inline]
+    pub fn clusters(&self) -> &[usize] {
+        &self.clusters
+    }
+
+    /// Returns the glyphs of this run in visual order, to be drawn at its
+    /// [`offset`](#method.offset) with the font of the text.
+    #[inline]
+    pub fn glyph_run(&self) -> GlyphRun<'_> {
+        GlyphRun::new(&self.glyphs, &self.placements, self.placement_type)
+    }
+}
+
+/// A line of bidirectional text, shaped and ordered visually.
+#[derive(Clone, Debug)]
+pub struct BidiText {
+    runs: Vec<ShapedRun>,
+    width: f64,
+}
+
+impl BidiText {
+    /// Itemizes the text as described by [`itemize`] and shapes every run
+    /// with the font.
+    pub fn new(text: &str, font: &Font, direction: Option<TextDirection>) -> Result<Self> {
+        let scale = f64::from(font.size()) / f64::from(font.units_per_em().max(1));
+        let mut buf = GlyphBuffer::new();
+        let mut runs = Vec::new();
+        let mut offset = 0.0;
+        for run in itemize(text, direction) {
+            let run_text = &text[run.range.clone()];
+            if run.direction() == TextDirection::Rtl {
+                buf.set_utf8_text(&run_text.chars().map(mirror).collect::<String>());
+            } else {
+                buf.set_utf8_text(run_text);
+            }
+            font.shape(&mut buf)?;
+            let placement_type = u32::from(buf.glyph_run().raw.placementType).into();
+            let mut glyphs: Vec<GlyphId> = buf.glyph_ids().collect();
+            let mut placements = buf.placements().to_vec();
+            placements.resize(glyphs.len(), GlyphPlacement::default());
+            let mut clusters: Vec<usize> = buf
+                .clusters()
+                .map(|cluster| run.range.start + cluster)
+                .collect();
+            if run.direction() == TextDirection::Rtl {
+                reverse_glyphs(&mut glyphs, &mut placements, &mut clusters);
+            }
+            let width = placements
+                .iter()
+                .map(|p| f64::from(p.advance.x) * scale)
+                .sum::<f64>();
+            runs.push(ShapedRun {
+                run,
+                glyphs,
+                placements,
+                placement_type,
+                clusters,
+                offset,
+                width,
+            });
+            offset += width;
+        }
+        Ok(BidiText {
+            runs,
+            width: offset,
+        })
+    }
+
+    /// Returns the shaped runs from left to right.
+    #[inline]
+    pub fn runs(&self) -> &[ShapedRun] {
+        &self.runs
+    }
+
+    /// Returns the advance width of the text.
+    #[inline]
+    pub fn width(&self) -> f64 {
+        self.width
+    }
+}
+
+/// Returns the mirrored counterpart of a character in right-to-left text, as
+/// given by its Bidi_Mirroring_Glyph property.
+fn mirror(c: char) -> char {
+    unicode_bidi_mirroring::get_mirrored(c).unwrap_or(c)
+}
+
+/// Reverses the glyphs of a run shaped left to right into right-to-left
+/// order.
+///
+/// Glyphs without advance, like marks, stay attached to the preceding glyph
+/// at the same position relative to it.
+fn reverse_glyphs(
+    glyphs: &mut [GlyphId],
+    placements: &mut [GlyphPlacement],
+    clusters: &mut [usize],
+) {
+    let len = glyphs.len();
+    // The start of every group of a glyph and its attached glyphs.
+    let group_starts: Vec<usize> = (0..len)
+        .filter(|&i| i == 0 || placements[i].advance.x != 0)
+        .collect();
+    let mut pens = Vec::with_capacity(len);
+    let mut pen = 0;
+    for placement in placements.iter() {
+        pens.push(pen);
+        pen += placement.advance.x;
+    }
+    let width = pen;
+
+    let mut order = Vec::with_capacity(len);
+    // The position of every glyph relative to the new start of its group.
+    let mut positions = Vec::with_capacity(len);
+    for (g, &start) in group_starts.iter().enumerate().rev() {
+        let end = group_starts.get(g + 1).copied().unwrap_or(len);
+        let group_width = pens.get(end).copied().unwrap_or(width) - pens[start];
+        let new_start = width - pens[start] - group_width;
+        for i in start..end {
+            order.push(i);
+            positions.push(new_start + pens[i] - pens[start] + placements[i].placement.x);
+        }
+    }
+
+    let old_placements = placements.to_vec();
+    let old_glyphs = glyphs.to_vec();
+    let old_clusters = clusters.to_vec();
+    let mut pen = 0;
+    for (out, (&i, &position)) in order.iter().zip(&positions).enumerate() {
+        glyphs[out] = old_glyphs[i];
+        clusters[out] = old_clusters[i];
+        placements[out] = old_placements[i];
+        placements[out].placement.x = position - pen;
+        pen += old_placements[i].advance.x;
+    }
+}
+
+#[cfg(test)]
+mod test_bidi {
+    use super::*;
+    use crate::context::Context;
+    use crate::font::FontFace;
+    use crate::geometry::{PointD, PointI};
+    use crate::image::{Image, ImageFormat};
+    use crate::DataAccessFlags;
+
+    fn runs(text: &str, direction: Option<TextDirection>) -> Vec<(&str, u8, Script)> {
+        itemize(text, direction)
+            .into_iter()
+            .map(|run| (&text[run.range], run.level, run.script))
+            .collect()
+    }
+
+    #[test]
+    fn test_itemize() {
+        assert_eq!(
+            runs("hello world", None),
+            [("hello world", 0, Script::Latin)]
+        );
+        assert_eq!(
+            runs("abc שלום def", None),
+            [
+                ("abc ", 0, Script::Latin),
+                ("שלום", 1, Script::Hebrew),
+                (" def", 0, Script::Latin)
+            ]
+        );
+        // The base direction is right-to-left, the Latin word is embedded.
+        assert_eq!(
+            runs("שלום abc!", None),
+            [
+                ("!", 1, Script::Common),
+                ("abc", 2, Script::Latin),
+                ("שלום ", 1, Script::Hebrew)
+            ]
+        );
+        assert_eq!(
+            runs("abc", Some(TextDirection::Rtl)),
+            [("abc", 2, Script::Latin)]
+        );
+        assert!(runs("", None).is_empty());
+    }
+
+    #[test]
+    fn test_itemize_scripts() {
+        // Arabic and Hebrew at the same level are ordered right to left.
+        assert_eq!(
+            runs("abc سلام שלום", None),
+            [
+                ("abc ", 0, Script::Latin),
+                ("שלום", 1, Script::Hebrew),
+                ("سلام ", 1, Script::Arabic)
+            ]
+        );
+        assert_eq!(runs("(1) Ωμέγα", None), [("(1) Ωμέγα", 0, Script::Greek)]);
+    }
+
+    #[test]
+    fn test_mirror() {
+        let mirrored: String = "(a) [b] «c»".chars().map(mirror).collect();
+        assert_eq!(mirrored, ")a( ]b[ »c«");
+        let mirrored: String = "x ≤ y ∈ ⁅z⁆".chars().map(mirror).collect();
+        assert_eq!(mirrored, "x ≥ y ∋ ⁆z⁅");
+    }
+
+    fn placement(offset: i32, advance: i32) -> GlyphPlacement {
+        GlyphPlacement {
+            placement: PointI { x: offset, y: 0 },
+            advance: PointI { x: advance, y: 0 },
+        }
+    }
+
+    #[test]
+    fn test_reverse_glyphs() {
+        // A glyph, a glyph with a mark attached to it and another glyph.
+        let mut glyphs = vec![1, 2, 3, 4];
+        let mut placements = vec![
+            placement(0, 20),
+            placement(0, 10),
+            placement(-6, 0),
+            placement(1, 5),
+        ];
+        let mut clusters = vec![0, 2, 4, 6];
+        reverse_glyphs(&mut glyphs, &mut placements, &mut clusters);
+        assert_eq!(glyphs, [4, 2, 3, 1]);
+        assert_eq!(clusters, [6, 2, 4, 0]);
+        assert_eq!(
+            placements,
+            [
+                placement(1, 5),
+                placement(0, 10),
+                placement(-6, 0),
+                placement(0, 20)
+            ]
+        );
+
+        reverse_glyphs(&mut [], &mut [], &mut []);
+    }
+
+    #[test]
+    fn test_reverse_leading_mark() {
+        let mut glyphs = vec![1, 2];
+        let mut placements = vec![placement(2, 0), placement(0, 10)];
+        let mut clusters = vec![0, 2];
+        reverse_glyphs(&mut glyphs, &mut placements, &mut clusters);
+        assert_eq!(glyphs, [2, 1]);
+        assert_eq!(placements, [placement(0, 10), placement(2, 0)]);
+    }
+
+    fn font() -> Font {
+        let path = concat!(env!("CARGO_MANIFEST_DIR"), "/assets/NotoSans-Regular.ttf");
+        let face = FontFace::from_path(path, DataAccessFlags::READ).unwrap();
+        face.create_font(20.0).unwrap()
+    }
+
+    // Noto Sans has no Hebrew and Arabic glyphs, their letters are shaped to
+    // the undefined glyph, one per character.
+    const MIXED: &str = "ab שלום سلام cd";
+
+    #[test]
+    fn test_bidi_text() {
+        let font = font();
+        let text = BidiText::new(MIXED, &font, None).unwrap();
+        let runs: Vec<(&str, Script, &[usize])> = text
+            .runs()
+            .iter()
+            .map(|run| {
+                let script_run = run.script_run();
+                (
+                    &MIXED[script_run.range.clone()],
+                    script_run.script,
+                    run.clusters(),
+                )
+            })
+            .collect();
+        assert_eq!(
+            runs,
+            [
+                ("ab ", Script::Latin, &[0, 1, 2][..]),
+                ("سلام", Script::Arabic, &[18, 16, 14, 12][..]),
+                ("שלום ", Script::Hebrew, &[11, 9, 7, 5, 3][..]),
+                (" cd", Script::Latin, &[20, 21, 22][..]),
+            ]
+        );
+
+        let mut offset = 0.0;
+        for run in text.runs() {
+            assert_eq!(run.offset(), offset);
+            assert!(run.width() > 0.0);
+            assert_eq!(run.glyphs.len(), run.clusters().len());
+            offset += run.width();
+        }
+        assert_eq!(text.width(), offset);
+        // The glyphs of the right-to-left runs are reversed.
+        let hebrew = &text.runs()[2].glyphs;
+        assert_ne!(hebrew[0], 0);
+        assert_eq!(hebrew[1..], [0, 0, 0, 0]);
+    }
+
+    #[test]
+    fn test_fill_bidi_text() {
+        let font = font();
+        let text = BidiText::new(MIXED, &font, None).unwrap();
+        let width = text.width().ceil() as i32 + 4;
+        let draw = |f: &mut dyn FnMut(&mut Context<'_>)| {
+            let mut img = Image::new(width, 32, ImageFormat::PRgb32).unwrap();
+            let mut ctx = Context::new(&mut img).unwrap();
+            ctx.clear_all().unwrap();
+            ctx.set_fill_style_rgba32(0xFF00_0000);
+            f(&mut ctx);
+            ctx.end().unwrap();
+            img.to_vec()
+        };
+
+        let filled = draw(&mut |ctx| {
+            ctx.fill_bidi_text(PointI { x: 2, y: 24 }, &font, &text)
+                .unwrap()
+        });
+        assert!(filled.iter().any(|&byte| byte != 0));
+        // Filling the text is the same as filling every run at its offset.
+        let by_run = draw(&mut |ctx| {
+            for run in text.runs() {
+                let dst = PointD {
+                    x: 2.0 + run.offset(),
+                    y: 24.0,
+                };
+                ctx.fill_glyph_run(dst, &font, run.glyph_run()).unwrap();
+            }
+        });
+        assert_eq!(filled, by_run);
+    }
+}
@@ -0,0 +1,708 @@
+//! Color types.
+//!
+//! Blend2D works with two integer color formats, [`Rgba32`] with 8-bit and
+//! [`Rgba64`] with 16-bit components, both stored as non-premultiplied ARGB
+//! in a single integer. [`Rgba`] is a floating-point color that is mainly
+//! useful for computations. [`Color`] can hold any of them and is what most
+//! color-taking functions accept.
+//!
+//! All color types can be parsed from CSS color strings:
+//!
+//! ```
+//! use blend2d::color::{Color, Rgba32};
+//!
+//! let red: Rgba32 = "#f00".parse().unwrap();
+//! assert_eq!(red, Rgba32::rgb(0xFF, 0x00, 0x00));
+//! let color: Color = "rgba(255, 0, 0, 50%)".parse().unwrap();
+//! assert_eq!(Rgba32::from(color), Rgba32::new(0xFF, 0x00, 0x00, 0x80));
+//! assert_eq!("RebeccaPurple".parse(), Ok(Rgba32(0xFF66_3399)));
+//! ```
+use std::{error, fmt, str::FromStr};
+
+/// A 32-bit ARGB color with 8-bit components, stored as `0xAARRGGBB`.
+#[repr(transparent)]
+#[derive(Copy, Clone, Debug, Default, PartialEq, Eq, Hash)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct Rgba32(pub u32);
+
+impl Rgba32 {
+    /// Creates a color from its components.
+    #[inline]
+    pub const fn new(r: u8, g: u8, b: u8, a: u8) -> Self {
+        Rgba32((a as u32) << 24 | (r as u32) << 16 | (g as u32) << 8 | b as u32)
+    }
+
+    /// Creates an opaque color from its components.
+    #[inline]
+    pub const fn rgb(r: u8, g: u8, b: u8) -> Self {
+        Self::new(r, g, b, 0xFF)
+    }
+
+    /// Looks up a CSS named color, ignoring case.
+    pub fn from_name(name: &str) -> Option<Self> {
+        let name = name.to_ascii_lowercase();
+        NAMED_COLORS
+            .binary_search_by(|&(n, _)| n.cmp(&name[..]))
+            .ok()
+            .map(|idx| Rgba32(NAMED_COLORS[idx].1))
+    }
+
+    #[inline]
+    pub fn r(self) -> u8 {
+        (self.0 >> 16) as u8
+    }
+
+    #[inline]
+    pub fn g(self) -> u8 {
+        (self.0 >> 8) as u8
+    }
+
+    #[inline]
+    pub fn b(self) -> u8 {
+        self.0 as u8
+    }
+
+    #[inline]
+    pub fn a(self) -> u8 {
+        (self.0 >> 24) as u8
+    }
+
+    /// Returns this color with its alpha component replaced.
+    #[inline]
+    pub fn with_alpha(self, a: u8) -> Self {
+        Self::new(self.r(), self.g(), self.b(), a)
+    }
+
+    /// Returns true if the alpha component is at its maximum.
+    #[inline]
+    pub fn is_opaque(self) -> bool {
+        self.a() == 0xFF
+    }
+
+    /// Returns true if the alpha component is zero.
+    #[inline]
+    pub fn is_transparent(self) -> bool {
+        self.a() == 0
+    }
+
+    /// Multiplies the color components by alpha.
+    pub fn premultiplied(self) -> Self {
+        let a = u32::from(self.a());
+        let mul = |c: u8| ((u32::from(c) * a + 0x7F) / 0xFF) as u8;
+        Self::new(mul(self.r()), mul(self.g()), mul(self.b()), self.a())
+    }
+
+    /// Divides the color components by alpha, the inverse of
+    /// [`premultiplied`](#method.premultiplied).
+    pub fn unpremultiplied(self) -> Self {
+        let a = u32::from(self.a());
+        if a == 0 {
+            return Rgba32(0);
+        }
+        let div = |c: u8| ((u32::from(c) * 0xFF + a / 2) / a).min(0xFF) as u8;
+        Self::new(div(self.r()), div(self.g()), div(self.b()), self.a())
+    }
+}
+
+/// A 64-bit ARGB color with 16-bit components, stored as
+/// `0xAAAARRRRGGGGBBBB`.
+#[repr(transparent)]
+#[derive(Copy, Clone, Debug, Default, PartialEq, Eq, Hash)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct Rgba64(pub u64);
+
+impl Rgba64 {
+    /// Creates a color from its components.
+    #[inline]
+    pub const fn new(r: u16, g: u16, b: u16, a: u16) -> Self {
+        Rgba64((a as u64) << 48 | (r as u64) << 32 | (g as u64) << 16 | b as u64)
+    }
+
+    /// Creates an opaque color from its components.
+    #[inline]
+    pub const fn rgb(r: u16, g: u16, b: u16) -> Self {
+        Self::new(r, g, b, 0xFFFF)
+    }
+
+    #[inline]
+    pub fn r(self) -> u16 {
+        (self.0 >> 32) as u16
+    }
+
+    #[inline]
+    pub fn g(self) -> u16 {
+        (self.0 >> 16) as u16
+    }
+
+    #[inline]
+    pub fn b(self) -> u16 {
+        self.0 as u16
+    }
+
+    #[inline]
+    pub fn a(self) -> u16 {
+        (self.0 >> 48) as u16
+    }
+
+    /// Returns this color with its alpha component replaced.
+    #[inline]
+    pub fn with_alpha(self, a: u16) -> Self {
+        Self::new(self.r(), self.g(), self.b(), a)
+    }
+
+    /// Returns true if the alpha component is at its maximum.
+    #[inline]
+    pub fn is_opaque(self) -> bool {
+        self.a() == 0xFFFF
+    }
+
+    /// Returns true if the alpha component is zero.
+    #[inline]
+    pub fn is_transparent(self) -> bool {
+        self.a() == 0
+    }
+
+    /// Multiplies the color components by alpha.
+    pub fn premultiplied(self) -> Self {
+        let a = u64::from(self.a());
+        let mul = |c: u16| ((u64::from(c) * a + 0x7FFF) / 0xFFFF) as u16;
+        Self::new(mul(self.r()), mul(self.g()), mul(self.b()), self.a())
+    }
+
+    /// Divides the color components by alpha, the inverse of
+    /// [`premultiplied`](#method.premultiplied).
+    pub fn unpremultiplied(self) -> Self {
+        let a = u64::from(self.a());
+        if a == 0 {
+            return Rgba64(0);
+        }
+        let div = |c: u16| ((u64::from(c) * 0xFFFF + a / 2) / a).min(0xFFFF) as u16;
+        Self::new(div(self.r()), div(self.g()), div(self.b()), self.a())
+    }
+}
+
+/// A floating-point RGBA color. Components are expected to be in the range
+/// `0.0..=1.0`, conversions to the integer formats clamp them.
+#[repr(C)]
+#[derive(Copy, Clone, Debug, Default, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct Rgba {
+    pub r: f32,
+    pub g: f32,
+    pub b: f32,
+    pub a: f32,
+}
+
+impl Rgba {
+    /// Creates a color from its components.
+    #[inline]
+    pub const fn new(r: f32, g: f32, b: f32, a: f32) -> Self {
+        Rgba { r, g, b, a }
+    }
+
+    /// Creates an opaque color from its components.
+    #[inline]
+    pub const fn rgb(r: f32, g: f32, b: f32) -> Self {
+        Rgba { r, g, b, a: 1.0 }
+    }
+
+    /// Returns a copy with every component clamped to `0.0..=1.0`.
+    #[inline]
+    pub fn clamped(self) -> Self {
+        let clamp = |c: f32| c.clamp(0.0, 1.0);
+        Rgba::new(clamp(self.r), clamp(self.g), clamp(self.b), clamp(self.a))
+    }
+
+    /// Multiplies the color components by alpha.
+    #[inline]
+    pub fn premultiplied(self) -> Self {
+        Rgba::new(self.r * self.a, self.g * self.a, self.b * self.a, self.a)
+    }
+
+    /// Divides the color components by alpha, the inverse of
+    /// [`premultiplied`](#method.premultiplied).
+    #[inline]
+    pub fn unpremultiplied(self) -> Self {
+        if self.a == 0.0 {
+            Rgba::default()
+        } else {
+            Rgba::new(self.r / self.a, self.g / self.a, self.b / self.a, self.a)
+        }
+    }
+}
+
+/// A color in any of the supported formats.
+#[derive(Copy, Clone, Debug, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub enum Color {
+    Rgba32(Rgba32),
+    Rgba64(Rgba64),
+    Rgba(Rgba),
+}
+
+impl Color {
+    #[inline]
+    pub fn to_rgba32(self) -> Rgba32 {
+        self.into()
+    }
+
+    #[inline]
+    pub fn to_rgba64(self) -> Rgba64 {
+        self.into()
+    }
+
+    #[inline]
+    pub fn to_rgba(self) -> Rgba {
+        self.into()
+    }
+}
+
+impl Default for Color {
+    #[inline]
+    fn default() -> Self {
+        Color::Rgba32(Rgba32::default())
+    }
+}
+
+impl From<u32> for Rgba32 {
+    #[inline]
+    fn from(val: u32) -> Self {
+        Rgba32(val)
+    }
+}
+
+impl From<Rgba32> for u32 {
+    #[inline]
+    fn from(val: Rgba32) -> Self {
+        val.0
+    }
+}
+
+impl From<u64> for Rgba64 {
+    #[inline]
+    fn from(val: u64) -> Self {
+        Rgba64(val)
+    }
+}
+
+impl From<Rgba64> for u64 {
+    #[inline]
+    fn from(val: Rgba64) -> Self {
+        val.0
+    }
+}
+
+/// Widens every component by repeating its bits, so `0xFF` becomes
+/// `0xFFFF`.
+impl From<Rgba32> for Rgba64 {
+    fn from(val: Rgba32) -> Self {
+        let c = u64::from(val.0);
+        let c = (c | (c << 16)) & 0x0000_FFFF_0000_FFFF;
+        let c = (c | (c << 8)) & 0x00FF_00FF_00FF_00FF;
+        Rgba64(c | (c << 8))
+    }
+}
+
+/// Narrows every component by dropping its low byte, like blend2d does.
+impl From<Rgba64> for Rgba32 {
+    #[inline]
+    fn from(val: Rgba64) -> Self {
+        let c = |c: u16| (c >> 8) as u8;
+        Rgba32::new(c(val.r()), c(val.g()), c(val.b()), c(val.a()))
+    }
+}
+
+impl From<Rgba32> for Rgba {
+    #[inline]
+    fn from(val: Rgba32) -> Self {
+        let c = |c: u8| f32::from(c) / 255.0;
+        Rgba::new(c(val.r()), c(val.g()), c(val.b()), c(val.a()))
+    }
+}
+
+impl From<Rgba> for Rgba32 {
+    #[inline]
+    fn from(val: Rgba) -> Self {
+        let val = val.clamped();
+        let c = |c: f32| (c * 255.0).round() as u8;
+        Rgba32::new(c(val.r), c(val.g), c(val.b), c(val.a))
+    }
+}
+
+impl From<Rgba64> for Rgba {
+    #[inline]
+    fn from(val: Rgba64) -> Self {
+        let c = |c: u16| f32::from(c) / 65535.0;
+        Rgba::new(c(val.r()), c(val.g()), c(val.b()), c(val.a()))
+    }
+}
+
+impl From<Rgba> for Rgba64 {
+    #[inline]
+    fn from(val: Rgba) -> Self {
+        let val = val.clamped();
+        let c = |c: f32| (c * 65535.0).round() as u16;
+        Rgba64::new(c(val.r), c(val.g), c(val.b), c(val.a))
+    }
+}
+
+/// Interprets the value as a 32-bit `0xAARRGGBB` color.
+impl From<u32> for Color {
+    #[inline]
+    fn from(val: u32) -> Self {
+        Color::Rgba32(Rgba32(val))
+    }
+}
+
+impl From<Rgba32> for Color {
+    #[inline]
+    fn from(val: Rgba32) -> Self {
+        Color::Rgba32(val)
+    }
+}
+
+impl From<Rgba64> for Color {
+    #[inline]
+    fn from(val: Rgba64) -> Self {
+        Color::Rgba64(val)
+    }
+}
+
+impl From<Rgba> for Color {
+    #[inline]
+    fn from(val: Rgba) -> Self {
+        Color::Rgba(val)
+    }
+}
+
+macro_rules! impl_from_color {
+    ($($ty:ident),*) => {
+        $(
+            impl From<Color> for $ty {
+                #[inline]
+                fn from(val: Color) -> Self {
+                    match val {
+                        Color::Rgba32(c) => c.into(),
+                        Color::Rgba64(c) => c.into(),
+                        Color::Rgba(c) => c.into(),
+                    }
+                }
+            }
+
+            impl FromStr for $ty {
+                type Err = ParseColorError;
+
+                #[inline]
+                fn from_str(s: &str) -> Result<Self, Self::Err> {
+                    s.parse::<Color>().map(Into::into)
+                }
+            }
+        )*
+    };
+}
+
+impl_from_color!(Rgba32, Rgba64, Rgba);
+
+/// An error returned when parsing a color string fails.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct ParseColorError(());
+
+impl error::Error for ParseColorError {}
+impl fmt::Display for ParseColorError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str("invalid color syntax")
+    }
+}
+
+/// Parses a CSS color.
+///
+/// Supported are hex colors (`#rgb`, `#rgba`, `#rrggbb` and `#rrggbbaa`),
+/// the `rgb()`, `rgba()`, `hsl()` and `hsla()` functions in both their comma
+/// and space separated forms, the named colors and `transparent`. Hex and
+/// named colors result in [`Color::Rgba32`], functions in [`Color::Rgba`].
+impl FromStr for Color {
+    type Err = ParseColorError;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        parse_color(s.trim()).ok_or(ParseColorError(()))
+    }
+}
+
+fn parse_color(s: &str) -> Option<Color> {
+    if let Some(hex) = s.strip_prefix('#') {
+        parse_hex(hex).map(Color::Rgba32)
+    } else if let Some(open) = s.find('(') {
+        if !s.ends_with(')') {
+            return None;
+        }
+        let args = parse_args(&s[open + 1..s.len() - 1])?;
+        match &s[..open].trim_end().to_ascii_lowercase()[..] {
+            "rgb" | "rgba" => parse_rgb(&args),
+            "hsl" | "hsla" => parse_hsl(&args),
+            _ => None,
+        }
+        .map(|c| Color::Rgba(c.clamped()))
+    } else if s.eq_ignore_ascii_case("transparent") {
+        Some(Color::Rgba32(Rgba32(0)))
+    } else {
+        Rgba32::from_name(s).map(Color::Rgba32)
+    }
+}
+
+fn parse_hex(hex: &str) -> Option<Rgba32> {
+    if !hex.bytes().all(|b| b.is_ascii_hexdigit()) {
+        return None;
+    }
+    let val = u32::from_str_radix(hex, 16).ok()?;
+    // Expands a 4-bit component to 8 bits.
+    let nibble = |shift: u32| ((val >> shift) & 0xF) as u8 * 0x11;
+    match hex.len() {
+        3 => Some(Rgba32::rgb(nibble(8), nibble(4), nibble(0))),
+        4 => Some(Rgba32::new(nibble(12), nibble(8), nibble(4), nibble(0))),
+        6 => Some(Rgba32(0xFF00_0000 | val)),
+        8 => Some(Rgba32(val.rotate_right(8))),
+        _ => None,
+    }
+}
+
+/// Splits the arguments of a color function into its three components and
+/// the optional alpha.
+fn parse_args(args: &str) -> Option<([&str; 3], Option<&str>)> {
+    let parts: Vec<&str> = if args.contains(',') {
+        args.split(',').map(str::trim).collect()
+    } else {
+        let mut parts = args.split('/');
+        let mut components: Vec<&str> = parts.next()?.split_whitespace().collect();
+        if let Some(alpha) = parts.next() {
+            components.push(alpha.trim());
+        }
+        if parts.next().is_some() {
+            return None;
+        }
+        components
+    };
+    match parts[..] {
+        [a, b, c] => Some(([a, b, c], None)),
+        [a, b, c, alpha] => Some(([a, b, c], Some(alpha))),
+        _ => None,
+    }
+}
+
+/// Parses a number or a percentage, mapping percentages to `0.0..=1.0` and
+/// numbers by dividing them by `scale`.
+fn parse_number(s: &str, scale: f32) -> Option<f32> {
+    let val = if let Some(percent) = s.strip_suffix('%') {
+        percent.parse::<f32>().ok()? / 100.0
+    } else {
+        s.parse::<f32>().ok()? / scale
+    };
+    if val.is_finite() {
+        Some(val)
+    } else {
+        None
+    }
+}
+
+fn parse_alpha(alpha: Option<&str>) -> Option<f32> {
+    alpha.map_or(Some(1.0), |a| parse_number(a, 1.0))
+}
+
+fn parse_rgb(&(components, alpha): &([&str; 3], Option<&str>)) -> Option<Rgba> {
+    Some(Rgba::new(
+        parse_number(components[0], 255.0)?,
+        parse_number(components[1], 255.0)?,
+        parse_number(components[2], 255.0)?,
+        parse_alpha(alpha)?,
+    ))
+}
+
+/// Parses an angle in degrees, radians, gradians or turns into turns.
+fn parse_hue(s: &str) -> Option<f32> {
+    let s = s.to_ascii_lowercase();
+    let (num, turn) = [
+        ("deg", 360.0),
+        ("grad", 400.0),
+        ("rad", std::f32::consts::PI * 2.0),
+        ("turn", 1.0),
+    ]
+    .iter()
+    .find(|(unit, _)| s.ends_with(unit))
+    .map_or((&s[..], 360.0), |&(unit, turn)| {
+        (&s[..s.len() - unit.len()], turn)
+    });
+    let hue = num.parse::<f32>().ok()? / turn;
+    if hue.is_finite() {
+        Some(hue - hue.floor())
+    } else {
+        None
+    }
+}
+
+fn parse_hsl(&(components, alpha): &([&str; 3], Option<&str>)) -> Option<Rgba> {
+    let h = parse_hue(components[0])?;
+    let s = parse_number(components[1], 100.0)?.clamp(0.0, 1.0);
+    let l = parse_number(components[2], 100.0)?.clamp(0.0, 1.0);
+    let a = parse_alpha(alpha)?;
+
+    let t2 = if l <= 0.5 {
+        l * (s + 1.0)
+    } else {
+        l + s - l * s
+    };
+    let t1 = l * 2.0 - t2;
+    // Works on hues scaled to `0.0..6.0` to keep the sector bounds exact.
+    let hue_to_rgb = |h: f32| {
+        let h = h.rem_euclid(6.0);
+        if h < 1.0 {
+            t1 + (t2 - t1) * h
+        } else if h < 3.0 {
+            t2
+        } else if h < 4.0 {
+            t1 + (t2 - t1) * (4.0 - h)
+        } else {
+            t1
+        }
+    };
+    let h = h * 6.0;
+    Some(Rgba::new(
+        hue_to_rgb(h + 2.0),
+        hue_to_rgb(h),
+        hue_to_rgb(h - 2.0),
+        a,
+    ))
+}
+
+/// The CSS named colors, sorted by name.
+#[rustfmt::skip]
+static NAMED_COLORS: [(&str, u32); 148] = [
+    ("aliceblue", 0xFFF0_F8FF), ("antiquewhite", 0xFFFA_EBD7), ("aqua", 0xFF00_FFFF),
+    ("aquamarine", 0xFF7F_FFD4), ("azure", 0xFFF0_FFFF), ("beige", 0xFFF5_F5DC),
+    ("bisque", 0xFFFF_E4C4), ("black", 0xFF00_0000), ("blanchedalmond", 0xFFFF_EBCD),
+    ("blue", 0xFF00_00FF), ("blueviolet", 0xFF8A_2BE2), ("brown", 0xFFA5_2A2A),
+    ("burlywood", 0xFFDE_B887), ("cadetblue", 0xFF5F_9EA0), ("chartreuse", 0xFF7F_FF00),
+    ("chocolate", 0xFFD2_691E), ("coral", 0xFFFF_7F50), ("cornflowerblue", 0xFF64_95ED),
+    ("cornsilk", 0xFFFF_F8DC), ("crimson", 0xFFDC_143C), ("cyan", 0xFF00_FFFF),
+    ("darkblue", 0xFF00_008B), ("darkcyan", 0xFF00_8B8B), ("darkgoldenrod", 0xFFB8_860B),
+    ("darkgray", 0xFFA9_A9A9), ("darkgreen", 0xFF00_6400), ("darkgrey", 0xFFA9_A9A9),
+    ("darkkhaki", 0xFFBD_B76B), ("darkmagenta", 0xFF8B_008B), ("darkolivegreen", 0xFF55_6B2F),
+    ("darkorange", 0xFFFF_8C00), ("darkorchid", 0xFF99_32CC), ("darkred", 0xFF8B_0000),
+    ("darksalmon", 0xFFE9_967A), ("darkseagreen", 0xFF8F_BC8F), ("darkslateblue", 0xFF48_3D8B),
+    ("darkslategray", 0xFF2F_4F4F), ("darkslategrey", 0xFF2F_4F4F), ("darkturquoise", 0xFF00_CED1),
+    ("darkviolet", 0xFF94_00D3), ("deeppink", 0xFFFF_1493), ("deepskyblue", 0xFF00_BFFF),
+    ("dimgray", 0xFF69_6969), ("dimgrey", 0xFF69_6969), ("dodgerblue", 0xFF1E_90FF),
+    ("firebrick", 0xFFB2_2222), ("floralwhite", 0xFFFF_FAF0), ("forestgreen", 0xFF22_8B22),
+    ("fuchsia", 0xFFFF_00FF), ("gainsboro", 0xFFDC_DCDC), ("ghostwhite", 0xFFF8_F8FF),
+    ("gold", 0xFFFF_D700), ("goldenrod", 0xFFDA_A520), ("gray", 0xFF80_8080),
+    ("green", 0xFF00_8000), ("greenyellow", 0xFFAD_FF2F), ("grey", 0xFF80_8080),
+    ("honeydew", 0xFFF0_FFF0), ("hotpink", 0xFFFF_69B4), ("indianred", 0xFFCD_5C5C),
+    ("indigo", 0xFF4B_0082), ("ivory", 0xFFFF_FFF0), ("khaki", 0xFFF0_E68C),
+    ("lavender", 0xFFE6_E6FA), ("lavenderblush", 0xFFFF_F0F5), ("lawngreen", 0xFF7C_FC00),
+    ("lemonchiffon", 0xFFFF_FACD), ("lightblue", 0xFFAD_D8E6), ("lightcoral", 0xFFF0_8080),
+    ("lightcyan", 0xFFE0_FFFF), ("lightgoldenrodyellow", 0xFFFA_FAD2), ("lightgray", 0xFFD3_D3D3),
+    ("lightgreen", 0xFF90_EE90), ("lightgrey", 0xFFD3_D3D3), ("lightpink", 0xFFFF_B6C1),
+    ("lightsalmon", 0xFFFF_A07A), ("lightseagreen", 0xFF20_B2AA), ("lightskyblue", 0xFF87_CEFA),
+    ("lightslategray", 0xFF77_8899), ("lightslategrey", 0xFF77_8899), ("lightsteelblue", 0xFFB0_C4DE),
+    ("lightyellow", 0xFFFF_FFE0), ("lime", 0xFF00_FF00), ("limegreen", 0xFF32_CD32),
+    ("linen", 0xFFFA_F0E6), ("magenta", 0xFFFF_00FF), ("maroon", 0xFF80_0000),
+    ("mediumaquamarine", 0xFF66_CDAA), ("mediumblue", 0xFF00_00CD), ("mediumorchid", 0xFFBA_55D3),
+    ("mediumpurple", 0xFF93_70DB), ("mediumseagreen", 0xFF3C_B371), ("mediumslateblue", 0xFF7B_68EE),
+    ("mediumspringgreen", 0xFF00_FA9A), ("mediumturquoise", 0xFF48_D1CC), ("mediumvioletred", 0xFFC7_1585),
+    ("midnightblue", 0xFF19_1970), ("mintcream", 0xFFF5_FFFA), ("mistyrose", 0xFFFF_E4E1),
+    ("moccasin", 0xFFFF_E4B5), ("navajowhite", 0xFFFF_DEAD), ("navy", 0xFF00_0080),
+    ("oldlace", 0xFFFD_F5E6), ("olive", 0xFF80_8000), ("olivedrab", 0xFF6B_8E23),
+    ("orange", 0xFFFF_A500), ("orangered", 0xFFFF_4500), ("orchid", 0xFFDA_70D6),
+    ("palegoldenrod", 0xFFEE_E8AA), ("palegreen", 0xFF98_FB98), ("paleturquoise", 0xFFAF_EEEE),
+    ("palevioletred", 0xFFDB_7093), ("papayawhip", 0xFFFF_EFD5), ("peachpuff", 0xFFFF_DAB9),
+    ("peru", 0xFFCD_853F), ("pink", 0xFFFF_C0CB), ("plum", 0xFFDD_A0DD),
+    ("powderblue", 0xFFB0_E0E6), ("purple", 0xFF80_0080), ("rebeccapurple", 0xFF66_3399),
+    ("red", 0xFFFF_0000), ("rosybrown", 0xFFBC_8F8F), ("royalblue", 0xFF41_69E1),
+    ("saddlebrown", 0xFF8B_4513), ("salmon", 0xFFFA_8072), ("sandybrown", 0xFFF4_A460),
+    ("seagreen", 0xFF2E_8B57), ("seashell", 0xFFFF_F5EE), ("sienna", 0xFFA0_522D),
+    ("silver", 0xFFC0_C0C0), ("skyblue", 0xFF87_CEEB), ("slateblue", 0xFF6A_5ACD),
+    ("slategray", 0xFF70_8090), ("slategrey", 0xFF70_8090), ("snow", 0xFFFF_FAFA),
+    ("springgreen", 0xFF00_FF7F), ("steelblue", 0xFF46_82B4), ("tan", 0xFFD2_B48C),
+    ("teal", 0xFF00_8080), ("thistle", 0xFFD8_BFD8), ("tomato", 0xFFFF_6347),
+    ("turquoise", 0xFF40_E0D0), ("violet", 0xFFEE_82EE), ("wheat", 0xFFF5_DEB3),
+    ("white", 0xFFFF_FFFF), ("whitesmoke", 0xFFF5_F5F5), ("yellow", 0xFFFF_FF00),
+    ("yellowgreen", 0xFF9A_CD32),
+];
+
+#[cfg(test)]
+mod test_color {
+    use crate::color::{Color, Rgba, Rgba32, Rgba64, NAMED_COLORS};
+
+    #[test]
+    fn test_named_colors_sorted() {
+        assert!(NAMED_COLORS.windows(2).all(|w| w[0].0 < w[1].0));
+        assert_eq!(
+            Rgba32::from_name("CornflowerBlue"),
+            Some(Rgba32(0xFF64_95ED))
+        );
+        assert_eq!(Rgba32::from_name("notacolor"), None);
+    }
+
+    #[test]
+    fn test_parse_hex() {
+        assert_eq!("#1a2B3c".parse(), Ok(Rgba32(0xFF1A_2B3C)));
+        assert_eq!("#1a2b3c80".parse(), Ok(Rgba32(0x801A_2B3C)));
+        assert_eq!("#abc".parse(), Ok(Rgba32(0xFFAA_BBCC)));
+        assert_eq!("#abcd".parse(), Ok(Rgba32(0xDDAA_BBCC)));
+        assert!("#abcde".parse::<Rgba32>().is_err());
+        assert!("#+bc".parse::<Rgba32>().is_err());
+        assert!("#".parse::<Rgba32>().is_err());
+    }
+
+    #[test]
+    fn test_parse_functions() {
+        let parse = |s: &str| s.parse::<Rgba32>();
+        assert_eq!(parse("rgb(255, 128, 0)"), Ok(Rgba32(0xFFFF_8000)));
+        assert_eq!(parse("rgba(255, 128, 0, 0.5)"), Ok(Rgba32(0x80FF_8000)));
+        assert_eq!(parse("rgb(100% 50% 0% / 25%)"), Ok(Rgba32(0x40FF_8000)));
+        assert_eq!(parse("RGB( 0 0 0 )"), Ok(Rgba32(0xFF00_0000)));
+        assert_eq!(parse("hsl(120, 100%, 50%)"), Ok(Rgba32(0xFF00_FF00)));
+        assert_eq!(parse("hsl(0.5turn 100% 25%)"), Ok(Rgba32(0xFF00_8080)));
+        assert_eq!(
+            parse("hsla(-120deg, 100%, 50%, 1)"),
+            Ok(Rgba32(0xFF00_00FF))
+        );
+        assert_eq!(parse("transparent"), Ok(Rgba32(0)));
+        assert!(parse("rgb(1, 2)").is_err());
+        assert!(parse("rgb(1, 2, 3").is_err());
+        assert!(parse("cmyk(1, 2, 3, 4)").is_err());
+        assert!(parse("rgb(1 2 3 / 4 / 5)").is_err());
+    }
+
+    #[test]
+    fn test_conversions() {
+        let c = Rgba32(0x8012_34AB);
+        assert_eq!(Rgba64::from(c), Rgba64(0x8080_1212_3434_ABAB));
+        assert_eq!(Rgba32::from(Rgba64::from(c)), c);
+        assert_eq!(Rgba32::from(Rgba::from(c)), c);
+        assert_eq!(Rgba64::from(Rgba::from(Rgba64::from(c))), Rgba64::from(c));
+        assert_eq!(
+            Rgba32::from(Rgba::new(2.0, -1.0, 0.5, 1.0)),
+            Rgba32(0xFFFF_0080)
+        );
+        assert_eq!(
+            Color::from(0xFF00_FF00).to_rgba64(),
+            Rgba64(0xFFFF_0000_FFFF_0000)
+        );
+    }
+
+    #[test]
+    fn test_premultiply() {
+        let c = Rgba32::new(0xFF, 0x80, 0x00, 0x80);
+        assert_eq!(c.premultiplied(), Rgba32::new(0x80, 0x40, 0x00, 0x80));
+        assert_eq!(
+            c.premultiplied().unpremultiplied(),
+            Rgba32::new(0xFF, 0x80, 0x00, 0x80)
+        );
+        assert_eq!(Rgba32(0x00FF_FFFF).premultiplied(), Rgba32(0));
+        assert_eq!(Rgba32(0x00FF_FFFF).unpremultiplied(), Rgba32(0));
+        let c = Rgba64::new(0xFFFF, 0x8000, 0, 0x8000);
+        assert_eq!(c.premultiplied(), Rgba64::new(0x8000, 0x4000, 0, 0x8000));
+        let c = Rgba::new(1.0, 0.5, 0.0, 0.5);
+        assert_eq!(c.premultiplied(), Rgba::new(0.5, 0.25, 0.0, 0.5));
+        assert_eq!(c.premultiplied().unpremultiplied(), c);
+    }
+}
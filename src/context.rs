@@ -1,23 +1,30 @@
 use bitflags::bitflags;
 
-use std::{fmt, ptr};
+use std::ffi::c_void;
+use std::marker::PhantomData;
+use std::{fmt, ptr, slice};
 
 use crate::array::Array;
-use crate::error::{errcode_to_result, expect_mem_err, Result};
+use crate::bidi::BidiText;
+use crate::color::{Color, Rgba32, Rgba64};
+use crate::error::{errcode_to_result, expect_mem_err, Error, Result};
 use crate::font::Font;
 use crate::font_defs::GlyphRun;
+use crate::glyph_buffer::{GlyphBuffer, GlyphId};
 use crate::geometry::{
-    Arc, BoxD, Chord, Circle, Ellipse, FillRule, GeoViewArray, Geometry, Line, Pie, Point, Rect,
-    RectD, RectI, RoundRect, SizeD, Triangle,
+    Arc, BoxD, Chord, Circle, Ellipse, FillRule, GeoViewArray, Geometry, GeometryDirection, Line,
+    Pie, Point, PointD, Rect, RectD, RectI, RoundRect, SizeD, Triangle,
 };
-use crate::gradient::{Gradient, GradientType};
-use crate::image::Image;
+use crate::gradient::{DynamicGradient, Gradient, GradientType, Linear};
+use crate::image::{read_pixel, Image, ImageFormat, ImageViewMut};
 use crate::matrix::{Matrix2D, Matrix2DOp, MatrixTransform};
 use crate::path::{
     ApproximationOptions, FlattenMode, Path, StrokeCap, StrokeCapPosition, StrokeJoin,
     StrokeOptions, StrokeTransformOrder,
 };
 use crate::pattern::Pattern;
+use crate::style::Style;
+use crate::text_layout::TextLayout;
 use crate::variant::{BlVariantCore, BlVariantImpl, WrappedBlCore};
 use crate::StyleType;
 use crate::util::cast_ref;
@@ -158,12 +165,103 @@ pub struct ContextHints {
     pub pattern_quality: u8,
 }
 
-#[repr(transparent)]
-pub struct Context {
+mod private {
+    use crate::image::{Image, ImageViewMut};
+    use crate::variant::WrappedBlCore;
+
+    /// The target is only reachable as a raw pointer from inside the crate,
+    /// handing out the image of an [`ImageViewMut`] would let it outlive the
+    /// buffer it borrows.
+    pub trait Sealed {
+        const OWNS_PIXELS: bool;
+        fn target_core(&mut self) -> *mut ffi::BLImageCore;
+    }
+
+    impl Sealed for Image {
+        const OWNS_PIXELS: bool = true;
+        #[inline]
+        fn target_core(&mut self) -> *mut ffi::BLImageCore {
+            self.core_mut()
+        }
+    }
+
+    impl Sealed for ImageViewMut<'_> {
+        const OWNS_PIXELS: bool = false;
+        #[inline]
+        fn target_core(&mut self) -> *mut ffi::BLImageCore {
+            self.image.core_mut()
+        }
+    }
+}
+
+/// A target a [`Context`] can render to, either an [`Image`] or an
+/// [`ImageViewMut`] of an external buffer.
+///
+/// The image behind an [`ImageViewMut`] can't be taken out through this
+/// trait:
+///
+/// ```compile_fail
+/// use blend2d::context::RenderTarget;
+/// use blend2d::image::{Image, ImageFormat, ImageViewMut};
+///
+/// let mut buffer = vec![0u8; 4 * 4 * 4];
+/// let mut view = ImageViewMut::new(&mut buffer, 4, 4, ImageFormat::PRgb32, 16).unwrap();
+/// let image: Image = view.image_mut().clone();
+/// ```
+///
+/// [`ImageViewMut`]: ../image/struct.ImageViewMut.html
+pub trait RenderTarget: private::Sealed {}
+
+impl RenderTarget for Image {}
+
+impl RenderTarget for ImageViewMut<'_> {}
+
+/// A rendering context that renders to an [`Image`] or an [`ImageViewMut`].
+///
+/// The target stays mutably borrowed for as long as the context is
+/// attached to it. This is what allows the context to render asynchronously
+/// on multiple threads, see [`ContextCreateInfo`] and
+/// [`flush`](#method.flush).
+///
+/// Unlike most other types a context cannot be shared between threads:
+///
+/// ```compile_fail
+/// fn assert_sync<T: Sync>() {}
+/// assert_sync::<blend2d::context::Context<'static>>();
+/// ```
+///
+/// nor be sent to another thread:
+///
+/// ```compile_fail
+/// fn assert_send<T: Send>() {}
+/// assert_send::<blend2d::context::Context<'static>>();
+/// ```
+///
+/// [`ImageViewMut`]: ../image/struct.ImageViewMut.html
+#[repr(C)]
+pub struct Context<'a> {
     core: ffi::BLContextCore,
+    target: *mut ffi::BLImageCore,
+    mask_clips: Vec<MaskClip>,
+    _pd: PhantomData<&'a mut Image>,
 }
 
-impl fmt::Debug for Context {
+/// A clip to a coverage mask.
+///
+/// Blend2D only clips to rectangles, so the context keeps rendering to the
+/// target and, once the clip is removed again, blends the pixels rendered in
+/// the meantime with the ones the target had when the clip was set, weighted
+/// by the coverage of every pixel.
+struct MaskClip {
+    /// The saved state count of the context when the clip was set.
+    depth: usize,
+    /// One coverage byte per pixel of the target, row by row.
+    coverage: Vec<u8>,
+    /// The pixel rows of the target when the clip was set, without padding.
+    backdrop: Vec<u8>,
+}
+
+impl fmt::Debug for Context<'_> {
     fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
         f.debug_struct("Context")
             .field("target_size", &self.target_size())
@@ -174,46 +272,72 @@ impl fmt::Debug for Context {
     }
 }
 
-unsafe impl WrappedBlCore for Context {
+unsafe impl WrappedBlCore for Context<'_> {
     type Core = ffi::BLContextCore;
     const IMPL_TYPE_INDEX: usize = crate::variant::ImplType::Context as usize;
 
     #[inline]
     fn from_core(core: Self::Core) -> Self {
-        Context { core }
+        Context {
+            core,
+            target: ptr::null_mut(),
+            mask_clips: Vec::new(),
+            _pd: PhantomData,
+        }
     }
 }
 
-impl Context {
-    /// Creates a new context that renders to the given [`Image`].
+impl<'a> Context<'a> {
+    /// Creates a new context that renders to the given [`RenderTarget`].
     #[inline]
-    pub fn new(target: &mut Image) -> Result<Context> {
+    pub fn new<T: RenderTarget>(target: &'a mut T) -> Result<Context<'a>> {
         Self::new_with_options(target, None)
     }
 
     /// Creates a new context with optional creation info that renders to the
-    /// given [`Image`].
-    pub fn new_with_options<C>(target: &mut Image, info: C) -> Result<Context>
+    /// given [`RenderTarget`].
+    ///
+    /// Passing a [`ContextCreateInfo`] with a `thread_count` greater than zero
+    /// creates an asynchronous context that distributes the rendering to
+    /// worker threads. Its render calls only enqueue commands, so the target
+    /// is guaranteed to be up to date only after a synchronous
+    /// [`flush`](#method.flush) or once the context has been ended.
+    ///
+    /// Asynchronous contexts can only render to an [`Image`], as a leaked
+    /// context could otherwise keep writing to the buffer of an
+    /// [`ImageViewMut`] after it has been released. Requesting one for a view
+    /// returns [`Error::InvalidValue`].
+    ///
+    /// [`ImageViewMut`]: ../image/struct.ImageViewMut.html
+    pub fn new_with_options<T, C>(target: &'a mut T, info: C) -> Result<Context<'a>>
     where
+        T: RenderTarget,
         C: Into<Option<ContextCreateInfo>>,
     {
+        let info = info.into();
+        if !T::OWNS_PIXELS && matches!(info, Some(info) if info.thread_count > 0) {
+            return Err(Error::InvalidValue);
+        }
         unsafe {
             let mut this = Context::from_core(*Self::none());
-            let info = info.into().map(|info| ffi::BLContextCreateInfo {
+            let info = info.map(|info| ffi::BLContextCreateInfo {
                 flags: info.flags.bits(),
                 threadCount: info.thread_count,
                 cpuFeatures: info.cpu_features,
                 reserved: [0; 5],
             });
+            this.target = target.target_core();
             errcode_to_result(ffi::blContextInitAs(
                 this.core_mut(),
-                target.core_mut(),
+                this.target,
                 info.as_ref().map_or(ptr::null(), |ptr| ptr as *const _),
             ))
             .map(|_| this)
         }
     }
+}
 
+impl Context<'_> {
     /// Retrieves the target [`Image`]'s dimensions.
     #[inline]
     pub fn target_size(&self) -> SizeD {
@@ -246,13 +370,18 @@ impl Context {
     /// context without calling this, but this might change in the future.
     #[inline]
     pub fn end(mut self) -> Result<()> {
+        errcode_to_result(self.pop_mask_clips(0))?;
         unsafe { errcode_to_result(ffi::blContextEnd(self.core_mut())) }
     }
 
-    /*#[inline]
-    pub fn flush(&mut self, flags: ContextFlushFlags) {
-        unsafe { ffi::blContextFlush(self.core_mut(), flags.bits()) };
-    }*/
+    /// Flushes the context by submitting all pending render commands.
+    ///
+    /// With [`ContextFlushFlags::FLUSH_SYNC`] this also waits until the
+    /// rendering of all submitted commands has finished.
+    #[inline]
+    pub fn flush(&mut self, flags: ContextFlushFlags) -> Result<()> {
+        unsafe { errcode_to_result(ffi::blContextFlush(self.core_mut(), flags.bits())) }
+    }
 
     /// Returns the number of saved states in the context (0 means no saved
     /// states).
@@ -284,7 +413,8 @@ impl Context {
     /// Restores the top-most saved context-state.
     #[inline]
     pub fn restore(&mut self) -> Result<()> {
-        unsafe { errcode_to_result(ffi::blContextRestore(self.core_mut(), ptr::null_mut())) }
+        unsafe { errcode_to_result(ffi::blContextRestore(self.core_mut(), ptr::null_mut()))? };
+        errcode_to_result(self.pop_mask_clips(self.saved_state_count() + 1))
     }
 
     /// Restores to the point that matches the given `cookie`.
@@ -294,20 +424,22 @@ impl Context {
             errcode_to_result(ffi::blContextRestore(
                 self.core_mut(),
                 &cookie as *const _ as *const _,
-            ))
-        }
+            ))?
+        };
+        errcode_to_result(self.pop_mask_clips(self.saved_state_count() + 1))
     }
 
     /// Runs a given closure while preserving the current context-state.
     /// This function basically saves the current context-state, executes the
-    /// given closure and then restores it again.
+    /// given closure and then restores it again, even if the closure fails.
     pub fn with_pushed_context<F>(&mut self, f: F) -> Result<()>
     where
         F: FnOnce(&mut Self) -> Result<()>,
     {
         let cookie = self.save_cookie();
-        f(self)?;
-        self.restore_cookie(cookie)
+        let result = f(self);
+        let restored = self.restore_cookie(cookie);
+        result.and(restored)
     }
 
     #[inline]
@@ -400,8 +532,69 @@ impl Context {
     }
 }
 
-// FIXME? make functions generic over a Stroke/FillStyle trait?
-impl Context {
+type SetStyleFn = unsafe extern "C" fn(*mut ffi::BLContextCore, *const c_void) -> ffi::BLResult;
+type SetStyleRgba64Fn = unsafe extern "C" fn(*mut ffi::BLContextCore, u64) -> ffi::BLResult;
+type GetStyleFn = unsafe extern "C" fn(*const ffi::BLContextCore, *mut c_void) -> ffi::BLResult;
+type GetStyleRgba64Fn = unsafe extern "C" fn(*const ffi::BLContextCore, *mut u64) -> ffi::BLResult;
+
+impl Context<'_> {
+    fn set_style(
+        &mut self,
+        style: &Style,
+        set_style: SetStyleFn,
+        set_rgba64: SetStyleRgba64Fn,
+    ) -> Result<()> {
+        unsafe {
+            errcode_to_result(match style {
+                // blend2d can't reset a context to render nothing.
+                Style::None => set_rgba64(self.core_mut(), 0),
+                Style::Solid(color) => set_rgba64(self.core_mut(), color.0),
+                Style::Gradient(gradient) => set_style(
+                    self.core_mut(),
+                    gradient.as_variant_core() as *const _ as *const _,
+                ),
+                Style::Pattern(pattern) => set_style(
+                    self.core_mut(),
+                    pattern.core().as_variant_core() as *const _ as *const _,
+                ),
+            })
+        }
+    }
+
+    fn style(
+        &self,
+        style_type: StyleType,
+        get_style: GetStyleFn,
+        get_rgba64: GetStyleRgba64Fn,
+    ) -> Style {
+        unsafe {
+            match style_type {
+                StyleType::None => Style::None,
+                StyleType::Solid => {
+                    let mut color = 0;
+                    expect_mem_err(get_rgba64(self.core(), &mut color));
+                    Style::Solid(Rgba64(color))
+                },
+                StyleType::Gradient => {
+                    let mut core = *Gradient::<Linear>::none();
+                    expect_mem_err(get_style(self.core(), &mut core as *mut _ as *mut _));
+                    Style::Gradient(DynamicGradient::from_core(core))
+                },
+                StyleType::Pattern => {
+                    let mut pattern = Pattern::from_core(*Pattern::none());
+                    expect_mem_err(get_style(
+                        self.core(),
+                        pattern.core_mut() as *mut _ as *mut _,
+                    ));
+                    Style::Pattern(pattern)
+                },
+            }
+        }
+    }
+}
+
+/// Fill Style Operations
+impl Context<'_> {
     #[inline]
     pub fn fill_rule(&self) -> FillRule {
         u32::from(self.state().fillRule).into()
@@ -428,6 +621,30 @@ impl Context {
         unsafe { ffi::blContextSetFillAlpha(self.core_mut(), alpha) };
     }
 
+    /// Sets the fill style.
+    ///
+    /// [`Style::None`] is set as a transparent solid color, which renders
+    /// nothing with the default [`CompOp::SrcOver`], but still affects the
+    /// target with composition operators like [`CompOp::SrcCopy`].
+    #[inline]
+    pub fn set_fill_style<S: Into<Style>>(&mut self, style: S) -> Result<()> {
+        self.set_style(
+            &style.into(),
+            ffi::blContextSetFillStyle,
+            ffi::blContextSetFillStyleRgba64,
+        )
+    }
+
+    /// Returns the current fill style.
+    #[inline]
+    pub fn fill_style(&self) -> Style {
+        self.style(
+            self.fill_style_type(),
+            ffi::blContextGetFillStyle,
+            ffi::blContextGetFillStyleRgba64,
+        )
+    }
+
     #[inline]
     pub fn set_fill_style_gradient<T: GradientType>(&mut self, gradient: &Gradient<T>) {
         unsafe {
@@ -449,33 +666,36 @@ impl Context {
     }
 
     #[inline]
-    pub fn set_fill_style_rgba32(&mut self, color: u32) {
-        unsafe { ffi::blContextSetFillStyleRgba32(self.core_mut(), color) };
+    pub fn set_fill_style_rgba32<C: Into<Rgba32>>(&mut self, color: C) {
+        unsafe { ffi::blContextSetFillStyleRgba32(self.core_mut(), color.into().0) };
     }
 
     #[inline]
-    pub fn set_fill_style_rgba64(&mut self, color: u64) {
-        unsafe { ffi::blContextSetFillStyleRgba64(self.core_mut(), color) };
+    pub fn set_fill_style_rgba64<C: Into<Rgba64>>(&mut self, color: C) {
+        unsafe { ffi::blContextSetFillStyleRgba64(self.core_mut(), color.into().0) };
     }
 
     #[inline]
-    pub fn get_fill_style_rgba32(&self) -> Result<u32> {
+    pub fn get_fill_style_rgba32(&self) -> Result<Rgba32> {
         unsafe {
             let mut out = 0;
-            errcode_to_result(ffi::blContextGetFillStyleRgba32(self.core(), &mut out)).map(|_| out)
+            errcode_to_result(ffi::blContextGetFillStyleRgba32(self.core(), &mut out))
+                .map(|_| Rgba32(out))
         }
     }
 
     #[inline]
-    pub fn get_fill_style_rgba64(&self) -> Result<u64> {
+    pub fn get_fill_style_rgba64(&self) -> Result<Rgba64> {
         unsafe {
             let mut out = 0;
-            errcode_to_result(ffi::blContextGetFillStyleRgba64(self.core(), &mut out)).map(|_| out)
+            errcode_to_result(ffi::blContextGetFillStyleRgba64(self.core(), &mut out))
+                .map(|_| Rgba64(out))
         }
     }
 }
 
-impl Context {
+/// Stroke Style Operations
+impl Context<'_> {
     #[inline]
     pub fn stroke_alpha(&self) -> f64 {
         self.state().styleAlpha[ContextOpType::Stroke as usize]
@@ -491,6 +711,30 @@ impl Context {
         u32::from(self.state().styleType[ContextOpType::Stroke as usize]).into()
     }
 
+    /// Sets the stroke style.
+    ///
+    /// [`Style::None`] is set as a transparent solid color, which renders
+    /// nothing with the default [`CompOp::SrcOver`], but still affects the
+    /// target with composition operators like [`CompOp::SrcCopy`].
+    #[inline]
+    pub fn set_stroke_style<S: Into<Style>>(&mut self, style: S) -> Result<()> {
+        self.set_style(
+            &style.into(),
+            ffi::blContextSetStrokeStyle,
+            ffi::blContextSetStrokeStyleRgba64,
+        )
+    }
+
+    /// Returns the current stroke style.
+    #[inline]
+    pub fn stroke_style(&self) -> Style {
+        self.style(
+            self.stroke_style_type(),
+            ffi::blContextGetStrokeStyle,
+            ffi::blContextGetStrokeStyleRgba64,
+        )
+    }
+
     #[inline]
     pub fn set_stroke_style_gradient<T: GradientType>(&mut self, gradient: &Gradient<T>) {
         unsafe {
@@ -512,30 +756,30 @@ impl Context {
     }
 
     #[inline]
-    pub fn set_stroke_style_rgba32(&mut self, color: u32) {
-        unsafe { ffi::blContextSetStrokeStyleRgba32(self.core_mut(), color) };
+    pub fn set_stroke_style_rgba32<C: Into<Rgba32>>(&mut self, color: C) {
+        unsafe { ffi::blContextSetStrokeStyleRgba32(self.core_mut(), color.into().0) };
     }
 
     #[inline]
-    pub fn set_stroke_style_rgba64(&mut self, color: u64) {
-        unsafe { ffi::blContextSetStrokeStyleRgba64(self.core_mut(), color) };
+    pub fn set_stroke_style_rgba64<C: Into<Rgba64>>(&mut self, color: C) {
+        unsafe { ffi::blContextSetStrokeStyleRgba64(self.core_mut(), color.into().0) };
     }
 
     #[inline]
-    pub fn get_stroke_style_rgba32(&self) -> Result<u32> {
+    pub fn get_stroke_style_rgba32(&self) -> Result<Rgba32> {
         unsafe {
             let mut out = 0;
             errcode_to_result(ffi::blContextGetStrokeStyleRgba32(self.core(), &mut out))
-                .map(|_| out)
+                .map(|_| Rgba32(out))
         }
     }
 
     #[inline]
-    pub fn get_stroke_style_rgba64(&self) -> Result<u64> {
+    pub fn get_stroke_style_rgba64(&self) -> Result<Rgba64> {
         unsafe {
             let mut out = 0;
             errcode_to_result(ffi::blContextGetStrokeStyleRgba64(self.core(), &mut out))
-                .map(|_| out)
+                .map(|_| Rgba64(out))
         }
     }
 
@@ -641,10 +885,13 @@ impl Context {
 }
 
 /// Clip Operations
-impl Context {
+impl Context<'_> {
+    /// Restores the clipping to the one of the last saved state, or removes
+    /// it if there is none.
     #[inline]
     pub fn restore_clipping(&mut self) {
         unsafe { ffi::blContextRestoreClipping(self.core_mut()) };
+        expect_mem_err(self.pop_mask_clips(self.saved_state_count()));
     }
 
     #[inline]
@@ -656,10 +903,191 @@ impl Context {
     pub fn clip_to(&mut self, x: f64, y: f64, w: f64, h: f64) {
         self.clip_to_rect(&RectD { x, y, w, h });
     }
+
+    /// Clips to the area covered by the given [`Path`] when filled with the
+    /// given [`FillRule`], mapped through the current transformation.
+    ///
+    /// Like every other clip operation the resulting clip is intersected with
+    /// the current one and is part of the context state, so it is removed
+    /// again by [`restore`](#method.restore),
+    /// [`restore_cookie`](#method.restore_cookie) and
+    /// [`restore_clipping`](#method.restore_clipping). An empty path clips
+    /// everything.
+    ///
+    /// Unless the path is a single axis-aligned rectangle, the path is
+    /// rasterized into a mask, see [`clip_to_mask`](#method.clip_to_mask).
+    pub fn clip_to_path(&mut self, path: &Path, fill_rule: FillRule) -> Result<()> {
+        if path.is_empty() {
+            self.clip_to_rect(&RectD::default());
+            return Ok(());
+        }
+        // Both fill rules cover the whole rectangle.
+        if let Some(BoxD { x0, y0, x1, y1 }) = path.axis_aligned_rect() {
+            self.clip_to(x0, y0, x1 - x0, y1 - y0);
+            return Ok(());
+        }
+        let mut device_path = Path::new();
+        device_path.add_transformed_path(path, self.user_matrix());
+        device_path.transform(self.meta_matrix());
+
+        let (width, height) = self.target_pixel_size();
+        let mut mask = Image::new(width as i32, height as i32, ImageFormat::PRgb32)?;
+        let mut ctx = Context::new(&mut mask)?;
+        ctx.set_comp_op(CompOp::SrcCopy);
+        ctx.set_fill_rule(fill_rule);
+        ctx.set_fill_style_rgba32(Rgba32::rgb(0xFF, 0xFF, 0xFF));
+        ctx.fill_path(&device_path)?;
+        ctx.end()?;
+
+        let coverage = mask
+            .data()
+            .rows()
+            .flat_map(|row| row.chunks_exact(4))
+            .map(|pixel| read_pixel(ImageFormat::PRgb32, pixel).a())
+            .collect();
+        self.push_mask_clip(coverage)
+    }
+
+    /// Clips to the area covered by the given [`Geometry`].
+    ///
+    /// See [`clip_to_path`](#method.clip_to_path) for details.
+    pub fn clip_to_geometry<T: Geometry + ?Sized>(&mut self, geometry: &T) -> Result<()> {
+        let mut path = Path::new();
+        path.add_geometry(geometry, None, GeometryDirection::None);
+        self.clip_to_path(&path, FillRule::NonZero)
+    }
+
+    /// Clips to the given [`A8`](../image/enum.ImageFormat.html#variant.A8)
+    /// mask, whose pixels give the coverage of the target pixels at the same
+    /// position. The mask is not transformed, pixels outside of it are
+    /// clipped away.
+    ///
+    /// Blend2D only clips to rectangles, so mask clips are emulated: the
+    /// pixels covered by the clip are blended with the ones the target had
+    /// when the clip was set once it is removed again, by a restore or when
+    /// the context is ended. This needs a copy of the target per clip, so
+    /// prefer [`clip_to_rect`](#method.clip_to_rect) where possible.
+    ///
+    /// As the pixels are blended once for all draws, partially covered
+    /// pixels differ from a real clip where draws overlap: two draws with an
+    /// alpha of 50% on a pixel with a coverage of 50% leave an alpha of 37.5%
+    /// instead of the 43.75% of clipping every draw on its own.
+    ///
+    /// Returns [`Error::InvalidValue`] if the mask is not an `A8` image.
+    pub fn clip_to_mask(&mut self, mask: &Image) -> Result<()> {
+        if mask.format() != ImageFormat::A8 {
+            return Err(Error::InvalidValue);
+        }
+        let (width, height) = self.target_pixel_size();
+        let mut coverage = vec![0; width * height];
+        for (dst, src) in coverage.chunks_exact_mut(width).zip(mask.data().rows()) {
+            let len = dst.len().min(src.len());
+            dst[..len].copy_from_slice(&src[..len]);
+        }
+        self.push_mask_clip(coverage)
+    }
+}
+
+impl Context<'_> {
+    fn target_data(&self) -> ffi::BLImageData {
+        unsafe {
+            let mut data = std::mem::zeroed();
+            expect_mem_err(ffi::blImageGetData(self.target, &mut data));
+            data
+        }
+    }
+
+    fn target_pixel_size(&self) -> (usize, usize) {
+        let ffi::BLSizeI { w, h } = self.target_data().size;
+        (w as usize, h as usize)
+    }
+
+    /// Calls `f` with the index, bytes per pixel and pixels of every row of
+    /// the target, after waiting for all pending render commands.
+    ///
+    /// Returns the error code of waiting for the commands, in which case `f`
+    /// isn't called.
+    fn for_each_target_row<F>(&mut self, mut f: F) -> u32
+    where
+        F: FnMut(usize, usize, &mut [u8]),
+    {
+        let code =
+            unsafe { ffi::blContextFlush(self.core_mut(), ContextFlushFlags::FLUSH_SYNC.bits()) };
+        if code != 0 {
+            return code;
+        }
+        let data = self.target_data();
+        let bytes_per_pixel = ImageFormat::from(data.format).bytes_per_pixel();
+        let row_len = data.size.w as usize * bytes_per_pixel;
+        for y in 0..data.size.h as usize {
+            // The context is flushed and we hold the only borrow of the target.
+            let row = unsafe {
+                slice::from_raw_parts_mut(
+                    (data.pixelData as *mut u8).offset(y as isize * data.stride),
+                    row_len,
+                )
+            };
+            f(y, bytes_per_pixel, row);
+        }
+        0
+    }
+
+    fn push_mask_clip(&mut self, coverage: Vec<u8>) -> Result<()> {
+        let mut backdrop = Vec::new();
+        errcode_to_result(self.for_each_target_row(|_, _, row| backdrop.extend_from_slice(row)))?;
+        self.mask_clips.push(MaskClip {
+            depth: self.saved_state_count(),
+            coverage,
+            backdrop,
+        });
+        Ok(())
+    }
+
+    /// Removes the mask clips that have been set at a saved state count of
+    /// at least `depth`, innermost first.
+    ///
+    /// Returns the first error code of waiting for the pending render
+    /// commands, the clips are removed regardless.
+    fn pop_mask_clips(&mut self, depth: usize) -> u32 {
+        let mut result = 0;
+        while matches!(self.mask_clips.last(), Some(clip) if clip.depth >= depth) {
+            let clip = self.mask_clips.pop().unwrap();
+            let code = self.for_each_target_row(|y, bytes_per_pixel, row| {
+                let width = row.len() / bytes_per_pixel;
+                let coverage = &clip.coverage[y * width..][..width];
+                let backdrop = &clip.backdrop[y * row.len()..][..row.len()];
+                let pixels = row.chunks_exact_mut(bytes_per_pixel);
+                let backdrop = backdrop.chunks_exact(bytes_per_pixel);
+                for ((pixel, backdrop), &coverage) in pixels.zip(backdrop).zip(coverage) {
+                    blend_pixel(pixel, backdrop, coverage);
+                }
+            });
+            if result == 0 {
+                result = code;
+            }
+        }
+        result
+    }
+}
+
+/// Linearly interpolates between `backdrop` and `pixel` by `coverage`, which
+/// is exact for premultiplied pixels.
+fn blend_pixel(pixel: &mut [u8], backdrop: &[u8], coverage: u8) {
+    match coverage {
+        0 => pixel.copy_from_slice(backdrop),
+        0xFF => {},
+        coverage => {
+            let coverage = u32::from(coverage);
+            for (p, &b) in pixel.iter_mut().zip(backdrop) {
+                let v = u32::from(*p) * coverage + u32::from(b) * (0xFF - coverage) + 0x80;
+                *p = ((v + (v >> 8)) >> 8) as u8;
+            }
+        },
+    }
 }
 
 /// Clear Operations
-impl Context {
+impl Context<'_> {
     #[inline]
     pub fn clear_all(&mut self) -> Result<()> {
         unsafe { errcode_to_result(ffi::blContextClearAll(self.core_mut())) }
@@ -680,6 +1108,19 @@ impl Context {
         self.clear_rect(&RectD { x, y, w, h })
     }
 
+    /// Fills the whole target with the given color, replacing its contents
+    /// instead of compositing onto them. Clipping still applies.
+    pub fn clear_to_color<C: Into<Color>>(&mut self, color: C) -> Result<()> {
+        let style = Style::solid(color);
+        self.with_pushed_context(|ctx| {
+            ctx.set_comp_op(CompOp::SrcCopy);
+            ctx.set_global_alpha(1.0);
+            ctx.set_fill_alpha(1.0);
+            ctx.set_fill_style(style)?;
+            ctx.fill_all()
+        })
+    }
+
     pub fn blit_image<'r, P, RI>(&mut self, dst: &P, src: &Image, src_area: RI) -> Result<()>
     where
         P: Point,
@@ -716,8 +1157,14 @@ impl Context {
 }
 
 /// Fill Operations
-impl Context {
+impl Context<'_> {
+    /// Fills the given geometry, [`Path`]s are filled by
+    /// [`fill_path`](#method.fill_path).
     pub fn fill_geometry<T: Geometry + ?Sized>(&mut self, geo: &T) -> Result<()> {
+        if T::GEO_TYPE == Path::GEO_TYPE {
+            // `Path` is the only geometry of this type.
+            return self.fill_path(unsafe { &*(geo as *const T as *const Path) });
+        }
         unsafe {
             errcode_to_result(ffi::blContextFillGeometry(
                 self.core_mut(),
@@ -782,9 +1229,19 @@ impl Context {
         self.fill_geometry(&Triangle { x0, y0, x1, y1, x2, y2 })
     }
 
-    #[inline]
+    /// Fills the given path.
+    ///
+    /// Blend2D may update the cached info of the path while doing so, which
+    /// is why a path that shares outdated info with a clone is copied first.
     pub fn fill_path(&mut self, p: &Path) -> Result<()> {
-        self.fill_geometry(p)
+        let path = p.with_info();
+        unsafe {
+            errcode_to_result(ffi::blContextFillGeometry(
+                self.core_mut(),
+                Path::GEO_TYPE,
+                path.core() as *const _ as *const _,
+            ))
+        }
     }
 
     #[inline]
@@ -809,14 +1266,66 @@ impl Context {
 
     #[inline]
     pub fn fill_utf8_text<P: Point>(&mut self, dst: P, font: &Font, text: &str) -> Result<()> {
+        self.fill_text(
+            dst,
+            font,
+            text.as_bytes(),
+            ffi::BLTextEncoding::BL_TEXT_ENCODING_UTF8,
+        )
+    }
+
+    /// Fills UTF-16 encoded text.
+    #[inline]
+    pub fn fill_utf16_text<P: Point>(&mut self, dst: P, font: &Font, text: &[u16]) -> Result<()> {
+        self.fill_text(dst, font, text, ffi::BLTextEncoding::BL_TEXT_ENCODING_UTF16)
+    }
+
+    /// Fills UTF-32 encoded text.
+    #[inline]
+    pub fn fill_utf32_text<P: Point>(&mut self, dst: P, font: &Font, text: &[u32]) -> Result<()> {
+        self.fill_text(dst, font, text, ffi::BLTextEncoding::BL_TEXT_ENCODING_UTF32)
+    }
+
+    /// Fills Latin-1 (ISO 8859-1) encoded text.
+    #[inline]
+    pub fn fill_latin1_text<P: Point>(&mut self, dst: P, font: &Font, text: &[u8]) -> Result<()> {
+        self.fill_text(
+            dst,
+            font,
+            text,
+            ffi::BLTextEncoding::BL_TEXT_ENCODING_LATIN1,
+        )
+    }
+
+    /// Fills already mapped glyphs, shaping them with the given font
+    /// first.
+    pub fn fill_glyph_ids<P: Point>(
+        &mut self,
+        dst: P,
+        font: &Font,
+        glyph_ids: &[GlyphId],
+    ) -> Result<()> {
+        let mut buf = GlyphBuffer::new();
+        buf.set_glyph_ids(glyph_ids);
+        font.shape(&mut buf)?;
+        self.fill_glyph_run(dst, font, buf.glyph_run())
+    }
+
+    fn fill_text<P: Point, T>(
+        &mut self,
+        dst: P,
+        font: &Font,
+        text: &[T],
+        encoding: ffi::BLTextEncoding::Type,
+    ) -> Result<()> {
         unsafe {
             errcode_to_result(P::FILL_TEXT(
                 self.core_mut(),
                 &dst as *const _ as *const _,
                 font.core(),
-                text.as_bytes().as_ptr() as *const _,
+                text.as_ptr() as *const _,
                 text.len(),
-                ffi::BLTextEncoding::BL_TEXT_ENCODING_UTF8 as u32,
+                encoding as u32,
             ))
         }
     }
@@ -833,15 +1342,55 @@ impl Context {
                 self.core_mut(),
                 &dst as *const _ as *const _,
                 font.core(),
-                glyph_run.raw,
+                &glyph_run.raw,
             ))
         }
     }
+
+    /// Fills every line of the text layout, with `dst` as the top left
+    /// corner of the layout.
+    pub fn fill_text_layout<P: Point>(
+        &mut self,
+        dst: P,
+        font: &Font,
+        layout: &TextLayout,
+    ) -> Result<()> {
+        let [x, y] = dst.into_f64();
+        for line in layout.lines() {
+            let origin = line.origin();
+            let dst = PointD {
+                x: x + origin.x,
+                y: y + origin.y,
+            };
+            self.fill_glyph_run(dst, font, line.glyph_run())?;
+        }
+        Ok(())
+    }
+
+    /// Fills every run of the bidirectional text, with `dst` as the start of
+    /// its baseline.
+    pub fn fill_bidi_text<P: Point>(&mut self, dst: P, font: &Font, text: &BidiText) -> Result<()> {
+        let [x, y] = dst.into_f64();
+        for run in text.runs() {
+            let dst = PointD {
+                x: x + run.offset(),
+                y,
+            };
+            self.fill_glyph_run(dst, font, run.glyph_run())?;
+        }
+        Ok(())
+    }
 }
 
 /// Stroke Operations
-impl Context {
+impl Context<'_> {
+    /// Strokes the given geometry, [`Path`]s are stroked by
+    /// [`stroke_path`](#method.stroke_path).
     pub fn stroke_geometry<T: Geometry + ?Sized>(&mut self, geo: &T) -> Result<()> {
+        if T::GEO_TYPE == Path::GEO_TYPE {
+            // `Path` is the only geometry of this type.
+            return self.stroke_path(unsafe { &*(geo as *const T as *const Path) });
+        }
         unsafe {
             errcode_to_result(ffi::blContextStrokeGeometry(
                 self.core_mut(),
@@ -906,9 +1455,19 @@ impl Context {
         self.stroke_geometry(&Triangle { x0, y0, x1, y1, x2, y2 })
     }
 
-    #[inline]
+    /// Strokes the given path.
+    ///
+    /// Blend2D may update the cached info of the path while doing so, which
+    /// is why a path that shares outdated info with a clone is copied first.
     pub fn stroke_path(&mut self, p: &Path) -> Result<()> {
-        self.stroke_geometry(p)
+        let path = p.with_info();
+        unsafe {
+            errcode_to_result(ffi::blContextStrokeGeometry(
+                self.core_mut(),
+                Path::GEO_TYPE,
+                path.core() as *const _ as *const _,
+            ))
+        }
     }
 
     #[inline]
@@ -949,14 +1508,66 @@ impl Context {
 
     #[inline]
     pub fn stroke_utf8_text<P: Point>(&mut self, dst: P, font: &Font, text: &str) -> Result<()> {
+        self.stroke_text(
+            dst,
+            font,
+            text.as_bytes(),
+            ffi::BLTextEncoding::BL_TEXT_ENCODING_UTF8,
+        )
+    }
+
+    /// Strokes UTF-16 encoded text.
+    #[inline]
+    pub fn stroke_utf16_text<P: Point>(&mut self, dst: P, font: &Font, text: &[u16]) -> Result<()> {
+        self.stroke_text(dst, font, text, ffi::BLTextEncoding::BL_TEXT_ENCODING_UTF16)
+    }
+
+    /// Strokes UTF-32 encoded text.
+    #[inline]
+    pub fn stroke_utf32_text<P: Point>(&mut self, dst: P, font: &Font, text: &[u32]) -> Result<()> {
+        self.stroke_text(dst, font, text, ffi::BLTextEncoding::BL_TEXT_ENCODING_UTF32)
+    }
+
+    /// Strokes Latin-1 (ISO 8859-1) encoded text.
+    #[inline]
+    pub fn stroke_latin1_text<P: Point>(&mut self, dst: P, font: &Font, text: &[u8]) -> Result<()> {
+        self.stroke_text(
+            dst,
+            font,
+            text,
+            ffi::BLTextEncoding::BL_TEXT_ENCODING_LATIN1,
+        )
+    }
+
+    /// Strokes already mapped glyphs, shaping them with the given font
+    /// first.
+    pub fn stroke_glyph_ids<P: Point>(
+        &mut self,
+        dst: P,
+        font: &Font,
+        glyph_ids: &[GlyphId],
+    ) -> Result<()> {
+        let mut buf = GlyphBuffer::new();
+        buf.set_glyph_ids(glyph_ids);
+        font.shape(&mut buf)?;
+        self.stroke_glyph_run(dst, font, buf.glyph_run())
+    }
+
+    fn stroke_text<P: Point, T>(
+        &mut self,
+        dst: P,
+        font: &Font,
+        text: &[T],
+        encoding: ffi::BLTextEncoding::Type,
+    ) -> Result<()> {
         unsafe {
             errcode_to_result(P::STROKE_TEXT(
                 self.core_mut(),
                 &dst as *const _ as *const _,
                 font.core(),
-                text.as_bytes().as_ptr() as *const _,
+                text.as_ptr() as *const _,
                 text.len(),
-                ffi::BLTextEncoding::BL_TEXT_ENCODING_UTF8 as u32,
+                encoding as u32,
             ))
         }
     }
@@ -973,13 +1584,13 @@ impl Context {
                 self.core_mut(),
                 &dst as *const _ as *const _,
                 font.core(),
-                glyph_run.raw,
+                &glyph_run.raw,
             ))
         }
     }
 }
 
-impl MatrixTransform for Context {
+impl MatrixTransform for Context<'_> {
     #[inline]
     #[doc(hidden)]
     fn apply_matrix_op(&mut self, op: Matrix2DOp, data: &[f64]) {
@@ -993,15 +1604,299 @@ impl MatrixTransform for Context {
     }
 }
 
-impl PartialEq for Context {
+impl PartialEq for Context<'_> {
     #[inline]
     fn eq(&self, other: &Self) -> bool {
         self.impl_equals(other)
     }
 }
 
-impl Drop for Context {
+impl Drop for Context<'_> {
     fn drop(&mut self) {
+        // Errors can't be reported from here, the clips are removed anyway.
+        self.pop_mask_clips(0);
         unsafe { ffi::blContextReset(&mut self.core) };
     }
 }
+
+#[cfg(test)]
+mod test_context {
+    use super::blend_pixel;
+    use crate::color::Rgba32;
+    use crate::context::{Context, ContextCreateFlags, ContextCreateInfo, ContextFlushFlags};
+    use crate::error::Error;
+    use crate::font::FontFace;
+    use crate::geometry::{FillRule, GeometryDirection, PointD, RectD};
+    use crate::glyph_buffer::{GlyphBuffer, GlyphId};
+    use crate::gradient::{LinearGradient, LinearGradientValues};
+    use crate::image::{Image, ImageFormat, ImageViewMut};
+    use crate::matrix::MatrixTransform;
+    use crate::path::Path;
+    use crate::style::Style;
+    use crate::{DataAccessFlags, ExtendMode, StyleType};
+
+    fn render_scene(thread_count: u32) -> Vec<u8> {
+        let mut img = Image::new(256, 256, ImageFormat::PRgb32).unwrap();
+        let info = ContextCreateInfo {
+            flags: if thread_count > 0 {
+                ContextCreateFlags::FORCE_THREADS
+            } else {
+                ContextCreateFlags::empty()
+            },
+            thread_count,
+            cpu_features: 0,
+        };
+        let mut ctx = Context::new_with_options(&mut img, info).unwrap();
+        ctx.clear_all().unwrap();
+        for i in 0..64 {
+            let t = f64::from(i);
+            ctx.set_fill_style_rgba32(0x8000_0000 | (i * 0x0004_0301));
+            ctx.fill_circle(128.0 + t, 128.0 - t, 16.0 + t).unwrap();
+            ctx.set_stroke_style_rgba32(0xFF00_0000 | (i * 0x0001_0203));
+            ctx.set_stroke_width(1.0 + t / 16.0);
+            ctx.stroke_line(0.0, t * 4.0, 256.0, 256.0 - t * 4.0)
+                .unwrap();
+        }
+        ctx.flush(ContextFlushFlags::FLUSH_SYNC).unwrap();
+        ctx.end().unwrap();
+        img.to_vec()
+    }
+
+    #[test]
+    fn test_threaded_rendering_matches_sync() {
+        let expected = render_scene(0);
+        assert_eq!(render_scene(1), expected);
+        assert_eq!(render_scene(4), expected);
+    }
+
+    #[test]
+    fn test_style_roundtrip() {
+        let mut img = Image::new(32, 32, ImageFormat::PRgb32).unwrap();
+        let mut ctx = Context::new(&mut img).unwrap();
+        ctx.set_fill_style(Rgba32(0xFF00_FF00)).unwrap();
+        assert_eq!(ctx.fill_style(), Style::solid(0xFF00_FF00));
+
+        let values = LinearGradientValues {
+            x0: 0.0,
+            y0: 0.0,
+            x1: 32.0,
+            y1: 32.0,
+        };
+        let gradient = LinearGradient::new(&values, ExtendMode::PadXPadY, [], None);
+        ctx.set_stroke_style(&gradient).unwrap();
+        assert_eq!(ctx.stroke_style_type(), StyleType::Gradient);
+        assert_eq!(ctx.stroke_style(), Style::from(gradient));
+
+        ctx.set_stroke_style(Style::None).unwrap();
+        assert_eq!(ctx.stroke_style(), Style::solid(Rgba32(0)));
+        ctx.set_fill_style(ctx.stroke_style()).unwrap();
+    }
+
+    #[test]
+    fn test_clear_to_color() {
+        let mut img = Image::new(4, 4, ImageFormat::PRgb32).unwrap();
+        let mut ctx = Context::new(&mut img).unwrap();
+        ctx.set_global_alpha(0.5);
+        let color = "#ff000080".parse::<Rgba32>().unwrap();
+        ctx.clear_to_color(color).unwrap();
+        assert_eq!(ctx.global_alpha(), 0.5);
+        assert_eq!(ctx.saved_state_count(), 0);
+        ctx.end().unwrap();
+        assert!(img
+            .to_vec()
+            .chunks(4)
+            .all(|px| px == [0x00, 0x00, 0x80, 0x80]));
+    }
+
+    #[test]
+    fn test_with_pushed_context_restores_on_error() {
+        let mut img = Image::new(4, 4, ImageFormat::PRgb32).unwrap();
+        let mut ctx = Context::new(&mut img).unwrap();
+        let result = ctx.with_pushed_context(|ctx| {
+            ctx.set_global_alpha(0.5);
+            Err(Error::InvalidValue)
+        });
+        assert!(matches!(result, Err(Error::InvalidValue)));
+        assert_eq!(ctx.saved_state_count(), 0);
+        assert_eq!(ctx.global_alpha(), 1.0);
+    }
+
+    #[test]
+    fn test_render_to_view() {
+        // Rows of 4 pixels padded to 20 bytes.
+        let mut buffer = vec![0xAAu8; 20 * 4];
+        let mut view = ImageViewMut::new(&mut buffer, 4, 4, ImageFormat::PRgb32, 20).unwrap();
+        let mut ctx = Context::new(&mut view).unwrap();
+        ctx.set_fill_style_rgba32(0xFF00_00FF);
+        ctx.fill_all().unwrap();
+        ctx.end().unwrap();
+        assert_eq!(view.data_mut().len(), 80);
+        for row in buffer.chunks(20) {
+            assert!(row[..16].chunks(4).all(|px| px == [0xFF, 0x00, 0x00, 0xFF]));
+            assert_eq!(row[16..], [0xAA; 4]);
+        }
+    }
+
+    #[test]
+    fn test_view_rejects_async_context() {
+        let mut buffer = vec![0u8; 4 * 4 * 4];
+        let mut view = ImageViewMut::new(&mut buffer, 4, 4, ImageFormat::PRgb32, 16).unwrap();
+        let info = ContextCreateInfo {
+            flags: ContextCreateFlags::FORCE_THREADS,
+            thread_count: 2,
+            cpu_features: 0,
+        };
+        assert!(matches!(
+            Context::new_with_options(&mut view, info),
+            Err(Error::InvalidValue)
+        ));
+    }
+
+    #[test]
+    fn test_flush_sync() {
+        let mut img = Image::new(32, 32, ImageFormat::PRgb32).unwrap();
+        let mut ctx = Context::new(&mut img).unwrap();
+        ctx.fill_all().unwrap();
+        assert!(ctx.flush(ContextFlushFlags::FLUSH_SYNC).is_ok());
+    }
+
+    #[test]
+    fn test_blend_pixel() {
+        let mut pixel = [0xFF, 0x80, 0x00, 0xFF];
+        blend_pixel(&mut pixel, &[0x00, 0x00, 0xFF, 0xFF], 0x80);
+        assert_eq!(pixel, [0x80, 0x40, 0x7F, 0xFF]);
+        blend_pixel(&mut pixel, &[0x01, 0x02, 0x03, 0x04], 0x00);
+        assert_eq!(pixel, [0x01, 0x02, 0x03, 0x04]);
+        blend_pixel(&mut pixel, &[0xFF; 4], 0xFF);
+        assert_eq!(pixel, [0x01, 0x02, 0x03, 0x04]);
+    }
+
+    #[test]
+    fn test_clip_to_mask() {
+        let mut mask = Image::new(3, 1, ImageFormat::A8).unwrap();
+        mask.data_mut().data[..3].copy_from_slice(&[0x00, 0x80, 0xFF]);
+
+        let mut img = Image::new(4, 2, ImageFormat::PRgb32).unwrap();
+        let mut ctx = Context::new(&mut img).unwrap();
+        let not_a8 = Image::new(3, 1, ImageFormat::PRgb32).unwrap();
+        assert!(matches!(
+            ctx.clip_to_mask(&not_a8),
+            Err(Error::InvalidValue)
+        ));
+        ctx.clear_all().unwrap();
+        ctx.save();
+        ctx.clip_to_mask(&mask).unwrap();
+        ctx.set_fill_style_rgba32(0xFFFF_0000);
+        ctx.fill_all().unwrap();
+        ctx.restore().unwrap();
+        // The clip is gone after restoring.
+        ctx.set_fill_style_rgba32(0xFF00_00FF);
+        ctx.fill_rect(0.0, 1.0, 1.0, 1.0).unwrap();
+        ctx.end().unwrap();
+
+        assert_eq!(img.get_pixel(0, 0), Some(Rgba32(0)));
+        assert_eq!(img.get_pixel(1, 0).map(|c| c.a()), Some(0x80));
+        assert_eq!(img.get_pixel(2, 0), Some(Rgba32(0xFFFF_0000)));
+        // Pixels outside of the mask are clipped away.
+        assert_eq!(img.get_pixel(3, 0), Some(Rgba32(0)));
+        assert_eq!(img.get_pixel(1, 1), Some(Rgba32(0)));
+        assert_eq!(img.get_pixel(0, 1), Some(Rgba32(0xFF00_00FF)));
+    }
+
+    #[test]
+    fn test_clip_to_mask_overdraw() {
+        // The coverage of a pixel on an anti-aliased edge.
+        let mut mask = Image::new(1, 1, ImageFormat::A8).unwrap();
+        mask.data_mut().data[0] = 0x80;
+        let render = |mask: Option<&Image>| {
+            let mut img = Image::new(1, 1, ImageFormat::PRgb32).unwrap();
+            let mut ctx = Context::new(&mut img).unwrap();
+            ctx.clear_all().unwrap();
+            if let Some(mask) = mask {
+                ctx.clip_to_mask(mask).unwrap();
+            }
+            ctx.set_fill_style_rgba32(0x80FF_FFFF);
+            ctx.fill_all().unwrap();
+            ctx.fill_all().unwrap();
+            ctx.end().unwrap();
+            img
+        };
+        let unclipped = render(None);
+        let clipped = render(Some(&mask));
+
+        // The result of both draws is blended with the backdrop once.
+        let mut expected = unclipped.data().data[..4].to_vec();
+        blend_pixel(&mut expected, &[0; 4], 0x80);
+        assert_eq!(&clipped.data().data[..4], &expected[..]);
+        // Clipping every draw on its own would leave an alpha of about 0x70.
+        assert_eq!(clipped.get_pixel(0, 0).map(|c| c.a()), Some(0x60));
+    }
+
+    #[test]
+    fn test_clip_to_path_fill_rule() {
+        // Two nested rectangles of the same orientation.
+        let mut path = Path::new();
+        for &(xy, wh) in &[(0.0, 8.0), (2.0, 4.0)] {
+            let rect = RectD {
+                x: xy,
+                y: xy,
+                w: wh,
+                h: wh,
+            };
+            path.add_geometry(&rect, None, GeometryDirection::Clockwise);
+        }
+
+        let render = |fill_rule| {
+            let mut img = Image::new(10, 10, ImageFormat::PRgb32).unwrap();
+            let mut ctx = Context::new(&mut img).unwrap();
+            ctx.clear_all().unwrap();
+            ctx.translate(1.0, 1.0);
+            ctx.clip_to_path(&path, fill_rule).unwrap();
+            ctx.set_fill_style_rgba32(0xFFFF_FFFF);
+            ctx.fill_all().unwrap();
+            ctx.end().unwrap();
+            img
+        };
+        let non_zero = render(FillRule::NonZero);
+        let even_odd = render(FillRule::EvenOdd);
+        for img in &[&non_zero, &even_odd] {
+            assert_eq!(img.get_pixel(0, 0), Some(Rgba32(0)));
+            assert_eq!(img.get_pixel(1, 1), Some(Rgba32(0xFFFF_FFFF)));
+            assert_eq!(img.get_pixel(9, 9), Some(Rgba32(0)));
+        }
+        assert_eq!(non_zero.get_pixel(4, 4), Some(Rgba32(0xFFFF_FFFF)));
+        assert_eq!(even_odd.get_pixel(4, 4), Some(Rgba32(0)));
+    }
+
+    #[test]
+    fn test_fill_glyph_ids_matches_text() {
+        let path = concat!(env!("CARGO_MANIFEST_DIR"), "/assets/NotoSans-Regular.ttf");
+        let face = FontFace::from_path(path, DataAccessFlags::READ).unwrap();
+        let font = face.create_font(16.0).unwrap();
+        let mut buf = GlyphBuffer::from("AVATAR");
+        font.map_text_to_glyphs(&mut buf).unwrap();
+        let glyph_ids: Vec<GlyphId> = buf.glyph_ids().collect();
+
+        let render = |glyphs: bool, stroke: bool| {
+            let mut img = Image::new(64, 24, ImageFormat::PRgb32).unwrap();
+            let mut ctx = Context::new(&mut img).unwrap();
+            ctx.clear_all().unwrap();
+            ctx.set_fill_style_rgba32(0xFFFF_FFFF);
+            ctx.set_stroke_style_rgba32(0xFFFF_FFFF);
+            let dst = PointD { x: 2.0, y: 18.0 };
+            match (glyphs, stroke) {
+                (true, false) => ctx.fill_glyph_ids(dst, &font, &glyph_ids),
+                (false, false) => ctx.fill_latin1_text(dst, &font, b"AVATAR"),
+                (true, true) => ctx.stroke_glyph_ids(dst, &font, &glyph_ids),
+                (false, true) => ctx.stroke_latin1_text(dst, &font, b"AVATAR"),
+            }
+            .unwrap();
+            ctx.end().unwrap();
+            img.to_vec()
+        };
+        let filled = render(false, false);
+        assert!(filled.iter().any(|&b| b != 0));
+        assert_eq!(render(true, false), filled);
+        assert_eq!(render(true, true), render(false, true));
+    }
+}
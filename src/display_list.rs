@@ -0,0 +1,1089 @@
+//! Recording of rendering commands for later replay.
+//!
+//! A [`DisplayList`] offers the fill, stroke, blit, state and matrix
+//! operations of a [`Context`] but instead of rendering them it records them
+//! as a list of [`DisplayCommand`]s. The list can then be replayed onto any
+//! number of contexts, for example to render thumbnails of a scene at several
+//! scales or to redraw it after its target has been resized.
+//!
+//! [`Context`]: ../context/struct.Context.html
+use std::slice;
+
+use crate::array::Array;
+use crate::color::{Color, Rgba32, Rgba64};
+use crate::context::{CompOp, Context, ContextHint};
+use crate::error::Result;
+use crate::font::Font;
+use crate::geometry::{
+    Arc, BoxD, BoxI, Chord, Circle, Ellipse, FillRule, GeoViewArray, Line, Pie, Point, PointD,
+    PointI, Rect, RectD, RectI, RoundRect, Triangle,
+};
+use crate::gradient::DynamicGradient;
+use crate::image::Image;
+use crate::matrix::{Matrix2D, Matrix2DOp, MatrixTransform};
+use crate::path::{
+    FlattenMode, Path, StrokeCap, StrokeCapPosition, StrokeJoin, StrokeOptions,
+    StrokeTransformOrder,
+};
+use crate::pattern::Pattern;
+use crate::region::Region;
+use crate::style::Style;
+
+/// A geometry owned by a [`DisplayList`].
+///
+/// Every type that can be passed to [`Context::fill_geometry`] converts into
+/// this, slices are copied and [`Path`]s and [`Region`]s are cloned.
+///
+/// [`Context::fill_geometry`]: ../context/struct.Context.html#method.fill_geometry
+#[derive(Clone, Debug, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub enum DisplayGeometry {
+    BoxI(BoxI),
+    BoxD(BoxD),
+    RectI(RectI),
+    RectD(RectD),
+    Circle(Circle),
+    Ellipse(Ellipse),
+    RoundRect(RoundRect),
+    Arc(Arc),
+    Chord(Chord),
+    Pie(Pie),
+    Line(Line),
+    Triangle(Triangle),
+    PolygonI(Vec<PointI>),
+    PolygonD(Vec<PointD>),
+    PolylineI(Vec<PointI>),
+    PolylineD(Vec<PointD>),
+    BoxIArray(Vec<BoxI>),
+    BoxDArray(Vec<BoxD>),
+    RectIArray(Vec<RectI>),
+    RectDArray(Vec<RectD>),
+    Path(Path),
+    Region(Region),
+}
+
+macro_rules! impl_from_geometry {
+    ($($ty:ident),*) => {
+        $(
+            impl From<&$ty> for DisplayGeometry {
+                #[inline]
+                fn from(geo: &$ty) -> Self {
+                    DisplayGeometry::$ty(*geo)
+                }
+            }
+        )*
+    };
+}
+
+impl_from_geometry!(
+    BoxI, BoxD, RectI, RectD, Circle, Ellipse, RoundRect, Arc, Chord, Pie, Line, Triangle
+);
+
+macro_rules! impl_from_geometry_slice {
+    ($($ty:ident => $variant:ident),*) => {
+        $(
+            impl From<&[$ty]> for DisplayGeometry {
+                #[inline]
+                fn from(geo: &[$ty]) -> Self {
+                    DisplayGeometry::$variant(geo.to_vec())
+                }
+            }
+        )*
+    };
+}
+
+impl_from_geometry_slice!(
+    PointI => PolygonI,
+    PointD => PolygonD,
+    BoxI => BoxIArray,
+    BoxD => BoxDArray,
+    RectI => RectIArray,
+    RectD => RectDArray
+);
+
+impl From<&Path> for DisplayGeometry {
+    #[inline]
+    fn from(path: &Path) -> Self {
+        DisplayGeometry::Path(path.clone())
+    }
+}
+
+impl From<&Region> for DisplayGeometry {
+    #[inline]
+    fn from(region: &Region) -> Self {
+        DisplayGeometry::Region(region.clone())
+    }
+}
+
+/// Calls `$body` with `$geo` bound to a reference to the contained geometry.
+/// Polylines are handed out as polygons.
+macro_rules! with_geometry {
+    ($geometry:expr, $geo:ident => $body:expr) => {
+        match $geometry {
+            DisplayGeometry::BoxI($geo) => $body,
+            DisplayGeometry::BoxD($geo) => $body,
+            DisplayGeometry::RectI($geo) => $body,
+            DisplayGeometry::RectD($geo) => $body,
+            DisplayGeometry::Circle($geo) => $body,
+            DisplayGeometry::Ellipse($geo) => $body,
+            DisplayGeometry::RoundRect($geo) => $body,
+            DisplayGeometry::Arc($geo) => $body,
+            DisplayGeometry::Chord($geo) => $body,
+            DisplayGeometry::Pie($geo) => $body,
+            DisplayGeometry::Line($geo) => $body,
+            DisplayGeometry::Triangle($geo) => $body,
+            DisplayGeometry::PolygonI($geo) | DisplayGeometry::PolylineI($geo) => {
+                let $geo = &$geo[..];
+                $body
+            },
+            DisplayGeometry::PolygonD($geo) | DisplayGeometry::PolylineD($geo) => {
+                let $geo = &$geo[..];
+                $body
+            },
+            DisplayGeometry::BoxIArray($geo) => {
+                let $geo = &$geo[..];
+                $body
+            },
+            DisplayGeometry::BoxDArray($geo) => {
+                let $geo = &$geo[..];
+                $body
+            },
+            DisplayGeometry::RectIArray($geo) => {
+                let $geo = &$geo[..];
+                $body
+            },
+            DisplayGeometry::RectDArray($geo) => {
+                let $geo = &$geo[..];
+                $body
+            },
+            DisplayGeometry::Path($geo) => $body,
+            DisplayGeometry::Region($geo) => $body,
+        }
+    };
+}
+
+impl DisplayGeometry {
+    fn fill(&self, ctx: &mut Context<'_>) -> Result<()> {
+        with_geometry!(self, geo => ctx.fill_geometry(geo))
+    }
+
+    fn stroke(&self, ctx: &mut Context<'_>) -> Result<()> {
+        match self {
+            DisplayGeometry::PolylineI(poly) => ctx.stroke_polyline(poly),
+            DisplayGeometry::PolylineD(poly) => ctx.stroke_polyline(poly),
+            geometry => with_geometry!(geometry, geo => ctx.stroke_geometry(geo)),
+        }
+    }
+
+    fn clip(&self, ctx: &mut Context<'_>) -> Result<()> {
+        with_geometry!(self, geo => ctx.clip_to_geometry(geo))
+    }
+}
+
+/// A single command recorded by a [`DisplayList`].
+///
+/// Every variant corresponds to the [`Context`] method of the same name.
+///
+/// [`Context`]: ../context/struct.Context.html
+#[derive(Clone, Debug, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub enum DisplayCommand {
+    Save,
+    Restore,
+    UserToMeta,
+    SetHint(ContextHint, u32),
+    SetFlattenMode(FlattenMode),
+    SetFlattenTolerance(f64),
+    SetCompOp(CompOp),
+    SetGlobalAlpha(f64),
+
+    SetFillRule(FillRule),
+    SetFillAlpha(f64),
+    SetFillStyle(Style),
+
+    SetStrokeAlpha(f64),
+    SetStrokeStyle(Style),
+    SetStrokeWidth(f64),
+    SetStrokeMiterLimit(f64),
+    SetStrokeJoin(StrokeJoin),
+    SetStrokeCap(StrokeCapPosition, StrokeCap),
+    SetStrokeDashOffset(f64),
+    SetStrokeDashArray(Vec<f64>),
+    SetStrokeTransformOrder(StrokeTransformOrder),
+
+    SetMatrix(Matrix2D),
+    ResetMatrix,
+    Translate(f64, f64),
+    Scale(f64, f64),
+    Skew(f64, f64),
+    Rotate(f64),
+    RotateAround(f64, f64, f64),
+    Transform(Matrix2D),
+    PostTranslate(f64, f64),
+    PostScale(f64, f64),
+    PostSkew(f64, f64),
+    PostRotate(f64),
+    PostRotateAround(f64, f64, f64),
+    PostTransform(Matrix2D),
+
+    RestoreClipping,
+    ClipToRect(RectD),
+    ClipToPath(Path, FillRule),
+    ClipToGeometry(DisplayGeometry),
+    ClipToMask(Image),
+
+    ClearAll,
+    ClearRect(RectD),
+    BlitImage {
+        dst: PointD,
+        src: Image,
+        src_area: Option<RectI>,
+    },
+    BlitScaledImage {
+        dst: RectD,
+        src: Image,
+        src_area: Option<RectI>,
+    },
+
+    FillAll,
+    FillGeometry(DisplayGeometry),
+    FillUtf8Text {
+        dst: PointD,
+        font: Font,
+        text: String,
+    },
+    StrokeGeometry(DisplayGeometry),
+    StrokeUtf8Text {
+        dst: PointD,
+        font: Font,
+        text: String,
+    },
+}
+
+impl DisplayCommand {
+    /// Executes this command on the given context.
+    pub fn replay(&self, ctx: &mut Context<'_>) -> Result<()> {
+        use DisplayCommand::*;
+        match self {
+            Save => ctx.save(),
+            Restore => ctx.restore()?,
+            UserToMeta => ctx.user_to_meta(),
+            SetHint(hint, value) => ctx.set_hint(*hint, *value),
+            SetFlattenMode(mode) => ctx.set_flatten_mode(*mode),
+            SetFlattenTolerance(tolerance) => ctx.set_flatten_tolerance(*tolerance),
+            SetCompOp(comp_op) => ctx.set_comp_op(*comp_op),
+            SetGlobalAlpha(alpha) => ctx.set_global_alpha(*alpha),
+
+            SetFillRule(rule) => ctx.set_fill_rule(*rule),
+            SetFillAlpha(alpha) => ctx.set_fill_alpha(*alpha),
+            SetFillStyle(style) => ctx.set_fill_style(style)?,
+
+            SetStrokeAlpha(alpha) => ctx.set_stroke_alpha(*alpha),
+            SetStrokeStyle(style) => ctx.set_stroke_style(style)?,
+            SetStrokeWidth(width) => ctx.set_stroke_width(*width),
+            SetStrokeMiterLimit(limit) => ctx.set_stroke_miter_limit(*limit),
+            SetStrokeJoin(join) => ctx.set_stroke_join(*join),
+            SetStrokeCap(pos, cap) => ctx.set_stroke_cap(*pos, *cap),
+            SetStrokeDashOffset(offset) => ctx.set_stroke_dash_offset(*offset),
+            SetStrokeDashArray(dashes) => ctx.set_stroke_dash_array(&Array::from(dashes.clone())),
+            SetStrokeTransformOrder(order) => ctx.set_stroke_transform_order(*order),
+
+            SetMatrix(m) => ctx.set_matrix(m),
+            ResetMatrix => ctx.reset_matrix(),
+            Translate(x, y) => ctx.translate(*x, *y),
+            Scale(x, y) => ctx.scale(*x, *y),
+            Skew(x, y) => ctx.skew(*x, *y),
+            Rotate(angle) => ctx.rotate(*angle),
+            RotateAround(angle, x, y) => ctx.rotate_around(*angle, *x, *y),
+            Transform(m) => ctx.transform(m),
+            PostTranslate(x, y) => ctx.post_translate(*x, *y),
+            PostScale(x, y) => ctx.post_scale(*x, *y),
+            PostSkew(x, y) => ctx.post_skew(*x, *y),
+            PostRotate(angle) => ctx.post_rotate(*angle),
+            PostRotateAround(angle, x, y) => ctx.post_rotate_around(*angle, *x, *y),
+            PostTransform(m) => ctx.post_transform(m),
+
+            RestoreClipping => ctx.restore_clipping(),
+            ClipToRect(rect) => ctx.clip_to_rect(rect),
+            ClipToPath(path, fill_rule) => ctx.clip_to_path(path, *fill_rule)?,
+            ClipToGeometry(geo) => geo.clip(ctx)?,
+            ClipToMask(mask) => ctx.clip_to_mask(mask)?,
+
+            ClearAll => ctx.clear_all()?,
+            ClearRect(rect) => ctx.clear_rect(rect)?,
+            BlitImage { dst, src, src_area } => ctx.blit_image(dst, src, src_area.as_ref())?,
+            BlitScaledImage { dst, src, src_area } => {
+                ctx.blit_scaled_image(dst, src, src_area.as_ref())?
+            },
+
+            FillAll => ctx.fill_all()?,
+            FillGeometry(geo) => geo.fill(ctx)?,
+            FillUtf8Text { dst, font, text } => ctx.fill_utf8_text(*dst, font, text)?,
+            StrokeGeometry(geo) => geo.stroke(ctx)?,
+            StrokeUtf8Text { dst, font, text } => ctx.stroke_utf8_text(*dst, font, text)?,
+        }
+        Ok(())
+    }
+}
+
+/// A recorder for rendering commands.
+///
+/// A `DisplayList` mirrors the rendering API of [`Context`], but every call
+/// only appends a [`DisplayCommand`] which can later be executed on a
+/// [`Context`] with [`replay`](#method.replay). All arguments are copied or
+/// cloned into the list, so it does not borrow anything it was given.
+///
+/// As nothing is rendered while recording, the recording methods cannot
+/// fail; errors are reported by [`replay`](#method.replay) instead.
+/// Context-specific state like [`ContextCookie`]s and glyph runs, which
+/// borrow their [`GlyphBuffer`], cannot be recorded.
+///
+/// With the `serde` feature enabled a `DisplayList` implements `Serialize`
+/// and `Deserialize`. Fonts are serialized along with the tables of their
+/// face, so a deserialized list renders the same text without the original
+/// font file.
+///
+/// [`Context`]: ../context/struct.Context.html
+/// [`ContextCookie`]: ../context/struct.ContextCookie.html
+/// [`GlyphBuffer`]: ../glyph_buffer/struct.GlyphBuffer.html
+#[derive(Clone, Debug, Default, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct DisplayList {
+    commands: Vec<DisplayCommand>,
+}
+
+impl DisplayList {
+    /// Creates a new, empty display list.
+    #[inline]
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Replays all recorded commands on the given context in order.
+    ///
+    /// Replaying stops at the first command that fails.
+    pub fn replay(&self, ctx: &mut Context<'_>) -> Result<()> {
+        self.commands.iter().try_for_each(|cmd| cmd.replay(ctx))
+    }
+
+    /// The recorded commands.
+    #[inline]
+    pub fn commands(&self) -> &[DisplayCommand] {
+        &self.commands
+    }
+
+    /// Returns an iterator over the recorded commands.
+    #[inline]
+    pub fn iter(&self) -> slice::Iter<'_, DisplayCommand> {
+        self.commands.iter()
+    }
+
+    /// The number of recorded commands.
+    #[inline]
+    pub fn len(&self) -> usize {
+        self.commands.len()
+    }
+
+    /// Returns true if no commands have been recorded.
+    #[inline]
+    pub fn is_empty(&self) -> bool {
+        self.commands.is_empty()
+    }
+
+    /// Removes all recorded commands.
+    #[inline]
+    pub fn reset(&mut self) {
+        self.commands.clear()
+    }
+
+    /// Appends a command to the list.
+    #[inline]
+    pub fn push(&mut self, cmd: DisplayCommand) {
+        self.commands.push(cmd)
+    }
+}
+
+/// State Operations
+impl DisplayList {
+    #[inline]
+    pub fn save(&mut self) {
+        self.push(DisplayCommand::Save);
+    }
+
+    #[inline]
+    pub fn restore(&mut self) {
+        self.push(DisplayCommand::Restore);
+    }
+
+    /// Records the commands of `f` enclosed by a save and restore.
+    pub fn with_pushed_context<F>(&mut self, f: F)
+    where
+        F: FnOnce(&mut Self),
+    {
+        self.save();
+        f(self);
+        self.restore();
+    }
+
+    #[inline]
+    pub fn user_to_meta(&mut self) {
+        self.push(DisplayCommand::UserToMeta);
+    }
+
+    #[inline]
+    pub fn set_hint(&mut self, hint: ContextHint, value: u32) {
+        self.push(DisplayCommand::SetHint(hint, value));
+    }
+
+    #[inline]
+    pub fn set_flatten_mode(&mut self, mode: FlattenMode) {
+        self.push(DisplayCommand::SetFlattenMode(mode));
+    }
+
+    #[inline]
+    pub fn set_flatten_tolerance(&mut self, tolerance: f64) {
+        self.push(DisplayCommand::SetFlattenTolerance(tolerance));
+    }
+
+    #[inline]
+    pub fn set_comp_op(&mut self, comp_op: CompOp) {
+        self.push(DisplayCommand::SetCompOp(comp_op));
+    }
+
+    #[inline]
+    pub fn set_global_alpha(&mut self, alpha: f64) {
+        self.push(DisplayCommand::SetGlobalAlpha(alpha));
+    }
+}
+
+/// Fill Style Operations
+impl DisplayList {
+    #[inline]
+    pub fn set_fill_rule(&mut self, rule: FillRule) {
+        self.push(DisplayCommand::SetFillRule(rule));
+    }
+
+    #[inline]
+    pub fn set_fill_alpha(&mut self, alpha: f64) {
+        self.push(DisplayCommand::SetFillAlpha(alpha));
+    }
+
+    /// Records a fill style.
+    ///
+    /// [`Style::None`] is replayed as a transparent solid color, see
+    /// [`Context::set_fill_style`].
+    ///
+    /// [`Style::None`]: ../style/enum.Style.html#variant.None
+    /// [`Context::set_fill_style`]: ../context/struct.Context.html#method.set_fill_style
+    #[inline]
+    pub fn set_fill_style<S: Into<Style>>(&mut self, style: S) {
+        self.push(DisplayCommand::SetFillStyle(style.into()));
+    }
+
+    #[inline]
+    pub fn set_fill_style_gradient<G>(&mut self, gradient: &G)
+    where
+        G: Clone + Into<DynamicGradient>,
+    {
+        self.set_fill_style(gradient.clone().into());
+    }
+
+    #[inline]
+    pub fn set_fill_style_pattern(&mut self, pattern: &Pattern) {
+        self.set_fill_style(pattern);
+    }
+
+    #[inline]
+    pub fn set_fill_style_rgba32<C: Into<Rgba32>>(&mut self, color: C) {
+        let color: Rgba32 = color.into();
+        self.set_fill_style(color);
+    }
+
+    #[inline]
+    pub fn set_fill_style_rgba64<C: Into<Rgba64>>(&mut self, color: C) {
+        let color: Rgba64 = color.into();
+        self.set_fill_style(color);
+    }
+}
+
+/// Stroke Style Operations
+impl DisplayList {
+    #[inline]
+    pub fn set_stroke_alpha(&mut self, alpha: f64) {
+        self.push(DisplayCommand::SetStrokeAlpha(alpha));
+    }
+
+    /// Records a stroke style.
+    ///
+    /// [`Style::None`] is replayed as a transparent solid color, see
+    /// [`Context::set_stroke_style`].
+    ///
+    /// [`Style::None`]: ../style/enum.Style.html#variant.None
+    /// [`Context::set_stroke_style`]: ../context/struct.Context.html#method.set_stroke_style
+    #[inline]
+    pub fn set_stroke_style<S: Into<Style>>(&mut self, style: S) {
+        self.push(DisplayCommand::SetStrokeStyle(style.into()));
+    }
+
+    #[inline]
+    pub fn set_stroke_style_gradient<G>(&mut self, gradient: &G)
+    where
+        G: Clone + Into<DynamicGradient>,
+    {
+        self.set_stroke_style(gradient.clone().into());
+    }
+
+    #[inline]
+    pub fn set_stroke_style_pattern(&mut self, pattern: &Pattern) {
+        self.set_stroke_style(pattern);
+    }
+
+    #[inline]
+    pub fn set_stroke_style_rgba32<C: Into<Rgba32>>(&mut self, color: C) {
+        let color: Rgba32 = color.into();
+        self.set_stroke_style(color);
+    }
+
+    #[inline]
+    pub fn set_stroke_style_rgba64<C: Into<Rgba64>>(&mut self, color: C) {
+        let color: Rgba64 = color.into();
+        self.set_stroke_style(color);
+    }
+
+    #[inline]
+    pub fn set_stroke_width(&mut self, width: f64) {
+        self.push(DisplayCommand::SetStrokeWidth(width));
+    }
+
+    #[inline]
+    pub fn set_stroke_miter_limit(&mut self, limit: f64) {
+        self.push(DisplayCommand::SetStrokeMiterLimit(limit));
+    }
+
+    #[inline]
+    pub fn set_stroke_join(&mut self, join: StrokeJoin) {
+        self.push(DisplayCommand::SetStrokeJoin(join));
+    }
+
+    #[inline]
+    pub fn set_stroke_cap(&mut self, pos: StrokeCapPosition, cap: StrokeCap) {
+        self.push(DisplayCommand::SetStrokeCap(pos, cap));
+    }
+
+    #[inline]
+    pub fn set_stroke_start_cap(&mut self, cap: StrokeCap) {
+        self.set_stroke_cap(StrokeCapPosition::Start, cap);
+    }
+
+    #[inline]
+    pub fn set_stroke_end_cap(&mut self, cap: StrokeCap) {
+        self.set_stroke_cap(StrokeCapPosition::End, cap);
+    }
+
+    #[inline]
+    pub fn set_stroke_caps(&mut self, cap: StrokeCap) {
+        self.set_stroke_start_cap(cap);
+        self.set_stroke_end_cap(cap);
+    }
+
+    #[inline]
+    pub fn set_stroke_dash_offset(&mut self, offset: f64) {
+        self.push(DisplayCommand::SetStrokeDashOffset(offset));
+    }
+
+    #[inline]
+    pub fn set_stroke_dash_array(&mut self, dash_array: &Array<f64>) {
+        self.push(DisplayCommand::SetStrokeDashArray(dash_array.to_vec()));
+    }
+
+    #[inline]
+    pub fn set_stroke_transform_order(&mut self, order: StrokeTransformOrder) {
+        self.push(DisplayCommand::SetStrokeTransformOrder(order));
+    }
+
+    /// Records all options of the given [`StrokeOptions`] individually.
+    pub fn set_stroke_options(&mut self, opts: &StrokeOptions) {
+        self.set_stroke_width(opts.width());
+        self.set_stroke_miter_limit(opts.miter_limit());
+        self.set_stroke_join(opts.join());
+        self.set_stroke_start_cap(opts.start_cap());
+        self.set_stroke_end_cap(opts.end_cap());
+        self.set_stroke_dash_offset(opts.dash_offset());
+        self.set_stroke_dash_array(opts.dash_array());
+        self.set_stroke_transform_order(opts.transform_order());
+    }
+}
+
+/// Clip Operations
+impl DisplayList {
+    #[inline]
+    pub fn restore_clipping(&mut self) {
+        self.push(DisplayCommand::RestoreClipping);
+    }
+
+    #[inline]
+    pub fn clip_to_rect<R: Rect>(&mut self, rect: &R) {
+        let [x, y, w, h] = rect.to_f64();
+        self.clip_to(x, y, w, h);
+    }
+
+    #[inline]
+    pub fn clip_to(&mut self, x: f64, y: f64, w: f64, h: f64) {
+        self.push(DisplayCommand::ClipToRect(RectD { x, y, w, h }));
+    }
+
+    /// Records a clip to the given [`Path`].
+    ///
+    /// See [`Context::clip_to_path`] for details.
+    ///
+    /// [`Context::clip_to_path`]: ../context/struct.Context.html#method.clip_to_path
+    #[inline]
+    pub fn clip_to_path(&mut self, path: &Path, fill_rule: FillRule) {
+        self.push(DisplayCommand::ClipToPath(path.clone(), fill_rule));
+    }
+
+    /// Records a clip to the given geometry.
+    ///
+    /// See [`Context::clip_to_path`] for details.
+    ///
+    /// [`Context::clip_to_path`]: ../context/struct.Context.html#method.clip_to_path
+    #[inline]
+    pub fn clip_to_geometry<G: Into<DisplayGeometry>>(&mut self, geometry: G) {
+        self.push(DisplayCommand::ClipToGeometry(geometry.into()));
+    }
+
+    /// Records a clip to the given `A8` mask.
+    ///
+    /// See [`Context::clip_to_mask`] for details.
+    ///
+    /// [`Context::clip_to_mask`]: ../context/struct.Context.html#method.clip_to_mask
+    #[inline]
+    pub fn clip_to_mask(&mut self, mask: &Image) {
+        self.push(DisplayCommand::ClipToMask(mask.clone()));
+    }
+}
+
+/// Clear Operations
+impl DisplayList {
+    #[inline]
+    pub fn clear_all(&mut self) {
+        self.push(DisplayCommand::ClearAll);
+    }
+
+    #[inline]
+    pub fn clear_rect<R: Rect>(&mut self, rect: &R) {
+        let [x, y, w, h] = rect.to_f64();
+        self.clear(x, y, w, h);
+    }
+
+    #[inline]
+    pub fn clear(&mut self, x: f64, y: f64, w: f64, h: f64) {
+        self.push(DisplayCommand::ClearRect(RectD { x, y, w, h }));
+    }
+
+    /// Records the commands that make up [`Context::clear_to_color`].
+    ///
+    /// [`Context::clear_to_color`]: ../context/struct.Context.html#method.clear_to_color
+    pub fn clear_to_color<C: Into<Color>>(&mut self, color: C) {
+        let style = Style::solid(color);
+        self.with_pushed_context(|list| {
+            list.set_comp_op(CompOp::SrcCopy);
+            list.set_global_alpha(1.0);
+            list.set_fill_alpha(1.0);
+            list.set_fill_style(style);
+            list.fill_all();
+        });
+    }
+
+    pub fn blit_image<'r, P, RI>(&mut self, dst: &P, src: &Image, src_area: RI)
+    where
+        P: Point,
+        RI: Into<Option<&'r RectI>>,
+    {
+        let [x, y] = dst.into_f64();
+        self.push(DisplayCommand::BlitImage {
+            dst: PointD { x, y },
+            src: src.clone(),
+            src_area: src_area.into().copied(),
+        });
+    }
+
+    pub fn blit_scaled_image<'r, R, RI>(&mut self, dst: &R, src: &Image, src_area: RI)
+    where
+        R: Rect,
+        RI: Into<Option<&'r RectI>>,
+    {
+        let [x, y, w, h] = dst.to_f64();
+        self.push(DisplayCommand::BlitScaledImage {
+            dst: RectD { x, y, w, h },
+            src: src.clone(),
+            src_area: src_area.into().copied(),
+        });
+    }
+}
+
+/// Fill Operations
+impl DisplayList {
+    #[inline]
+    pub fn fill_geometry<G: Into<DisplayGeometry>>(&mut self, geo: G) {
+        self.push(DisplayCommand::FillGeometry(geo.into()));
+    }
+
+    #[inline]
+    pub fn fill_all(&mut self) {
+        self.push(DisplayCommand::FillAll);
+    }
+
+    #[inline]
+    pub fn fill_box(&mut self, x0: f64, y0: f64, x1: f64, y1: f64) {
+        self.fill_geometry(&BoxD { x0, y0, x1, y1 })
+    }
+
+    #[inline]
+    pub fn fill_rect(&mut self, x: f64, y: f64, w: f64, h: f64) {
+        self.fill_geometry(&RectD { x, y, w, h })
+    }
+
+    #[inline]
+    pub fn fill_circle(&mut self, cx: f64, cy: f64, r: f64) {
+        self.fill_geometry(&Circle { cx, cy, r })
+    }
+
+    #[inline]
+    pub fn fill_ellipse(&mut self, cx: f64, cy: f64, rx: f64, ry: f64) {
+        self.fill_geometry(&Ellipse { cx, cy, rx, ry })
+    }
+
+    #[inline]
+    #[rustfmt::skip]
+    pub fn fill_round_rect(&mut self, x: f64, y: f64, w: f64, h: f64, rx: f64, ry: f64) {
+        self.fill_geometry(&RoundRect { x, y, w, h, rx, ry })
+    }
+
+    #[inline]
+    #[rustfmt::skip]
+    pub fn fill_arc(&mut self, cx: f64, cy: f64, rx: f64, ry: f64, start: f64, sweep: f64) {
+        self.fill_geometry(&Arc { cx, cy, rx, ry, start, sweep })
+    }
+
+    #[inline]
+    #[rustfmt::skip]
+    pub fn fill_chord(&mut self, cx: f64, cy: f64, rx: f64, ry: f64, start: f64, sweep: f64) {
+        self.fill_geometry(&Chord { cx, cy, rx, ry, start, sweep })
+    }
+
+    #[inline]
+    #[rustfmt::skip]
+    pub fn fill_pie(&mut self, cx: f64, cy: f64, rx: f64, ry: f64, start: f64, sweep: f64) {
+        self.fill_geometry(&Pie { cx, cy, rx, ry, start, sweep })
+    }
+
+    #[inline]
+    #[rustfmt::skip]
+    pub fn fill_triangle(&mut self, x0: f64, y0: f64, x1: f64, y1: f64, x2: f64, y2: f64) {
+        self.fill_geometry(&Triangle { x0, y0, x1, y1, x2, y2 })
+    }
+
+    #[inline]
+    pub fn fill_path(&mut self, p: &Path) {
+        self.fill_geometry(p)
+    }
+
+    #[inline]
+    pub fn fill_polygon<R, P>(&mut self, poly: R)
+    where
+        R: AsRef<[P]>,
+        P: Point,
+    {
+        self.fill_geometry(DisplayGeometry::PolygonD(to_points(poly.as_ref())))
+    }
+
+    #[inline]
+    pub fn fill_slice<'r, P>(&mut self, slice: &'r [P])
+    where
+        &'r [P]: Into<DisplayGeometry>,
+        P: GeoViewArray,
+    {
+        self.fill_geometry(slice)
+    }
+
+    #[inline]
+    pub fn fill_utf8_text<P: Point>(&mut self, dst: P, font: &Font, text: &str) {
+        let [x, y] = dst.into_f64();
+        self.push(DisplayCommand::FillUtf8Text {
+            dst: PointD { x, y },
+            font: font.clone(),
+            text: text.to_owned(),
+        });
+    }
+}
+
+/// Stroke Operations
+impl DisplayList {
+    #[inline]
+    pub fn stroke_geometry<G: Into<DisplayGeometry>>(&mut self, geo: G) {
+        self.push(DisplayCommand::StrokeGeometry(geo.into()));
+    }
+
+    #[inline]
+    pub fn stroke_box(&mut self, x0: f64, y0: f64, x1: f64, y1: f64) {
+        self.stroke_geometry(&BoxD { x0, y0, x1, y1 })
+    }
+
+    #[inline]
+    pub fn stroke_rect(&mut self, x: f64, y: f64, w: f64, h: f64) {
+        self.stroke_geometry(&RectD { x, y, w, h })
+    }
+
+    #[inline]
+    pub fn stroke_line(&mut self, x0: f64, y0: f64, x1: f64, y1: f64) {
+        self.stroke_geometry(&Line { x0, y0, x1, y1 })
+    }
+
+    #[inline]
+    pub fn stroke_circle(&mut self, cx: f64, cy: f64, r: f64) {
+        self.stroke_geometry(&Circle { cx, cy, r })
+    }
+
+    #[inline]
+    pub fn stroke_ellipse(&mut self, cx: f64, cy: f64, rx: f64, ry: f64) {
+        self.stroke_geometry(&Ellipse { cx, cy, rx, ry })
+    }
+
+    #[inline]
+    #[rustfmt::skip]
+    pub fn stroke_round_rect(&mut self, x: f64, y: f64, w: f64, h: f64, rx: f64, ry: f64) {
+        self.stroke_geometry(&RoundRect { x, y, w, h, rx, ry })
+    }
+
+    #[inline]
+    #[rustfmt::skip]
+    pub fn stroke_arc(&mut self, cx: f64, cy: f64, rx: f64, ry: f64, start: f64, sweep: f64) {
+        self.stroke_geometry(&Arc { cx, cy, rx, ry, start, sweep })
+    }
+
+    #[inline]
+    #[rustfmt::skip]
+    pub fn stroke_chord(&mut self, cx: f64, cy: f64, rx: f64, ry: f64, start: f64, sweep: f64) {
+        self.stroke_geometry(&Chord { cx, cy, rx, ry, start, sweep })
+    }
+
+    #[inline]
+    #[rustfmt::skip]
+    pub fn stroke_pie(&mut self, cx: f64, cy: f64, rx: f64, ry: f64, start: f64, sweep: f64) {
+        self.stroke_geometry(&Pie { cx, cy, rx, ry, start, sweep })
+    }
+
+    #[inline]
+    #[rustfmt::skip]
+    pub fn stroke_triangle(&mut self, x0: f64, y0: f64, x1: f64, y1: f64, x2: f64, y2: f64) {
+        self.stroke_geometry(&Triangle { x0, y0, x1, y1, x2, y2 })
+    }
+
+    #[inline]
+    pub fn stroke_path(&mut self, p: &Path) {
+        self.stroke_geometry(p)
+    }
+
+    #[inline]
+    pub fn stroke_polygon<R, P>(&mut self, poly: R)
+    where
+        R: AsRef<[P]>,
+        P: Point,
+    {
+        self.stroke_geometry(DisplayGeometry::PolygonD(to_points(poly.as_ref())))
+    }
+
+    #[inline]
+    pub fn stroke_polyline<R, P>(&mut self, poly: R)
+    where
+        R: AsRef<[P]>,
+        P: Point,
+    {
+        self.stroke_geometry(DisplayGeometry::PolylineD(to_points(poly.as_ref())))
+    }
+
+    #[inline]
+    pub fn stroke_slice<'r, P>(&mut self, slice: &'r [P])
+    where
+        &'r [P]: Into<DisplayGeometry>,
+        P: GeoViewArray,
+    {
+        self.stroke_geometry(slice)
+    }
+
+    #[inline]
+    pub fn stroke_utf8_text<P: Point>(&mut self, dst: P, font: &Font, text: &str) {
+        let [x, y] = dst.into_f64();
+        self.push(DisplayCommand::StrokeUtf8Text {
+            dst: PointD { x, y },
+            font: font.clone(),
+            text: text.to_owned(),
+        });
+    }
+}
+
+fn to_points<P: Point>(poly: &[P]) -> Vec<PointD> {
+    poly.iter()
+        .map(|p| {
+            let [x, y] = p.into_f64();
+            PointD { x, y }
+        })
+        .collect()
+}
+
+impl MatrixTransform for DisplayList {
+    #[doc(hidden)]
+    fn apply_matrix_op(&mut self, op: Matrix2DOp, data: &[f64]) {
+        use DisplayCommand::*;
+        let matrix = || Matrix2D::new(data[0], data[1], data[2], data[3], data[4], data[5]);
+        let cmd = match op {
+            Matrix2DOp::Reset => ResetMatrix,
+            Matrix2DOp::Assign => SetMatrix(matrix()),
+            Matrix2DOp::Translate => Translate(data[0], data[1]),
+            Matrix2DOp::Scale => Scale(data[0], data[1]),
+            Matrix2DOp::Skew => Skew(data[0], data[1]),
+            Matrix2DOp::Rotate => Rotate(data[0]),
+            Matrix2DOp::RotatePoint => RotateAround(data[0], data[1], data[2]),
+            Matrix2DOp::Transform => Transform(matrix()),
+            Matrix2DOp::PostTranslate => PostTranslate(data[0], data[1]),
+            Matrix2DOp::PostScale => PostScale(data[0], data[1]),
+            Matrix2DOp::PostSkew => PostSkew(data[0], data[1]),
+            Matrix2DOp::PostRotate => PostRotate(data[0]),
+            Matrix2DOp::PostRotatePoint => PostRotateAround(data[0], data[1], data[2]),
+            Matrix2DOp::PostTransform => PostTransform(matrix()),
+        };
+        self.push(cmd);
+    }
+}
+
+impl<'a> IntoIterator for &'a DisplayList {
+    type Item = &'a DisplayCommand;
+    type IntoIter = slice::Iter<'a, DisplayCommand>;
+
+    #[inline]
+    fn into_iter(self) -> Self::IntoIter {
+        self.iter()
+    }
+}
+
+impl Extend<DisplayCommand> for DisplayList {
+    #[inline]
+    fn extend<I: IntoIterator<Item = DisplayCommand>>(&mut self, iter: I) {
+        self.commands.extend(iter)
+    }
+}
+
+#[cfg(test)]
+mod test_display_list {
+    use crate::context::Context;
+    use crate::display_list::{DisplayCommand, DisplayGeometry, DisplayList};
+    use crate::geometry::{Circle, PointD, RectD};
+    use crate::image::{Image, ImageFormat};
+    use crate::matrix::{Matrix2D, MatrixTransform};
+    use crate::style::Style;
+
+    fn scene() -> DisplayList {
+        let mut list = DisplayList::new();
+        list.clear_all();
+        list.set_fill_style_rgba32(0xFF00_80FF);
+        list.fill_circle(32.0, 32.0, 16.0);
+        list.with_pushed_context(|list| {
+            list.translate(8.0, 8.0);
+            list.set_stroke_style_rgba32(0xFFFF_FFFF);
+            list.set_stroke_width(2.0);
+            list.stroke_rect(0.0, 0.0, 24.0, 24.0);
+        });
+        list
+    }
+
+    #[test]
+    fn test_record_commands() {
+        let list = scene();
+        assert_eq!(
+            list.commands(),
+            &[
+                DisplayCommand::ClearAll,
+                DisplayCommand::SetFillStyle(Style::solid(0xFF00_80FF)),
+                DisplayCommand::FillGeometry(DisplayGeometry::Circle(Circle {
+                    cx: 32.0,
+                    cy: 32.0,
+                    r: 16.0
+                })),
+                DisplayCommand::Save,
+                DisplayCommand::Translate(8.0, 8.0),
+                DisplayCommand::SetStrokeStyle(Style::solid(0xFFFF_FFFF)),
+                DisplayCommand::SetStrokeWidth(2.0),
+                DisplayCommand::StrokeGeometry(DisplayGeometry::RectD(RectD {
+                    x: 0.0,
+                    y: 0.0,
+                    w: 24.0,
+                    h: 24.0
+                })),
+                DisplayCommand::Restore,
+            ][..]
+        );
+    }
+
+    #[test]
+    fn test_record_matrix_and_polygon() {
+        let mut list = DisplayList::new();
+        list.set_matrix(&Matrix2D::scaling(2.0, 3.0));
+        list.rotate_around(1.0, 2.0, 3.0);
+        list.stroke_polyline([PointD { x: 1.0, y: 2.0 }, PointD { x: 3.0, y: 4.0 }]);
+        let mut iter = list.iter();
+        assert_eq!(
+            iter.next(),
+            Some(&DisplayCommand::SetMatrix(Matrix2D::scaling(2.0, 3.0)))
+        );
+        assert_eq!(
+            iter.next(),
+            Some(&DisplayCommand::RotateAround(1.0, 2.0, 3.0))
+        );
+        assert_eq!(
+            iter.next(),
+            Some(&DisplayCommand::StrokeGeometry(DisplayGeometry::PolylineD(
+                vec![PointD { x: 1.0, y: 2.0 }, PointD { x: 3.0, y: 4.0 }]
+            )))
+        );
+        assert_eq!(iter.next(), None);
+    }
+
+    #[test]
+    fn test_replay_matches_direct_rendering() {
+        let mut expected = Image::new(64, 64, ImageFormat::PRgb32).unwrap();
+        let mut ctx = Context::new(&mut expected).unwrap();
+        ctx.clear_all().unwrap();
+        ctx.set_fill_style_rgba32(0xFF00_80FF);
+        ctx.fill_circle(32.0, 32.0, 16.0).unwrap();
+        ctx.save();
+        ctx.translate(8.0, 8.0);
+        ctx.set_stroke_style_rgba32(0xFFFF_FFFF);
+        ctx.set_stroke_width(2.0);
+        ctx.stroke_rect(0.0, 0.0, 24.0, 24.0).unwrap();
+        ctx.restore().unwrap();
+        ctx.end().unwrap();
+
+        let list = scene();
+        for _ in 0..2 {
+            let mut img = Image::new(64, 64, ImageFormat::PRgb32).unwrap();
+            let mut ctx = Context::new(&mut img).unwrap();
+            list.replay(&mut ctx).unwrap();
+            ctx.end().unwrap();
+            assert_eq!(img, expected);
+        }
+    }
+
+    #[test]
+    fn test_replay_style_none() {
+        let mut list = DisplayList::new();
+        list.clear_all();
+        list.set_fill_style(Style::None);
+        list.fill_all();
+        let mut img = Image::new(4, 4, ImageFormat::PRgb32).unwrap();
+        let mut ctx = Context::new(&mut img).unwrap();
+        list.replay(&mut ctx).unwrap();
+        ctx.end().unwrap();
+        assert!(img.data().rows().all(|row| row.iter().all(|&b| b == 0)));
+    }
+}
@@ -1,4 +1,4 @@
-use std::{error, fmt};
+use std::{error, fmt, io};
 
 use ffi::BLResultCode;
 
@@ -77,6 +77,41 @@ pub enum FontError {
     ProgramTerminated,
 }
 
+/// An error in SVG path data, returned by
+/// [`Path::from_svg_path_data`](../path/struct.Path.html#method.from_svg_path_data).
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct SvgPathError {
+    /// The byte offset into the path data at which the error was detected.
+    pub position: usize,
+    pub kind: SvgPathErrorKind,
+}
+
+/// The kind of an [`SvgPathError`].
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum SvgPathErrorKind {
+    /// The path data doesn't start with a moveto command.
+    MissingMoveTo,
+    /// The character can't appear at this position.
+    UnexpectedCharacter(char),
+    /// The path data ended in the middle of a command.
+    UnexpectedEnd,
+    /// A number is malformed or out of range.
+    InvalidNumber,
+}
+
+impl error::Error for SvgPathError {}
+impl fmt::Display for SvgPathError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self.kind {
+            SvgPathErrorKind::MissingMoveTo => f.write_str("path data must start with a moveto")?,
+            SvgPathErrorKind::UnexpectedCharacter(c) => write!(f, "unexpected character {:?}", c)?,
+            SvgPathErrorKind::UnexpectedEnd => f.write_str("unexpected end of path data")?,
+            SvgPathErrorKind::InvalidNumber => f.write_str("invalid number")?,
+        }
+        write!(f, " at position {}", self.position)
+    }
+}
+
 /// An error returned by blend2d.
 #[derive(Debug)]
 pub enum Error {
@@ -140,6 +175,7 @@ pub enum Error {
     Jpeg(JpegError),
     Font(FontError),
     InvalidGlyph,
+    SvgPath(SvgPathError),
 }
 
 pub(super) fn error_from_errcode(errcode: u32) -> Error {
@@ -226,6 +262,29 @@ pub(super) fn error_from_errcode(errcode: u32) -> Error {
     }
 }
 
+impl From<io::Error> for Error {
+    fn from(err: io::Error) -> Self {
+        match err.kind() {
+            io::ErrorKind::NotFound => Error::NoEntry,
+            io::ErrorKind::PermissionDenied => Error::AccessDenied,
+            io::ErrorKind::AlreadyExists => Error::AlreadyExists,
+            io::ErrorKind::Interrupted => Error::Interrupted,
+            io::ErrorKind::TimedOut => Error::TimedOut,
+            io::ErrorKind::BrokenPipe => Error::BrokenPipe,
+            io::ErrorKind::InvalidData => Error::InvalidData,
+            io::ErrorKind::UnexpectedEof => Error::DataTruncated,
+            _ => Error::Io,
+        }
+    }
+}
+
+impl From<SvgPathError> for Error {
+    #[inline]
+    fn from(err: SvgPathError) -> Self {
+        Error::SvgPath(err)
+    }
+}
+
 impl error::Error for Error {}
 impl fmt::Display for Error {
     fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
@@ -0,0 +1,140 @@
+//! Conversions between the geometry types and the ones of `euclid`, for any
+//! unit.
+use euclid::{Box2D, Point2D, Rect, Size2D, Transform2D, Vector2D};
+
+use crate::geometry::{BoxD, PointD, RectD, SizeD};
+use crate::matrix::Matrix2D;
+
+impl<U> From<Point2D<f64, U>> for PointD {
+    #[inline]
+    fn from(p: Point2D<f64, U>) -> Self {
+        PointD { x: p.x, y: p.y }
+    }
+}
+
+impl<U> From<PointD> for Point2D<f64, U> {
+    #[inline]
+    fn from(p: PointD) -> Self {
+        Point2D::new(p.x, p.y)
+    }
+}
+
+impl<U> From<Vector2D<f64, U>> for PointD {
+    #[inline]
+    fn from(v: Vector2D<f64, U>) -> Self {
+        PointD { x: v.x, y: v.y }
+    }
+}
+
+impl<U> From<PointD> for Vector2D<f64, U> {
+    #[inline]
+    fn from(p: PointD) -> Self {
+        Vector2D::new(p.x, p.y)
+    }
+}
+
+impl<U> From<Size2D<f64, U>> for SizeD {
+    #[inline]
+    fn from(s: Size2D<f64, U>) -> Self {
+        SizeD {
+            w: s.width,
+            h: s.height,
+        }
+    }
+}
+
+impl<U> From<SizeD> for Size2D<f64, U> {
+    #[inline]
+    fn from(s: SizeD) -> Self {
+        Size2D::new(s.w, s.h)
+    }
+}
+
+impl<U> From<Rect<f64, U>> for RectD {
+    #[inline]
+    fn from(r: Rect<f64, U>) -> Self {
+        RectD {
+            x: r.origin.x,
+            y: r.origin.y,
+            w: r.size.width,
+            h: r.size.height,
+        }
+    }
+}
+
+impl<U> From<RectD> for Rect<f64, U> {
+    #[inline]
+    fn from(r: RectD) -> Self {
+        Rect::new(Point2D::new(r.x, r.y), Size2D::new(r.w, r.h))
+    }
+}
+
+impl<U> From<Box2D<f64, U>> for BoxD {
+    #[inline]
+    fn from(b: Box2D<f64, U>) -> Self {
+        BoxD {
+            x0: b.min.x,
+            y0: b.min.y,
+            x1: b.max.x,
+            y1: b.max.y,
+        }
+    }
+}
+
+impl<U> From<BoxD> for Box2D<f64, U> {
+    #[inline]
+    fn from(b: BoxD) -> Self {
+        Box2D::new(Point2D::new(b.x0, b.y0), Point2D::new(b.x1, b.y1))
+    }
+}
+
+/// Both matrices transform row vectors, so the coefficients map one to one.
+impl<Src, Dst> From<Transform2D<f64, Src, Dst>> for Matrix2D {
+    #[inline]
+    fn from(t: Transform2D<f64, Src, Dst>) -> Self {
+        Matrix2D::new(t.m11, t.m12, t.m21, t.m22, t.m31, t.m32)
+    }
+}
+
+impl<Src, Dst> From<Matrix2D> for Transform2D<f64, Src, Dst> {
+    #[inline]
+    fn from(m: Matrix2D) -> Self {
+        let [m00, m01, m10, m11, m20, m21] = m.coefficients();
+        Transform2D::new(m00, m01, m10, m11, m20, m21)
+    }
+}
+
+#[cfg(test)]
+mod test_euclid_impls {
+    use super::*;
+    use euclid::default;
+
+    #[test]
+    fn test_rect_and_box() {
+        let rect = RectD {
+            x: 1.0,
+            y: 2.0,
+            w: 3.0,
+            h: 4.0,
+        };
+        let euclid_rect = default::Rect::from(rect);
+        assert_eq!(euclid_rect.max(), Point2D::new(4.0, 6.0));
+        assert_eq!(RectD::from(euclid_rect), rect);
+        let b = BoxD::from(euclid_rect.to_box2d());
+        assert_eq!((b.x0, b.y0, b.x1, b.y1), (1.0, 2.0, 4.0, 6.0));
+        assert_eq!(default::Box2D::from(b), euclid_rect.to_box2d());
+    }
+
+    #[test]
+    fn test_transform() {
+        let t = default::Transform2D::rotation(euclid::Angle::radians(0.5))
+            .then_translate(default::Vector2D::new(3.0, -2.0));
+        let m = Matrix2D::from(t);
+        let p = default::Point2D::new(1.5, 2.5);
+        let mapped = m.map_point(&p.into());
+        let expected = t.transform_point(p);
+        assert!((mapped.x - expected.x).abs() < 1e-12);
+        assert!((mapped.y - expected.y).abs() < 1e-12);
+        assert_eq!(default::Transform2D::from(m), t);
+    }
+}
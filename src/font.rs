@@ -7,14 +7,34 @@ pub use self::manager::FontManager;
 mod data;
 pub use self::data::FontData;
 
-use std::fmt;
+mod collection;
+pub use self::collection::{FontCollection, FontCollectionEntry};
+
+mod fallback;
+pub use self::fallback::{FallbackRun, FontFallback};
+
+mod tables;
+pub use self::tables::{
+    CmapSubtable, CmapTable, KernSubtable, KernTable, NameRecord, NameTable, Os2Table, PostTable,
+};
+
+use std::any::Any;
+use std::ffi::c_void;
+use std::panic::{self, AssertUnwindSafe};
+use std::{fmt, mem, ptr};
 
 use crate::array::Array;
 use crate::error::{errcode_to_result, Result};
 use crate::font_defs::*;
-use crate::glyph_buffer::GlyphBuffer;
+use crate::geometry::BoxI;
+use crate::glyph_buffer::{GlyphBuffer, GlyphId};
+use crate::matrix::Matrix2D;
+use crate::path::Path;
 use crate::util::cast_ref;
-use crate::variant::WrappedBlCore;
+use crate::variant::{BlVariantImpl, WrappedBlCore};
+use crate::Tag;
+
+const KERN: Tag = Tag::new(b"kern");
 
 /// Font
 #[repr(transparent)]
@@ -84,6 +104,116 @@ impl Font {
         unsafe { cast_ref(&self.impl_().variations) }
     }
 
+    /// Returns the value of the font-feature, if it has been set.
+    pub fn feature(&self, tag: Tag) -> Option<u32> {
+        self.features()
+            .iter()
+            .find(|feature| feature.tag == tag)
+            .map(|feature| feature.value)
+    }
+
+    /// Sets the value of an OpenType font-feature, like `liga` or `kern`.
+    ///
+    /// Usually a value of 0 disables and 1 enables the feature. [`shape`]
+    /// skips kerning if `kern` is 0. The other features are stored with the
+    /// font, but the bundled blend2d doesn't select the lookups it applies by
+    /// them yet.
+    ///
+    /// [`shape`]: #method.shape
+    pub fn set_feature(&mut self, tag: Tag, value: u32) -> Result<()> {
+        let features = self.features_mut()?;
+        match features.iter().position(|feature| feature.tag == tag) {
+            Some(index) => (**features)[index].value = value,
+            None => features.push(FontFeature { tag, value }),
+        }
+        Ok(())
+    }
+
+    /// Removes the font-feature, restoring its default, and returns its value.
+    pub fn remove_feature(&mut self, tag: Tag) -> Result<Option<u32>> {
+        let index = match self
+            .features()
+            .iter()
+            .position(|feature| feature.tag == tag)
+        {
+            Some(index) => index,
+            None => return Ok(None),
+        };
+        let features = self.features_mut()?;
+        let value = features[index].value;
+        features.remove(index)?;
+        Ok(Some(value))
+    }
+
+    /// Returns the value of the variation axis, if it has been set.
+    pub fn variation(&self, tag: Tag) -> Option<f32> {
+        self.variations()
+            .iter()
+            .find(|variation| variation.tag == tag)
+            .map(|variation| variation.value)
+    }
+
+    /// Sets the value of a variation axis of a variable font, like `wght`,
+    /// `wdth`, `slnt` or `opsz`.
+    ///
+    /// Like the features, the variations are stored with the font but not
+    /// applied by the bundled blend2d yet.
+    pub fn set_variation(&mut self, tag: Tag, value: f32) -> Result<()> {
+        let variations = self.variations_mut()?;
+        match variations.iter().position(|variation| variation.tag == tag) {
+            Some(index) => (**variations)[index].value = value,
+            None => variations.push(FontVariation { tag, value }),
+        }
+        Ok(())
+    }
+
+    /// Removes the variation axis, restoring its default, and returns its
+    /// value.
+    pub fn remove_variation(&mut self, tag: Tag) -> Result<Option<f32>> {
+        let index = match self
+            .variations()
+            .iter()
+            .position(|variation| variation.tag == tag)
+        {
+            Some(index) => index,
+            None => return Ok(None),
+        };
+        let variations = self.variations_mut()?;
+        let value = variations[index].value;
+        variations.remove(index)?;
+        Ok(Some(value))
+    }
+
+    fn features_mut(&mut self) -> Result<&mut Array<FontFeature>> {
+        self.make_mut()?;
+        unsafe { Ok(&mut *(&mut self.impl_mut().features as *mut _ as *mut _)) }
+    }
+
+    fn variations_mut(&mut self) -> Result<&mut Array<FontVariation>> {
+        self.make_mut()?;
+        unsafe { Ok(&mut *(&mut self.impl_mut().variations as *mut _ as *mut _)) }
+    }
+
+    /// Detaches the font from the impl it shares with its clones, so its
+    /// settings can be changed without affecting them.
+    fn make_mut(&mut self) -> Result<()> {
+        if self.impl_().ref_count() == 1 {
+            return Ok(());
+        }
+        let mut font = Self::from_core(*Self::none());
+        unsafe {
+            errcode_to_result(ffi::blFontCreateFromFace(
+                font.core_mut(),
+                self.face().core(),
+                self.size(),
+            ))?;
+        }
+        *font.features_mut()? = self.features().clone();
+        *font.variations_mut()? = self.variations().clone();
+        *self = font;
+        Ok(())
+    }
+
     /// Returns the weight of the font.
     #[inline]
     pub fn weight(&self) -> FontWeight {
@@ -130,9 +260,15 @@ impl Font {
         self.face().design_metrics()
     }
 
-    #[inline]
+    /// Maps the text of the buffer to glyphs and positions them, applying
+    /// kerning unless the `kern` feature is 0.
     pub fn shape(&self, buf: &mut GlyphBuffer) -> Result<()> {
-        unsafe { errcode_to_result(ffi::blFontShape(self.core(), &mut buf.core)) }
+        self.map_text_to_glyphs(buf)?;
+        self.position_glyphs(buf, 0)?;
+        if self.feature(KERN) != Some(0) {
+            self.apply_kerning(buf)?;
+        }
+        Ok(())
     }
 
     #[inline]
@@ -152,7 +288,18 @@ impl Font {
         }
     }
 
-    // TODO positionGlyphs
+    /// Sets the placements of the glyphs to their advances. Kerning is applied
+    /// as well if `positioning_flags` is not 0.
+    #[inline]
+    pub fn position_glyphs(&self, buf: &mut GlyphBuffer, positioning_flags: u32) -> Result<()> {
+        unsafe {
+            errcode_to_result(ffi::blFontPositionGlyphs(
+                self.core(),
+                &mut buf.core,
+                positioning_flags,
+            ))
+        }
+    }
 
     #[inline]
     pub fn apply_kerning(&self, buf: &mut GlyphBuffer) -> Result<()> {
@@ -196,13 +343,127 @@ impl Font {
         }
     }
 
-    //TODO getGlyphBounds
+    /// Returns the bounding boxes of the glyphs in design units.
+    pub fn glyph_bounds(&self, glyphs: &[GlyphId]) -> Result<Vec<BoxI>> {
+        let mut bounds = vec![BoxI::default(); glyphs.len()];
+        unsafe {
+            errcode_to_result(ffi::blFontGetGlyphBounds(
+                self.core(),
+                glyphs.as_ptr() as *const _,
+                mem::size_of::<GlyphId>() as isize,
+                bounds.as_mut_ptr() as *mut _,
+                glyphs.len(),
+            ))
+            .map(|_| bounds)
+        }
+    }
+
+    /// Returns the advances of the glyphs in design units.
+    pub fn glyph_advances(&self, glyphs: &[GlyphId]) -> Result<Vec<GlyphPlacement>> {
+        let mut advances = vec![GlyphPlacement::default(); glyphs.len()];
+        unsafe {
+            errcode_to_result(ffi::blFontGetGlyphAdvances(
+                self.core(),
+                glyphs.as_ptr() as *const _,
+                mem::size_of::<GlyphId>() as isize,
+                advances.as_mut_ptr() as *mut _,
+                glyphs.len(),
+            ))
+            .map(|_| advances)
+        }
+    }
+
+    /// Returns the outlines of the glyph, scaled to the font size and
+    /// transformed by `m`.
+    pub fn glyph_outlines(&self, glyph_id: GlyphId, m: &Matrix2D) -> Result<Path> {
+        let mut path = Path::new();
+        unsafe {
+            errcode_to_result(ffi::blFontGetGlyphOutlines(
+                self.core(),
+                u32::from(glyph_id),
+                m as *const _ as *const _,
+                path.core_mut(),
+                None,
+                ptr::null_mut(),
+            ))
+            .map(|_| path)
+        }
+    }
 
-    //TODO getGlyphAdvances
+    /// Returns the outlines of all glyphs of the glyph run, scaled to the font
+    /// size, placed along the run and transformed by `m`.
+    pub fn glyph_run_outlines(&self, glyph_run: GlyphRun<'_>, m: &Matrix2D) -> Result<Path> {
+        let mut path = Path::new();
+        unsafe {
+            errcode_to_result(ffi::blFontGetGlyphRunOutlines(
+                self.core(),
+                &glyph_run.raw,
+                m as *const _ as *const _,
+                path.core_mut(),
+                None,
+                ptr::null_mut(),
+            ))
+            .map(|_| path)
+        }
+    }
+
+    /// Appends the outlines of all glyphs of the glyph run to `path`, like
+    /// [`glyph_run_outlines`](#method.glyph_run_outlines), calling `sink`
+    /// with the path after the outlines of every glyph have been added.
+    ///
+    /// If `sink` panics, no further outlines are added and the panic is
+    /// resumed once blend2d has returned.
+    pub fn glyph_run_outlines_with_sink<F>(
+        &self,
+        glyph_run: GlyphRun<'_>,
+        m: &Matrix2D,
+        path: &mut Path,
+        sink: F,
+    ) -> Result<()>
+    where
+        F: FnMut(&mut Path, &GlyphOutlineSinkInfo),
+    {
+        struct SinkState<F> {
+            sink: F,
+            panic: Option<Box<dyn Any + Send>>,
+        }
 
-    //TODO getGlyphOutlines
+        unsafe extern "C" fn sink_callback<F>(
+            path: *mut ffi::BLPathCore,
+            info: *const c_void,
+            closure: *mut c_void,
+        ) -> ffi::BLResult
+        where
+            F: FnMut(&mut Path, &GlyphOutlineSinkInfo),
+        {
+            let state = &mut *(closure as *mut SinkState<F>);
+            let path = &mut *(path as *mut Path);
+            let info = &*(info as *const GlyphOutlineSinkInfo);
+            match panic::catch_unwind(AssertUnwindSafe(|| (state.sink)(path, info))) {
+                Ok(()) => 0,
+                Err(payload) => {
+                    state.panic = Some(payload);
+                    ffi::BLResultCode::BL_ERROR_INVALID_STATE
+                },
+            }
+        }
 
-    //TODO getGlyphRunOutlines
+        let mut state = SinkState { sink, panic: None };
+        let result = unsafe {
+            errcode_to_result(ffi::blFontGetGlyphRunOutlines(
+                self.core(),
+                &glyph_run.raw,
+                m as *const _ as *const _,
+                path.core_mut(),
+                Some(sink_callback::<F>),
+                &mut state as *mut _ as *mut _,
+            ))
+        };
+        if let Some(payload) = state.panic {
+            panic::resume_unwind(payload);
+        }
+        result
+    }
 }
 
 impl PartialEq for Font {
@@ -229,3 +490,164 @@ impl fmt::Debug for Font {
         f.debug_struct("Font").finish()
     }
 }
+
+#[cfg(test)]
+mod test_font {
+    use std::panic::{self, AssertUnwindSafe};
+
+    use super::{Font, FontFace};
+    use crate::glyph_buffer::{GlyphBuffer, GlyphId};
+    use crate::matrix::Matrix2D;
+    use crate::path::Path;
+    use crate::{DataAccessFlags, Tag};
+
+    fn font() -> Font {
+        let path = concat!(env!("CARGO_MANIFEST_DIR"), "/assets/NotoSans-Regular.ttf");
+        let face = FontFace::from_path(path, DataAccessFlags::READ).unwrap();
+        face.create_font(20.0).unwrap()
+    }
+
+    fn shape(font: &Font, text: &str) -> GlyphBuffer {
+        let mut buf = GlyphBuffer::from(text);
+        font.shape(&mut buf).unwrap();
+        buf
+    }
+
+    fn glyph_id(font: &Font, c: char) -> GlyphId {
+        shape(font, c.encode_utf8(&mut [0; 4]))
+            .glyph_ids()
+            .next()
+            .unwrap()
+    }
+
+    #[test]
+    fn test_glyph_bounds_and_outlines() {
+        let font = font();
+        let a = glyph_id(&font, 'A');
+        let space = glyph_id(&font, ' ');
+        assert_ne!(a, space);
+
+        let bounds = font.glyph_bounds(&[a, space]).unwrap();
+        assert_eq!(bounds.len(), 2);
+        assert!(bounds[0].x1 > bounds[0].x0 && bounds[0].y1 > bounds[0].y0);
+
+        let outline = font.glyph_outlines(a, &Matrix2D::identity()).unwrap();
+        assert!(!outline.is_empty());
+        // The outline is scaled to the font size.
+        let outline_box = outline.bounding_box().unwrap();
+        assert!(outline_box.x1 - outline_box.x0 < f64::from(font.size()));
+        assert!(font
+            .glyph_outlines(space, &Matrix2D::identity())
+            .unwrap()
+            .is_empty());
+    }
+
+    #[test]
+    fn test_glyph_advances() {
+        let font = font();
+        let buf = shape(&font, "Hill");
+        let glyph_ids: Vec<GlyphId> = buf.glyph_ids().collect();
+        let advances = font.glyph_advances(&glyph_ids).unwrap();
+        assert_eq!(advances.len(), glyph_ids.len());
+        for (advance, placement) in advances.iter().zip(buf.placements()) {
+            assert!(advance.advance.x > 0);
+            assert_eq!(advance.advance, placement.advance);
+        }
+    }
+
+    #[test]
+    fn test_glyph_run_outlines() {
+        let font = font();
+        let single = font
+            .glyph_outlines(glyph_id(&font, 'A'), &Matrix2D::identity())
+            .unwrap();
+        let buf = shape(&font, "AA");
+        let outlines = font
+            .glyph_run_outlines(buf.glyph_run(), &Matrix2D::identity())
+            .unwrap();
+        assert_eq!(outlines.len(), 2 * single.len());
+
+        let mut path = Path::new();
+        let mut indices = Vec::new();
+        font.glyph_run_outlines_with_sink(
+            buf.glyph_run(),
+            &Matrix2D::identity(),
+            &mut path,
+            |path, info| {
+                assert_eq!(path.len(), (info.glyph_index + 1) * single.len());
+                indices.push(info.glyph_index);
+            },
+        )
+        .unwrap();
+        assert_eq!(indices, [0, 1]);
+        assert_eq!(path, outlines);
+    }
+
+    #[test]
+    fn test_glyph_run_outlines_sink_panic() {
+        let font = font();
+        let buf = shape(&font, "AA");
+        let mut path = Path::new();
+        let mut calls = 0;
+        let result = panic::catch_unwind(AssertUnwindSafe(|| {
+            font.glyph_run_outlines_with_sink(
+                buf.glyph_run(),
+                &Matrix2D::identity(),
+                &mut path,
+                |_, _| {
+                    calls += 1;
+                    panic!("sink panicked");
+                },
+            )
+        }));
+        let payload = result.unwrap_err();
+        assert_eq!(payload.downcast_ref::<&str>(), Some(&"sink panicked"));
+        assert_eq!(calls, 1);
+    }
+
+    #[test]
+    fn test_features() {
+        let mut font = font();
+        let liga = Tag::from(b"liga");
+        assert_eq!(font.feature(liga), None);
+        let shared = font.clone();
+        font.set_feature(liga, 0).unwrap();
+        assert_eq!(font.feature(liga), Some(0));
+        // Clones keep their settings.
+        assert_eq!(shared.feature(liga), None);
+        font.set_feature(liga, 1).unwrap();
+        assert_eq!(font.features().len(), 1);
+        assert_eq!(font.remove_feature(liga).unwrap(), Some(1));
+        assert_eq!(font.remove_feature(liga).unwrap(), None);
+    }
+
+    #[test]
+    fn test_kern_feature() {
+        let mut font = font();
+        let text = "AVATAR";
+        let kerned = shape(&font, text);
+        let mut manual = GlyphBuffer::from(text);
+        font.map_text_to_glyphs(&mut manual).unwrap();
+        font.position_glyphs(&mut manual, 0).unwrap();
+        font.apply_kerning(&mut manual).unwrap();
+        assert_eq!(kerned.placements(), manual.placements());
+
+        font.set_feature(Tag::from(b"kern"), 0).unwrap();
+        let unkerned = shape(&font, text);
+        let glyph_ids: Vec<GlyphId> = unkerned.glyph_ids().collect();
+        let advances = font.glyph_advances(&glyph_ids).unwrap();
+        assert_eq!(unkerned.placements(), &advances[..]);
+    }
+
+    #[test]
+    #[ignore = "blend2d doesn't select the lookups it applies by the font features yet"]
+    fn test_liga_feature() {
+        let mut font = font();
+        font.set_feature(Tag::from(b"liga"), 1).unwrap();
+        let with_liga: Vec<GlyphId> = shape(&font, "fi").glyph_ids().collect();
+        font.set_feature(Tag::from(b"liga"), 0).unwrap();
+        let without_liga: Vec<GlyphId> = shape(&font, "fi").glyph_ids().collect();
+        assert_eq!(without_liga.len(), 2);
+        assert_ne!(with_liga, without_liga);
+    }
+}
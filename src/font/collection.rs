@@ -0,0 +1,247 @@
+use std::fs;
+use std::path::{Path, PathBuf};
+
+use crate::error::Result;
+use crate::font_defs::*;
+use crate::DataAccessFlags;
+
+use super::{FontData, FontFace};
+
+/// The file extensions of the font files picked up by
+/// [`FontCollection::add_dir`], compared case-insensitively.
+const FONT_EXTENSIONS: [&str; 4] = ["ttf", "otf", "ttc", "otc"];
+
+/// A font-face of a [`FontCollection`].
+///
+/// The entry only describes the face, its data is loaded from the file again
+/// by [`load`](#method.load).
+#[derive(Clone, Debug, PartialEq)]
+pub struct FontCollectionEntry {
+    /// The path of the font file.
+    pub path: PathBuf,
+    /// The index of the face in the font file, which is only non-zero for
+    /// font collections.
+    pub face_index: u32,
+    pub family_name: String,
+    pub subfamily_name: String,
+    pub full_name: String,
+    pub weight: FontWeight,
+    pub stretch: FontStretch,
+    pub style: FontStyle,
+    pub unicode_coverage: FontUnicodeCoverage,
+}
+
+impl FontCollectionEntry {
+    fn new(path: PathBuf, face: &FontFace) -> Self {
+        FontCollectionEntry {
+            path,
+            face_index: face.face_index(),
+            family_name: face.family_name().to_owned(),
+            subfamily_name: face.subfamily_name().to_owned(),
+            full_name: face.full_name().to_owned(),
+            weight: face.weight(),
+            stretch: face.stretch(),
+            style: face.style(),
+            unicode_coverage: *face.unicode_coverage(),
+        }
+    }
+
+    /// Loads the font-face from its file.
+    pub fn load(&self) -> Result<FontFace> {
+        let data = FontData::from_path(&self.path, DataAccessFlags::READ)?;
+        FontFace::from_data(&data, self.face_index)
+    }
+}
+
+/// An index of the font-faces of font files.
+///
+/// Only the names, properties and unicode coverage of every face are kept
+/// in memory, a face is loaded when it is needed by
+/// [`FontCollectionEntry::load`].
+#[derive(Clone, Debug, Default)]
+pub struct FontCollection {
+    entries: Vec<FontCollectionEntry>,
+}
+
+impl FontCollection {
+    /// Creates a new, empty font collection.
+    #[inline]
+    pub fn new() -> Self {
+        FontCollection {
+            entries: Vec::new(),
+        }
+    }
+
+    /// Creates a font collection of the font files in the directory and its
+    /// subdirectories.
+    ///
+    /// See [`add_dir`](#method.add_dir).
+    pub fn scan_dir<P: AsRef<Path>>(path: P) -> Result<Self> {
+        let mut this = Self::new();
+        this.add_dir(path)?;
+        Ok(this)
+    }
+
+    /// Adds the faces of all `.ttf`, `.otf`, `.ttc` and `.otc` files in the
+    /// directory and its subdirectories.
+    ///
+    /// Files that can't be loaded as fonts are skipped, only failing to read
+    /// a directory is an error. Symbolic links to directories are not
+    /// followed.
+    pub fn add_dir<P: AsRef<Path>>(&mut self, path: P) -> Result<()> {
+        let mut entries = fs::read_dir(path)?.collect::<std::io::Result<Vec<_>>>()?;
+        entries.sort_by_key(|entry| entry.path());
+        for entry in entries {
+            let path = entry.path();
+            if entry.file_type()?.is_dir() {
+                self.add_dir(&path)?;
+            } else if is_font_file(&path) {
+                let _ = self.add_file(&path);
+            }
+        }
+        Ok(())
+    }
+
+    /// Adds the faces of the font file.
+    pub fn add_file<P: AsRef<Path>>(&mut self, path: P) -> Result<()> {
+        let path = path.as_ref();
+        let data = FontData::from_path(path, DataAccessFlags::READ)?;
+        let faces = (0..data.face_count())
+            .map(|index| FontFace::from_data(&data, index))
+            .collect::<Result<Vec<_>>>()?;
+        self.entries.extend(
+            faces
+                .iter()
+                .map(|face| FontCollectionEntry::new(path.to_owned(), face)),
+        );
+        Ok(())
+    }
+
+    /// Returns the faces of this collection, ordered by their path.
+    #[inline]
+    pub fn entries(&self) -> &[FontCollectionEntry] {
+        &self.entries
+    }
+
+    /// Returns the sorted and deduplicated family names of the faces.
+    pub fn family_names(&self) -> Vec<&str> {
+        let mut names: Vec<&str> = self
+            .entries
+            .iter()
+            .map(|entry| entry.family_name.as_str())
+            .collect();
+        names.sort_unstable();
+        names.dedup();
+        names
+    }
+
+    /// Returns the faces of the family, the name is compared
+    /// case-insensitively.
+    pub fn query_family<'a>(
+        &'a self,
+        family_name: &'a str,
+    ) -> impl Iterator<Item = &'a FontCollectionEntry> + 'a {
+        self.entries
+            .iter()
+            .filter(move |entry| entry.family_name.eq_ignore_ascii_case(family_name))
+    }
+
+    /// Returns the face of the family with the subfamily name, like
+    /// "Bold Italic". Both names are compared case-insensitively.
+    pub fn query_face(
+        &self,
+        family_name: &str,
+        subfamily_name: &str,
+    ) -> Option<&FontCollectionEntry> {
+        self.entries.iter().find(|entry| {
+            entry.family_name.eq_ignore_ascii_case(family_name)
+                && entry.subfamily_name.eq_ignore_ascii_case(subfamily_name)
+        })
+    }
+
+    /// Returns the faces covering the unicode range with the given bit index
+    /// of the OS/2 table.
+    ///
+    /// See [`FontUnicodeCoverage::has_range`].
+    pub fn query_coverage(&self, bit: u32) -> impl Iterator<Item = &FontCollectionEntry> {
+        self.entries
+            .iter()
+            .filter(move |entry| entry.unicode_coverage.has_range(bit))
+    }
+}
+
+fn is_font_file(path: &Path) -> bool {
+    path.is_file()
+        && path
+            .extension()
+            .and_then(|ext| ext.to_str())
+            .is_some_and(|ext| {
+                FONT_EXTENSIONS
+                    .iter()
+                    .any(|font_ext| ext.eq_ignore_ascii_case(font_ext))
+            })
+}
+
+#[cfg(test)]
+mod test_font_collection {
+    use std::fs;
+    use std::path::PathBuf;
+
+    use crate::font::{FontCollection, FontCollectionEntry};
+    use crate::font_defs::*;
+
+    fn entry(family_name: &str, subfamily_name: &str, coverage: u32) -> FontCollectionEntry {
+        FontCollectionEntry {
+            path: PathBuf::from(format!("{} {}.ttf", family_name, subfamily_name)),
+            face_index: 0,
+            family_name: family_name.to_owned(),
+            subfamily_name: subfamily_name.to_owned(),
+            full_name: format!("{} {}", family_name, subfamily_name),
+            weight: FontWeight::Normal,
+            stretch: FontStretch::Normal,
+            style: FontStyle::Normal,
+            unicode_coverage: FontUnicodeCoverage {
+                data: [coverage, 0, 0, 0],
+            },
+        }
+    }
+
+    #[test]
+    fn test_queries() {
+        let collection = FontCollection {
+            entries: vec![
+                entry("Noto Sans", "Regular", 0b01),
+                entry("Noto Sans", "Bold", 0b01),
+                entry("DejaVu Serif", "Regular", 0b11),
+            ],
+        };
+        assert_eq!(collection.family_names(), ["DejaVu Serif", "Noto Sans"]);
+        assert_eq!(collection.query_family("noto sans").count(), 2);
+        assert_eq!(
+            collection
+                .query_face("Noto Sans", "bold")
+                .map(|entry| entry.full_name.as_str()),
+            Some("Noto Sans Bold")
+        );
+        assert!(collection.query_face("Noto Sans", "Italic").is_none());
+        assert_eq!(collection.query_coverage(0).count(), 3);
+        let covering: Vec<_> = collection
+            .query_coverage(1)
+            .map(|entry| entry.family_name.as_str())
+            .collect();
+        assert_eq!(covering, ["DejaVu Serif"]);
+        assert_eq!(collection.query_coverage(128).count(), 0);
+    }
+
+    #[test]
+    fn test_scan_dir_skips_other_files() {
+        let dir = std::env::temp_dir().join(format!("blend2d-scan-dir-{}", std::process::id()));
+        fs::create_dir_all(dir.join("sub")).unwrap();
+        fs::write(dir.join("readme.txt"), "not a font").unwrap();
+        fs::write(dir.join("sub").join("font.ttf.bak"), "not a font").unwrap();
+        let collection = FontCollection::scan_dir(&dir);
+        fs::remove_dir_all(&dir).unwrap();
+        assert!(collection.unwrap().entries().is_empty());
+        assert!(FontCollection::scan_dir(&dir).is_err());
+    }
+}
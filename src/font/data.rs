@@ -100,12 +100,12 @@ impl FontData {
                 tags.as_ptr() as *const _ as *const _,
                 tags.len(),
             );
-            (
-                FontTable {
-                    data: slice::from_raw_parts(dst.data, dst.size),
-                },
-                n,
-            )
+            let data = if dst.data.is_null() {
+                &[]
+            } else {
+                slice::from_raw_parts(dst.data, dst.size)
+            };
+            (FontTable { data }, n)
         }
     }
 
@@ -5,8 +5,9 @@ use crate::error::{errcode_to_result, Result};
 use crate::font_defs::*;
 use crate::util::cast_ref;
 use crate::variant::WrappedBlCore;
-use crate::DataAccessFlags;
+use crate::{DataAccessFlags, Tag};
 
+use super::tables::{CmapTable, KernTable, NameTable, Os2Table, PostTable};
 use super::Font;
 use super::FontData;
 
@@ -250,7 +251,12 @@ impl FontFace {
         self.design_metrics().units_per_em
     }
 
-    // TODO panose
+    /// Returns the PANOSE classification, which is empty unless
+    /// [`has_panose_data`](#method.has_panose_data) is true.
+    #[inline]
+    pub fn panose(&self) -> &FontPanose {
+        unsafe { cast_ref(&self.impl_().panose) }
+    }
 
     #[inline]
     pub fn unicode_coverage(&self) -> &FontUnicodeCoverage {
@@ -280,6 +286,45 @@ impl FontFace {
     pub fn post_script_name(&self) -> &str {
         bl_string_to_str(&self.impl_().postScriptName)
     }
+
+    /// Returns the data of the table with the given tag, or `None` if the
+    /// face has no such table.
+    pub fn table(&self, tag: Tag) -> Option<&[u8]> {
+        match self.data().query_table(self.face_index(), tag) {
+            (table, 1) => Some(table.data),
+            _ => None,
+        }
+    }
+
+    /// Returns the parsed `name` table, or `None` if the face has no `name`
+    /// table or it is malformed.
+    pub fn name_table(&self) -> Option<NameTable<'_>> {
+        NameTable::parse(self.table(Tag::new(b"name"))?).ok()
+    }
+
+    /// Returns the parsed `OS/2` table, or `None` if the face has no `OS/2`
+    /// table or it is malformed.
+    pub fn os2_table(&self) -> Option<Os2Table> {
+        Os2Table::parse(self.table(Tag::new(b"OS/2"))?).ok()
+    }
+
+    /// Returns the parsed `cmap` table, or `None` if the face has no `cmap`
+    /// table or it is malformed.
+    pub fn cmap_table(&self) -> Option<CmapTable<'_>> {
+        CmapTable::parse(self.table(Tag::new(b"cmap"))?).ok()
+    }
+
+    /// Returns the parsed `post` table, or `None` if the face has no `post`
+    /// table or it is malformed.
+    pub fn post_table(&self) -> Option<PostTable<'_>> {
+        PostTable::parse(self.table(Tag::new(b"post"))?).ok()
+    }
+
+    /// Returns the parsed `kern` table, or `None` if the face has no `kern`
+    /// table or it is malformed.
+    pub fn kern_table(&self) -> Option<KernTable<'_>> {
+        KernTable::parse(self.table(Tag::new(b"kern"))?).ok()
+    }
 }
 
 #[inline]
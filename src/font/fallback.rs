@@ -0,0 +1,276 @@
+use std::iter::{self, FromIterator};
+use std::ops::Range;
+
+use crate::error::Result;
+use crate::glyph_buffer::GlyphBuffer;
+
+use super::Font;
+
+/// A run of text that is rendered with a single font of a [`FontFallback`].
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct FallbackRun {
+    /// The byte range of the run in the text.
+    pub range: Range<usize>,
+    /// The index of the font in the fallback chain.
+    pub font_index: usize,
+}
+
+/// A chain of fonts, in which every character is rendered with the first font
+/// that provides a glyph for it.
+///
+/// Characters that no font provides a glyph for are rendered with the first
+/// font.
+#[derive(Clone, Debug, Default)]
+pub struct FontFallback {
+    fonts: Vec<Font>,
+}
+
+impl FontFallback {
+    /// Creates a new, empty fallback chain.
+    #[inline]
+    pub fn new() -> Self {
+        FontFallback { fonts: Vec::new() }
+    }
+
+    /// Appends a font to the end of the chain.
+    #[inline]
+    pub fn push(&mut self, font: Font) {
+        self.fonts.push(font);
+    }
+
+    /// Returns the fonts of the chain.
+    #[inline]
+    pub fn fonts(&self) -> &[Font] {
+        &self.fonts
+    }
+
+    /// Splits the text into runs of characters that are rendered with the
+    /// same font.
+    ///
+    /// Returns no runs if the chain is empty.
+    pub fn itemize(&self, text: &str) -> Result<Vec<FallbackRun>> {
+        let mut buf = GlyphBuffer::new();
+        itemize(text, self.fonts.len(), &mut |font_index, text| {
+            undefined_offsets(&self.fonts[font_index], &mut buf, text)
+        })
+    }
+
+    /// Itemizes the text and shapes every run with its font.
+    pub fn shape(&self, text: &str) -> Result<Vec<(FallbackRun, GlyphBuffer)>> {
+        self.itemize(text)?
+            .into_iter()
+            .map(|run| {
+                let mut buf = GlyphBuffer::from_utf8_text(&text[run.range.clone()]);
+                self.fonts[run.font_index].shape(&mut buf)?;
+                Ok((run, buf))
+            })
+            .collect()
+    }
+}
+
+impl FromIterator<Font> for FontFallback {
+    fn from_iter<I: IntoIterator<Item = Font>>(iter: I) -> Self {
+        FontFallback {
+            fonts: iter.into_iter().collect(),
+        }
+    }
+}
+
+impl Extend<Font> for FontFallback {
+    fn extend<I: IntoIterator<Item = Font>>(&mut self, iter: I) {
+        self.fonts.extend(iter)
+    }
+}
+
+/// Returns the byte offsets of the characters of the text the font doesn't
+/// provide glyphs for.
+fn undefined_offsets(font: &Font, buf: &mut GlyphBuffer, text: &str) -> Result<Vec<usize>> {
+    buf.set_utf8_text(text);
+    font.map_text_to_glyphs(buf)?;
+    if !buf.has_undefined_chars() {
+        return Ok(Vec::new());
+    }
+    Ok(buf
+        .glyph_ids()
+        .zip(buf.clusters())
+        .filter(|&(glyph_id, _)| glyph_id == 0)
+        .map(|(_, cluster)| cluster)
+        .collect())
+}
+
+/// Assigns every character to the first of `font_count` fonts that provides
+/// a glyph for it. `undefined` returns the byte offsets of the characters of
+/// a piece of text the font with the given index doesn't provide glyphs for.
+fn itemize(
+    text: &str,
+    font_count: usize,
+    undefined: &mut dyn FnMut(usize, &str) -> Result<Vec<usize>>,
+) -> Result<Vec<FallbackRun>> {
+    if font_count == 0 || text.is_empty() {
+        return Ok(Vec::new());
+    }
+    // The font of every byte of the text.
+    let mut fonts = vec![0; text.len()];
+    let mut pending: Vec<Range<usize>> = iter::once(0..text.len()).collect();
+    for font_index in 0..font_count {
+        let mut next: Vec<Range<usize>> = Vec::new();
+        for range in pending {
+            let offsets = undefined(font_index, &text[range.clone()])?;
+            for font in &mut fonts[range.clone()] {
+                *font = font_index;
+            }
+            for offset in offsets {
+                let start = range.start + offset;
+                let end = start + text[start..].chars().next().map_or(0, char::len_utf8);
+                match next.last_mut() {
+                    Some(last) if last.end >= start => last.end = last.end.max(end),
+                    _ => next.push(start..end),
+                }
+            }
+        }
+        pending = next;
+        if pending.is_empty() {
+            break;
+        }
+    }
+    for range in pending {
+        for font in &mut fonts[range] {
+            *font = 0;
+        }
+    }
+
+    let mut runs: Vec<FallbackRun> = Vec::new();
+    for (offset, c) in text.char_indices() {
+        let font_index = fonts[offset];
+        let end = offset + c.len_utf8();
+        match runs.last_mut() {
+            Some(last) if last.font_index == font_index => last.range.end = end,
+            _ => runs.push(FallbackRun {
+                range: offset..end,
+                font_index,
+            }),
+        }
+    }
+    Ok(runs)
+}
+
+#[cfg(test)]
+mod test_fallback {
+    use super::*;
+    use crate::font::FontFace;
+    use crate::DataAccessFlags;
+
+    /// Fonts covering ASCII, Greek and Hiragana respectively.
+    fn undefined(font_index: usize, text: &str) -> Result<Vec<usize>> {
+        let defined = |c: char| match font_index {
+            0 => c.is_ascii(),
+            1 => ('\u{370}'..='\u{3FF}').contains(&c) || c == ' ',
+            _ => ('\u{3040}'..='\u{309F}').contains(&c),
+        };
+        Ok(text
+            .char_indices()
+            .filter(|&(_, c)| !defined(c))
+            .map(|(i, _)| i)
+            .collect())
+    }
+
+    fn runs(text: &str, font_count: usize) -> Vec<(&str, usize)> {
+        itemize(text, font_count, &mut undefined)
+            .unwrap()
+            .into_iter()
+            .map(|run| (&text[run.range], run.font_index))
+            .collect()
+    }
+
+    #[test]
+    fn test_itemize() {
+        assert_eq!(runs("hello", 3), [("hello", 0)]);
+        assert_eq!(
+            runs("ab αβ γ あい!", 3),
+            [
+                ("ab ", 0),
+                ("αβ", 1),
+                (" ", 0),
+                ("γ", 1),
+                (" ", 0),
+                ("あい", 2),
+                ("!", 0)
+            ]
+        );
+        // Characters without a glyph in any font use the first font.
+        assert_eq!(runs("a€αカ", 3), [("a€", 0), ("α", 1), ("カ", 0)]);
+        assert_eq!(runs("αあ", 2), [("α", 1), ("あ", 0)]);
+        assert!(runs("", 3).is_empty());
+        assert!(runs("abc", 0).is_empty());
+    }
+
+    fn noto_sans() -> Font {
+        let path = concat!(env!("CARGO_MANIFEST_DIR"), "/assets/NotoSans-Regular.ttf");
+        let face = FontFace::from_path(path, DataAccessFlags::READ).unwrap();
+        face.create_font(20.0).unwrap()
+    }
+
+    #[test]
+    fn test_undefined_offsets() {
+        let font = noto_sans();
+        let mut buf = GlyphBuffer::new();
+        assert!(undefined_offsets(&font, &mut buf, "façade")
+            .unwrap()
+            .is_empty());
+        // Noto Sans doesn't cover CJK, the offsets are counted in bytes.
+        let text = "é漢a字";
+        assert_eq!(undefined_offsets(&font, &mut buf, text).unwrap(), [2, 6]);
+
+        // The only font asset is Noto Sans, so the second font is a stand-in
+        // that covers CJK.
+        let mut undefined = |font_index: usize, text: &str| match font_index {
+            0 => undefined_offsets(&font, &mut buf, text),
+            _ => Ok(Vec::new()),
+        };
+        let runs = itemize(text, 2, &mut undefined).unwrap();
+        assert_eq!(
+            runs,
+            [
+                FallbackRun {
+                    range: 0..2,
+                    font_index: 0
+                },
+                FallbackRun {
+                    range: 2..5,
+                    font_index: 1
+                },
+                FallbackRun {
+                    range: 5..6,
+                    font_index: 0
+                },
+                FallbackRun {
+                    range: 6..9,
+                    font_index: 1
+                },
+            ]
+        );
+    }
+
+    #[test]
+    fn test_fallback_chain() {
+        let fallback: FontFallback = iter::once(noto_sans()).collect();
+        assert_eq!(
+            fallback.itemize("a漢b").unwrap(),
+            [FallbackRun {
+                range: 0..5,
+                font_index: 0
+            }]
+        );
+        let shaped = fallback.shape("a漢b").unwrap();
+        assert_eq!(shaped.len(), 1);
+        let buf = &shaped[0].1;
+        assert!(buf.has_undefined_chars());
+        let undefined: Vec<usize> = buf
+            .glyph_ids()
+            .zip(buf.clusters())
+            .filter(|&(glyph_id, _)| glyph_id == 0)
+            .map(|(_, cluster)| cluster)
+            .collect();
+        assert_eq!(undefined, [1]);
+    }
+}
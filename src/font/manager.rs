@@ -1,7 +1,12 @@
 use std::fmt;
 
+use crate::array::Array;
+use crate::error::{errcode_to_result, expect_mem_err, Result};
+use crate::font_defs::{FontStretch, FontStyle, FontWeight};
 use crate::variant::WrappedBlCore;
 
+use super::FontFace;
+
 /// Font Manager
 #[repr(transparent)]
 pub struct FontManager {
@@ -17,6 +22,95 @@ unsafe impl WrappedBlCore for FontManager {
     }
 }
 
+impl FontManager {
+    /// Creates a new, empty font manager.
+    pub fn new() -> Self {
+        let mut this = Self::from_core(*Self::none());
+        unsafe { expect_mem_err(ffi::blFontManagerCreate(this.core_mut())) };
+        this
+    }
+
+    /// Returns the number of faces in this font manager.
+    #[inline]
+    pub fn face_count(&self) -> usize {
+        unsafe { ffi::blFontManagerGetFaceCount(self.core()) }
+    }
+
+    /// Returns the number of font families in this font manager.
+    #[inline]
+    pub fn family_count(&self) -> usize {
+        unsafe { ffi::blFontManagerGetFamilyCount(self.core()) }
+    }
+
+    /// Tests whether the face has been added to this font manager.
+    #[inline]
+    pub fn has_face(&self, face: &FontFace) -> bool {
+        unsafe { ffi::blFontManagerHasFace(self.core(), face.core()) }
+    }
+
+    /// Adds a face to this font manager, grouping it into a family by its
+    /// family name.
+    ///
+    /// Adding a face that has already been added does nothing.
+    #[inline]
+    pub fn add_face(&mut self, face: &FontFace) -> Result<()> {
+        unsafe { errcode_to_result(ffi::blFontManagerAddFace(self.core_mut(), face.core())) }
+    }
+
+    /// Queries the face of the family that matches the given properties best.
+    ///
+    /// The matching follows the CSS font matching algorithm: the stretch,
+    /// style and weight narrow down the faces of the family in that order,
+    /// each falling back to the closest available value if there is no exact
+    /// match. Returns `None` if there is no family with the given name.
+    pub fn query_face(
+        &self,
+        family_name: &str,
+        weight: FontWeight,
+        stretch: FontStretch,
+        style: FontStyle,
+    ) -> Option<FontFace> {
+        let properties = ffi::BLFontQueryProperties {
+            style: style.into(),
+            weight: weight.into(),
+            stretch: stretch.into(),
+        };
+        let mut face = FontFace::from_core(*FontFace::none());
+        unsafe {
+            errcode_to_result(ffi::blFontManagerQueryFace(
+                self.core(),
+                family_name.as_ptr() as *const _,
+                family_name.len(),
+                &properties,
+                face.core_mut(),
+            ))
+            .ok()
+            .map(|_| face)
+        }
+    }
+
+    /// Returns all faces of the family with the given name.
+    pub fn query_faces_by_family(&self, family_name: &str) -> Array<FontFace> {
+        let mut faces = Array::new();
+        unsafe {
+            expect_mem_err(ffi::blFontManagerQueryFacesByFamilyName(
+                self.core(),
+                family_name.as_ptr() as *const _,
+                family_name.len(),
+                faces.core_mut(),
+            ))
+        };
+        faces
+    }
+}
+
+impl Default for FontManager {
+    #[inline]
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
 impl PartialEq for FontManager {
     #[inline]
     fn eq(&self, other: &Self) -> bool {
@@ -39,6 +133,67 @@ impl Drop for FontManager {
 
 impl fmt::Debug for FontManager {
     fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
-        f.debug_struct("FontManager").finish()
+        f.debug_struct("FontManager")
+            .field("face_count", &self.face_count())
+            .field("family_count", &self.family_count())
+            .finish()
+    }
+}
+
+#[cfg(test)]
+mod test_font_manager {
+    use super::FontManager;
+    use crate::font::FontFace;
+    use crate::font_defs::{FontStretch, FontStyle, FontWeight};
+    use crate::DataAccessFlags;
+
+    fn face() -> FontFace {
+        let path = concat!(env!("CARGO_MANIFEST_DIR"), "/assets/NotoSans-Regular.ttf");
+        FontFace::from_path(path, DataAccessFlags::READ).unwrap()
+    }
+
+    #[test]
+    fn test_add_face() {
+        let face = face();
+        let mut manager = FontManager::new();
+        assert_eq!((manager.face_count(), manager.family_count()), (0, 0));
+        assert!(!manager.has_face(&face));
+
+        manager.add_face(&face).unwrap();
+        assert_eq!((manager.face_count(), manager.family_count()), (1, 1));
+        assert!(manager.has_face(&face));
+        // Adding the same face again does nothing.
+        manager.add_face(&face).unwrap();
+        assert_eq!((manager.face_count(), manager.family_count()), (1, 1));
+    }
+
+    #[test]
+    fn test_query_face() {
+        let face = face();
+        let family_name = face.family_name().to_owned();
+        let mut manager = FontManager::new();
+        manager.add_face(&face).unwrap();
+
+        let query =
+            |weight, style| manager.query_face(&family_name, weight, FontStretch::Normal, style);
+        let expected = Some(face.clone());
+        assert_eq!(query(FontWeight::Normal, FontStyle::Normal), expected);
+        // There are no other weights or styles, so the closest face is the
+        // regular one.
+        assert_eq!(query(FontWeight::Black, FontStyle::Normal), expected);
+        assert_eq!(query(FontWeight::Thin, FontStyle::Italic), expected);
+        assert_eq!(
+            manager.query_face(
+                "No Such Family",
+                FontWeight::Normal,
+                FontStretch::Normal,
+                FontStyle::Normal
+            ),
+            None
+        );
+
+        let faces = manager.query_faces_by_family(&family_name);
+        assert_eq!(faces.as_slice(), [face]);
+        assert!(manager.query_faces_by_family("No Such Family").is_empty());
     }
 }
@@ -0,0 +1,40 @@
+//! Parsers for OpenType tables queried by
+//! [`FontData::query_table`](../struct.FontData.html#method.query_table).
+mod cmap;
+pub use self::cmap::{CmapSubtable, CmapTable};
+
+mod kern;
+pub use self::kern::{KernSubtable, KernTable};
+
+mod name;
+pub use self::name::{NameRecord, NameTable};
+
+mod os2;
+pub use self::os2::Os2Table;
+
+mod post;
+pub use self::post::PostTable;
+
+use std::convert::TryInto;
+
+use crate::error::{Error, Result};
+
+/// Returns `len` bytes of `data` starting at `offset`.
+fn bytes_at(data: &[u8], offset: usize, len: usize) -> Result<&[u8]> {
+    offset
+        .checked_add(len)
+        .and_then(|end| data.get(offset..end))
+        .ok_or(Error::DataTruncated)
+}
+
+fn u16_at(data: &[u8], offset: usize) -> Result<u16> {
+    bytes_at(data, offset, 2).map(|b| u16::from_be_bytes(b.try_into().unwrap()))
+}
+
+fn i16_at(data: &[u8], offset: usize) -> Result<i16> {
+    u16_at(data, offset).map(|v| v as i16)
+}
+
+fn u32_at(data: &[u8], offset: usize) -> Result<u32> {
+    bytes_at(data, offset, 4).map(|b| u32::from_be_bytes(b.try_into().unwrap()))
+}
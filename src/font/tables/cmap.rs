@@ -0,0 +1,372 @@
+use std::ops::RangeInclusive;
+
+use crate::error::{Error, Result};
+use crate::glyph_buffer::GlyphId;
+
+use super::{bytes_at, u16_at, u32_at};
+
+/// The `cmap` table, mapping characters to glyphs by one or more subtables
+/// for different platforms and encodings.
+#[derive(Clone, Copy, Debug)]
+pub struct CmapTable<'a> {
+    data: &'a [u8],
+    records: &'a [u8],
+}
+
+impl<'a> CmapTable<'a> {
+    /// Parses the data of a `cmap` table.
+    ///
+    /// Subtables of format 0, 4, 6, 12 and 13 are supported, subtables of
+    /// other formats are listed but don't map any characters.
+    pub fn parse(data: &'a [u8]) -> Result<Self> {
+        let count = u16_at(data, 2)? as usize;
+        let records = bytes_at(data, 4, count * 8)?;
+        let this = CmapTable { data, records };
+        for subtable in this.subtables() {
+            subtable.validate()?;
+        }
+        Ok(this)
+    }
+
+    /// Returns the subtables of the table.
+    pub fn subtables(&self) -> impl ExactSizeIterator<Item = CmapSubtable<'a>> + 'a {
+        let data = self.data;
+        self.records.chunks_exact(8).map(move |record| {
+            let offset = u32::from_be_bytes([record[4], record[5], record[6], record[7]]) as usize;
+            CmapSubtable {
+                platform_id: u16::from_be_bytes([record[0], record[1]]),
+                encoding_id: u16::from_be_bytes([record[2], record[3]]),
+                data: data.get(offset..).unwrap_or(&[]),
+            }
+        })
+    }
+
+    /// Returns the subtable mapping unicode characters, preferring
+    /// subtables that cover all of unicode over ones limited to the Basic
+    /// Multilingual Plane.
+    pub fn unicode_subtable(&self) -> Option<CmapSubtable<'a>> {
+        self.subtables()
+            .filter(|subtable| subtable.is_supported())
+            .filter_map(|subtable| {
+                let priority = match (subtable.platform_id, subtable.encoding_id) {
+                    (PLATFORM_WINDOWS, 10) | (PLATFORM_UNICODE, 4) | (PLATFORM_UNICODE, 6) => 0,
+                    (PLATFORM_WINDOWS, 1) | (PLATFORM_UNICODE, 0..=3) => 1,
+                    _ => return None,
+                };
+                Some((priority, subtable))
+            })
+            .min_by_key(|&(priority, _)| priority)
+            .map(|(_, subtable)| subtable)
+    }
+
+    /// Returns the glyph of the character, or `None` if the character isn't
+    /// mapped by the unicode subtable.
+    pub fn glyph_id(&self, c: char) -> Option<GlyphId> {
+        self.unicode_subtable()?.glyph_id(u32::from(c))
+    }
+
+    /// Returns the sorted ranges of characters mapped by the unicode
+    /// subtable.
+    pub fn coverage(&self) -> Vec<RangeInclusive<u32>> {
+        self.unicode_subtable()
+            .map(|subtable| subtable.coverage())
+            .unwrap_or_default()
+    }
+}
+
+const PLATFORM_UNICODE: u16 = 0;
+const PLATFORM_WINDOWS: u16 = 3;
+
+/// A subtable of a [`CmapTable`].
+#[derive(Clone, Copy, Debug)]
+pub struct CmapSubtable<'a> {
+    pub platform_id: u16,
+    pub encoding_id: u16,
+    data: &'a [u8],
+}
+
+impl<'a> CmapSubtable<'a> {
+    /// Returns the format of the subtable.
+    #[inline]
+    pub fn format(&self) -> u16 {
+        u16_at(self.data, 0).unwrap()
+    }
+
+    /// Tests whether the subtable maps unicode characters.
+    pub fn is_unicode(&self) -> bool {
+        matches!(
+            (self.platform_id, self.encoding_id),
+            (PLATFORM_UNICODE, _) | (PLATFORM_WINDOWS, 1) | (PLATFORM_WINDOWS, 10)
+        )
+    }
+
+    /// Tests whether the format of the subtable is supported.
+    #[inline]
+    pub fn is_supported(&self) -> bool {
+        matches!(self.format(), 0 | 4 | 6 | 12 | 13)
+    }
+
+    /// Returns the glyph of the character code, or `None` if it isn't
+    /// mapped.
+    pub fn glyph_id(&self, code: u32) -> Option<GlyphId> {
+        let glyph_id = match self.format() {
+            0 if code < 256 => self.data[6 + code as usize] as u32,
+            4 => {
+                let seg_count = self.seg_count();
+                (0..seg_count)
+                    .find(|&segment| {
+                        let (start, end) = self.segment_range(seg_count, segment);
+                        start <= code && code <= end
+                    })
+                    .map_or(0, |segment| self.segment_glyph_id(seg_count, segment, code))
+            },
+            6 => {
+                let index = code.checked_sub(self.u16(6) as u32)?;
+                if index >= self.u16(8) as u32 {
+                    return None;
+                }
+                u16_at(self.data, 10 + index as usize * 2).unwrap_or(0) as u32
+            },
+            12 | 13 => self
+                .groups()
+                .find(|&(start, end, _)| start <= code && code <= end)
+                .map_or(0, |(start, _, glyph_id)| {
+                    if self.format() == 12 {
+                        glyph_id.wrapping_add(code - start)
+                    } else {
+                        glyph_id
+                    }
+                }),
+            _ => 0,
+        };
+        match glyph_id {
+            0 => None,
+            glyph_id => Some(glyph_id as GlyphId),
+        }
+    }
+
+    /// Returns the sorted ranges of character codes mapped by the subtable.
+    pub fn coverage(&self) -> Vec<RangeInclusive<u32>> {
+        let mut ranges: Vec<RangeInclusive<u32>> = Vec::new();
+        match self.format() {
+            0 => ranges.extend(
+                (0..256)
+                    .filter(|&code| self.glyph_id(code).is_some())
+                    .map(|code| code..=code),
+            ),
+            4 => {
+                let seg_count = self.seg_count();
+                for segment in 0..seg_count {
+                    let (start, end) = self.segment_range(seg_count, segment);
+                    ranges.extend(
+                        (start..=end)
+                            .filter(|&code| self.segment_glyph_id(seg_count, segment, code) != 0)
+                            .map(|code| code..=code),
+                    );
+                }
+            },
+            6 => {
+                let first = self.u16(6) as u32;
+                ranges.extend(
+                    (first..first + self.u16(8) as u32)
+                        .filter(|&code| self.glyph_id(code).is_some())
+                        .map(|code| code..=code),
+                );
+            },
+            12 | 13 => {
+                for (start, end, glyph_id) in self.groups() {
+                    // Only the first character of a format 12 group maps to
+                    // the starting glyph.
+                    let start = match (glyph_id, self.format()) {
+                        (0, 12) => start.checked_add(1),
+                        (0, _) => None,
+                        _ => Some(start),
+                    };
+                    match start {
+                        Some(start) if start <= end => ranges.push(start..=end),
+                        _ => (),
+                    }
+                }
+            },
+            _ => (),
+        }
+        merge_ranges(ranges)
+    }
+
+    /// Checks that the header and the arrays of the subtable are in bounds.
+    fn validate(&self) -> Result<()> {
+        let format = u16_at(self.data, 0)?;
+        match format {
+            0 => bytes_at(self.data, 6, 256).map(drop),
+            4 => {
+                let seg_count_x2 = u16_at(self.data, 6)? as usize;
+                if seg_count_x2 & 1 != 0 {
+                    return Err(Error::InvalidData);
+                }
+                bytes_at(self.data, 14, seg_count_x2 * 4 + 2).map(drop)
+            },
+            6 => bytes_at(self.data, 10, u16_at(self.data, 8)? as usize * 2).map(drop),
+            12 | 13 => bytes_at(self.data, 16, u32_at(self.data, 12)? as usize * 12).map(drop),
+            _ => Ok(()),
+        }
+    }
+
+    /// Reads a `u16` of the validated header.
+    #[inline]
+    fn u16(&self, offset: usize) -> u16 {
+        u16_at(self.data, offset).unwrap()
+    }
+
+    fn seg_count(&self) -> usize {
+        self.u16(6) as usize / 2
+    }
+
+    fn segment_range(&self, seg_count: usize, segment: usize) -> (u32, u32) {
+        let end = self.u16(14 + segment * 2) as u32;
+        let start = self.u16(16 + seg_count * 2 + segment * 2) as u32;
+        (start, end)
+    }
+
+    fn segment_glyph_id(&self, seg_count: usize, segment: usize, code: u32) -> u32 {
+        let (start, _) = self.segment_range(seg_count, segment);
+        let id_delta = self.u16(16 + seg_count * 4 + segment * 2);
+        let id_range_offset_pos = 16 + seg_count * 6 + segment * 2;
+        let id_range_offset = self.u16(id_range_offset_pos) as usize;
+        let glyph_id = if id_range_offset == 0 {
+            code as u16
+        } else {
+            // The offset is relative to its own position in the table.
+            let pos = id_range_offset_pos + id_range_offset + (code - start) as usize * 2;
+            match u16_at(self.data, pos).unwrap_or(0) {
+                0 => return 0,
+                glyph_id => glyph_id,
+            }
+        };
+        glyph_id.wrapping_add(id_delta) as u32
+    }
+
+    /// Returns the start and end character codes and the glyph of the
+    /// groups of a format 12 or 13 subtable.
+    fn groups(&self) -> impl Iterator<Item = (u32, u32, u32)> + 'a {
+        let count = u32_at(self.data, 12).unwrap() as usize;
+        self.data[16..16 + count * 12]
+            .chunks_exact(12)
+            .map(|group| {
+                let field = |index: usize| u32_at(group, index * 4).unwrap();
+                (field(0), field(1), field(2))
+            })
+    }
+}
+
+/// Sorts the ranges and merges overlapping and adjacent ones.
+fn merge_ranges(mut ranges: Vec<RangeInclusive<u32>>) -> Vec<RangeInclusive<u32>> {
+    ranges.sort_by_key(|range| *range.start());
+    let mut merged: Vec<RangeInclusive<u32>> = Vec::with_capacity(ranges.len());
+    for range in ranges {
+        match merged.last_mut() {
+            Some(last) if *range.start() <= last.end().saturating_add(1) => {
+                *last = *last.start()..=*last.end().max(range.end());
+            },
+            _ => merged.push(range),
+        }
+    }
+    merged
+}
+
+#[cfg(test)]
+mod test_cmap {
+    use super::*;
+
+    fn be16(values: &[u16]) -> Vec<u8> {
+        values
+            .iter()
+            .flat_map(|v| v.to_be_bytes().to_vec())
+            .collect()
+    }
+
+    fn be32(values: &[u32]) -> Vec<u8> {
+        values
+            .iter()
+            .flat_map(|v| v.to_be_bytes().to_vec())
+            .collect()
+    }
+
+    /// Format 4 subtable mapping 'A'..='C' by delta, 'a'..='c' through the
+    /// glyph array with 'b' unmapped, and the final 0xFFFF segment.
+    fn format4() -> Vec<u8> {
+        let mut data = be16(&[4, 0, 0, 6, 0, 0, 0]);
+        data.extend(be16(&[0x43, 0x63, 0xFFFF, 0]));
+        data.extend(be16(&[0x41, 0x61, 0xFFFF]));
+        data.extend(be16(&[(10u16).wrapping_sub(0x41), 0, 1]));
+        data.extend(be16(&[0, 4, 0]));
+        data.extend(be16(&[20, 0, 22]));
+        data
+    }
+
+    /// Format 12 subtable mapping two groups, the first of them starting at
+    /// glyph 0.
+    fn format12() -> Vec<u8> {
+        let mut data = be16(&[12, 0]);
+        data.extend(be32(&[0, 0, 2]));
+        data.extend(be32(&[0x20, 0x22, 0]));
+        data.extend(be32(&[0x1F600, 0x1F601, 30]));
+        data
+    }
+
+    fn table(subtables: &[(u16, u16, Vec<u8>)]) -> Vec<u8> {
+        let mut data = be16(&[0, subtables.len() as u16]);
+        let mut offset = 4 + subtables.len() * 8;
+        for (platform_id, encoding_id, subtable) in subtables {
+            data.extend(be16(&[*platform_id, *encoding_id]));
+            data.extend(be32(&[offset as u32]));
+            offset += subtable.len();
+        }
+        for (_, _, subtable) in subtables {
+            data.extend_from_slice(subtable);
+        }
+        data
+    }
+
+    #[test]
+    fn test_format4() {
+        let data = table(&[(3, 1, format4())]);
+        let cmap = CmapTable::parse(&data).unwrap();
+        assert_eq!(cmap.subtables().len(), 1);
+        assert_eq!(cmap.glyph_id('A'), Some(10));
+        assert_eq!(cmap.glyph_id('C'), Some(12));
+        assert_eq!(cmap.glyph_id('a'), Some(20));
+        assert_eq!(cmap.glyph_id('b'), None);
+        assert_eq!(cmap.glyph_id('c'), Some(22));
+        assert_eq!(cmap.glyph_id('D'), None);
+        assert_eq!(cmap.coverage(), [0x41..=0x43, 0x61..=0x61, 0x63..=0x63]);
+    }
+
+    #[test]
+    fn test_format12_preferred() {
+        let data = table(&[(3, 1, format4()), (3, 10, format12())]);
+        let cmap = CmapTable::parse(&data).unwrap();
+        assert_eq!(cmap.unicode_subtable().map(|s| s.format()), Some(12));
+        assert_eq!(cmap.glyph_id(' '), None);
+        assert_eq!(cmap.glyph_id('"'), Some(2));
+        assert_eq!(cmap.glyph_id('😁'), Some(31));
+        assert_eq!(cmap.coverage(), [0x21..=0x22, 0x1F600..=0x1F601]);
+    }
+
+    #[test]
+    fn test_unsupported() {
+        let data = table(&[(1, 0, be16(&[2, 0, 0])), (3, 1, be16(&[4, 0, 0, 200]))]);
+        assert!(CmapTable::parse(&data).is_err());
+        let data = table(&[(1, 0, be16(&[2, 0, 0]))]);
+        let cmap = CmapTable::parse(&data).unwrap();
+        assert!(cmap.unicode_subtable().is_none());
+        assert!(cmap.coverage().is_empty());
+    }
+
+    #[test]
+    fn test_merge_ranges() {
+        assert_eq!(
+            merge_ranges(vec![5..=6, 0..=1, 2..=2, 4..=5, 9..=9]),
+            [0..=2, 4..=6, 9..=9]
+        );
+    }
+}
@@ -0,0 +1,178 @@
+use std::cmp::Ordering;
+
+use crate::error::{Error, Result};
+use crate::glyph_buffer::GlyphId;
+
+use super::{bytes_at, u16_at};
+
+/// A subtable of a [`KernTable`].
+#[derive(Clone, Copy, Debug)]
+pub struct KernSubtable<'a> {
+    pub format: u8,
+    /// Whether the subtable holds horizontal kerning values, as opposed to
+    /// vertical ones.
+    pub horizontal: bool,
+    /// Whether the values are minimum values instead of kerning values.
+    pub minimum: bool,
+    /// Whether the values are perpendicular to the flow of the text.
+    pub cross_stream: bool,
+    /// Whether the values replace the values accumulated so far instead of
+    /// being added to them.
+    pub replace: bool,
+    pairs: &'a [u8],
+}
+
+impl<'a> KernSubtable<'a> {
+    /// Returns the left glyph, the right glyph and the value of every pair
+    /// of a format 0 subtable, sorted by the glyphs. Subtables of other
+    /// formats have no pairs.
+    pub fn pairs(&self) -> impl ExactSizeIterator<Item = (GlyphId, GlyphId, i16)> + 'a {
+        self.pairs.chunks_exact(6).map(read_pair)
+    }
+
+    /// Returns the value of the pair of glyphs, if the subtable holds one.
+    pub fn kerning(&self, left: GlyphId, right: GlyphId) -> Option<i16> {
+        let (mut low, mut high) = (0, self.pairs.len() / 6);
+        while low < high {
+            let mid = (low + high) / 2;
+            let (pair_left, pair_right, value) = read_pair(&self.pairs[mid * 6..mid * 6 + 6]);
+            match (pair_left, pair_right).cmp(&(left, right)) {
+                Ordering::Less => low = mid + 1,
+                Ordering::Greater => high = mid,
+                Ordering::Equal => return Some(value),
+            }
+        }
+        None
+    }
+}
+
+fn read_pair(pair: &[u8]) -> (GlyphId, GlyphId, i16) {
+    (
+        u16::from_be_bytes([pair[0], pair[1]]),
+        u16::from_be_bytes([pair[2], pair[3]]),
+        i16::from_be_bytes([pair[4], pair[5]]),
+    )
+}
+
+/// The `kern` table, holding the kerning values of pairs of glyphs for
+/// fonts without a `GPOS` table.
+///
+/// Only the OpenType version of the table is supported, not the one of
+/// Apple.
+#[derive(Clone, Debug)]
+pub struct KernTable<'a> {
+    subtables: Vec<KernSubtable<'a>>,
+}
+
+impl<'a> KernTable<'a> {
+    /// Parses the data of a `kern` table.
+    pub fn parse(data: &'a [u8]) -> Result<Self> {
+        if u16_at(data, 0)? != 0 {
+            return Err(Error::InvalidData);
+        }
+        let count = u16_at(data, 2)?;
+        let mut subtables = Vec::with_capacity(count as usize);
+        let mut offset = 4;
+        for _ in 0..count {
+            let coverage = u16_at(data, offset + 4)?;
+            let format = (coverage >> 8) as u8;
+            let (len, pairs) = if format == 0 {
+                // The length field overflows for large subtables, so the
+                // length is computed from the number of pairs.
+                let pair_count = u16_at(data, offset + 6)? as usize;
+                (
+                    14 + pair_count * 6,
+                    bytes_at(data, offset + 14, pair_count * 6)?,
+                )
+            } else {
+                (u16_at(data, offset + 2)? as usize, &[][..])
+            };
+            subtables.push(KernSubtable {
+                format,
+                horizontal: coverage & 0x01 != 0,
+                minimum: coverage & 0x02 != 0,
+                cross_stream: coverage & 0x04 != 0,
+                replace: coverage & 0x08 != 0,
+                pairs,
+            });
+            offset += len;
+        }
+        Ok(KernTable { subtables })
+    }
+
+    /// Returns the subtables of the table.
+    #[inline]
+    pub fn subtables(&self) -> &[KernSubtable<'a>] {
+        &self.subtables
+    }
+
+    /// Returns the horizontal kerning of the pair of glyphs in font units,
+    /// accumulated over all subtables holding horizontal kerning values.
+    pub fn kerning(&self, left: GlyphId, right: GlyphId) -> i32 {
+        self.subtables
+            .iter()
+            .filter(|subtable| subtable.horizontal && !subtable.minimum && !subtable.cross_stream)
+            .fold(0, |kerning, subtable| match subtable.kerning(left, right) {
+                Some(value) if subtable.replace => i32::from(value),
+                Some(value) => kerning + i32::from(value),
+                None => kerning,
+            })
+    }
+}
+
+#[cfg(test)]
+mod test_kern {
+    use super::*;
+
+    fn be16(values: &[u16]) -> Vec<u8> {
+        values
+            .iter()
+            .flat_map(|v| v.to_be_bytes().to_vec())
+            .collect()
+    }
+
+    fn format0(coverage: u16, pairs: &[(u16, u16, i16)]) -> Vec<u8> {
+        let mut data = be16(&[0, 0, coverage, pairs.len() as u16, 0, 0, 0]);
+        for &(left, right, value) in pairs {
+            data.extend(be16(&[left, right, value as u16]));
+        }
+        data
+    }
+
+    #[test]
+    fn test_kerning() {
+        let mut data = be16(&[0, 3]);
+        data.extend(format0(0x0001, &[(1, 2, -50), (1, 3, 20), (4, 1, -10)]));
+        // A vertical subtable and a horizontal one of an unsupported format.
+        data.extend(format0(0x0000, &[(1, 2, -7)]));
+        data.extend(be16(&[0, 8, 0x0201, 0]));
+        let kern = KernTable::parse(&data).unwrap();
+        assert_eq!(kern.subtables().len(), 3);
+        assert_eq!(kern.subtables()[0].pairs().len(), 3);
+        assert_eq!(kern.subtables()[2].format, 2);
+        assert_eq!(kern.subtables()[2].pairs().len(), 0);
+        assert_eq!(kern.kerning(1, 2), -50);
+        assert_eq!(kern.kerning(4, 1), -10);
+        assert_eq!(kern.kerning(2, 1), 0);
+    }
+
+    #[test]
+    fn test_accumulate() {
+        let mut data = be16(&[0, 3]);
+        data.extend(format0(0x0001, &[(1, 2, -50)]));
+        data.extend(format0(0x0001, &[(1, 2, 10)]));
+        data.extend(format0(0x0009, &[(3, 4, 5)]));
+        let kern = KernTable::parse(&data).unwrap();
+        assert_eq!(kern.kerning(1, 2), -40);
+        assert_eq!(kern.kerning(3, 4), 5);
+    }
+
+    #[test]
+    fn test_invalid() {
+        let mut data = be16(&[0, 1]);
+        data.extend(format0(0x0001, &[(1, 2, -50)]));
+        assert!(KernTable::parse(&data[..data.len() - 1]).is_err());
+        data[1] = 1;
+        assert!(KernTable::parse(&data).is_err());
+    }
+}
@@ -0,0 +1,296 @@
+use crate::error::{Error, Result};
+use crate::font_defs::FontStringId;
+
+use super::{bytes_at, u16_at};
+
+/// The [`FontStringId`]s in the order of their name ids.
+const STRING_IDS: [FontStringId; 26] = [
+    FontStringId::CopyrightNotice,
+    FontStringId::FamilyName,
+    FontStringId::SubfamilyName,
+    FontStringId::UniqueIdentifier,
+    FontStringId::FullName,
+    FontStringId::VersionString,
+    FontStringId::PostScriptName,
+    FontStringId::Trademark,
+    FontStringId::ManufacturerName,
+    FontStringId::DesignerName,
+    FontStringId::Description,
+    FontStringId::VendorUrl,
+    FontStringId::DesignerUrl,
+    FontStringId::LicenseDescription,
+    FontStringId::LicenseInfoUrl,
+    FontStringId::Reserved,
+    FontStringId::TypographicsFamilyName,
+    FontStringId::TypographicsSubfamilyName,
+    FontStringId::CompatibleFullname,
+    FontStringId::SampleText,
+    FontStringId::PostScriptCidName,
+    FontStringId::WwsFamilyName,
+    FontStringId::WwsSubfamilyName,
+    FontStringId::LightBackgroundPalette,
+    FontStringId::DarkBackgroundPalette,
+    FontStringId::VariationsPostScriptPrefix,
+];
+
+/// The upper half of the Mac OS Roman encoding, the lower half is ASCII.
+const MAC_ROMAN: &str = "ÄÅÇÉÑÖÜáàâäãåçéèêëíìîïñóòôöõúùûü†°¢£§•¶ß®©™´¨≠ÆØ∞±≤≥¥µ∂∑∏π∫ªºΩæø\
+                         ¿¡¬√ƒ≈∆«»…\u{A0}ÀÃÕŒœ–—“”‘’÷◊ÿŸ⁄€‹›ﬁﬂ‡·‚„‰ÂÊÁËÈÍÎÏÌÓÔ\
+                         \u{F8FF}ÒÚÛÙıˆ˜¯˘˙˚¸˝˛ˇ";
+
+const PLATFORM_UNICODE: u16 = 0;
+const PLATFORM_MACINTOSH: u16 = 1;
+const PLATFORM_WINDOWS: u16 = 3;
+const WINDOWS_ENGLISH_US: u16 = 0x0409;
+
+/// A string of a [`NameTable`].
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct NameRecord<'a> {
+    pub platform_id: u16,
+    pub encoding_id: u16,
+    /// A platform specific language id, or from `0x8000` on the index of a
+    /// language tag, see [`NameTable::language_tag`].
+    pub language_id: u16,
+    pub name_id: u16,
+    /// The encoded string.
+    pub data: &'a [u8],
+}
+
+impl NameRecord<'_> {
+    /// Returns the [`FontStringId`] of the name id, or `None` for font
+    /// specific name ids.
+    #[inline]
+    pub fn string_id(&self) -> Option<FontStringId> {
+        STRING_IDS.get(self.name_id as usize).copied()
+    }
+
+    /// Decodes the string, returns `None` if its encoding isn't supported.
+    ///
+    /// Strings of the unicode and windows platforms are UTF-16 encoded,
+    /// strings of the macintosh platform are only supported in the Roman
+    /// encoding.
+    pub fn decode(&self) -> Option<String> {
+        match (self.platform_id, self.encoding_id) {
+            (PLATFORM_UNICODE, _)
+            | (PLATFORM_WINDOWS, 0)
+            | (PLATFORM_WINDOWS, 1)
+            | (PLATFORM_WINDOWS, 10) => decode_utf16_be(self.data),
+            (PLATFORM_MACINTOSH, 0) => Some(self.data.iter().map(|&b| mac_roman(b)).collect()),
+            _ => None,
+        }
+    }
+}
+
+/// The `name` table, holding the strings of a font-face in several
+/// languages and encodings.
+#[derive(Clone, Copy, Debug)]
+pub struct NameTable<'a> {
+    data: &'a [u8],
+    records: &'a [u8],
+    lang_tags: &'a [u8],
+    storage_offset: usize,
+}
+
+impl<'a> NameTable<'a> {
+    /// Parses the data of a `name` table.
+    pub fn parse(data: &'a [u8]) -> Result<Self> {
+        let format = u16_at(data, 0)?;
+        if format > 1 {
+            return Err(Error::InvalidData);
+        }
+        let count = u16_at(data, 2)? as usize;
+        let storage_offset = u16_at(data, 4)? as usize;
+        let records = bytes_at(data, 6, count * 12)?;
+        let lang_tags = if format == 1 {
+            let lang_tag_count = u16_at(data, 6 + count * 12)? as usize;
+            bytes_at(data, 8 + count * 12, lang_tag_count * 4)?
+        } else {
+            &[]
+        };
+        let this = NameTable {
+            data,
+            records,
+            lang_tags,
+            storage_offset,
+        };
+        // Check the bounds of all strings, so that they can be accessed
+        // without failing later on.
+        for record in records.chunks_exact(12) {
+            this.string(&record[8..])?;
+        }
+        for lang_tag in lang_tags.chunks_exact(4) {
+            this.string(lang_tag)?;
+        }
+        Ok(this)
+    }
+
+    /// Returns all strings of the table.
+    pub fn records(&self) -> impl ExactSizeIterator<Item = NameRecord<'a>> + 'a {
+        let this = *self;
+        self.records.chunks_exact(12).map(move |record| {
+            let field =
+                |index: usize| u16::from_be_bytes([record[index * 2], record[index * 2 + 1]]);
+            NameRecord {
+                platform_id: field(0),
+                encoding_id: field(1),
+                language_id: field(2),
+                name_id: field(3),
+                data: this.string(&record[8..]).unwrap(),
+            }
+        })
+    }
+
+    /// Returns the string with the given id, preferably in US English.
+    ///
+    /// Strings of the windows platform are preferred over the unicode and
+    /// macintosh platforms, as they are the most commonly provided ones.
+    pub fn get(&self, id: FontStringId) -> Option<String> {
+        let name_id = STRING_IDS.iter().position(|&string_id| string_id == id)? as u16;
+        self.records()
+            .filter(|record| record.name_id == name_id)
+            .filter_map(|record| {
+                let priority = match (record.platform_id, record.encoding_id) {
+                    (PLATFORM_WINDOWS, 0) | (PLATFORM_WINDOWS, 1) | (PLATFORM_WINDOWS, 10)
+                        if record.language_id == WINDOWS_ENGLISH_US =>
+                    {
+                        0
+                    },
+                    (PLATFORM_WINDOWS, 0) | (PLATFORM_WINDOWS, 1) | (PLATFORM_WINDOWS, 10) => 1,
+                    (PLATFORM_UNICODE, _) => 2,
+                    (PLATFORM_MACINTOSH, 0) if record.language_id == 0 => 3,
+                    _ => return None,
+                };
+                Some((priority, record))
+            })
+            .min_by_key(|&(priority, _)| priority)
+            .and_then(|(_, record)| record.decode())
+    }
+
+    /// Returns the IETF BCP 47 language tag of a language id from `0x8000`
+    /// on, which are only used by tables of format 1.
+    pub fn language_tag(&self, language_id: u16) -> Option<String> {
+        let index = language_id.checked_sub(0x8000)? as usize;
+        let lang_tag = self.lang_tags.get(index * 4..index * 4 + 4)?;
+        decode_utf16_be(self.string(lang_tag).unwrap())
+    }
+
+    /// Returns the string of a record that ends with its length and offset.
+    fn string(&self, length_offset: &[u8]) -> Result<&'a [u8]> {
+        let len = u16_at(length_offset, 0)? as usize;
+        let offset = u16_at(length_offset, 2)? as usize;
+        bytes_at(self.data, self.storage_offset + offset, len)
+    }
+}
+
+fn decode_utf16_be(data: &[u8]) -> Option<String> {
+    let units = data.chunks_exact(2);
+    if !units.remainder().is_empty() {
+        return None;
+    }
+    let units: Vec<u16> = units
+        .map(|unit| u16::from_be_bytes([unit[0], unit[1]]))
+        .collect();
+    String::from_utf16(&units).ok()
+}
+
+fn mac_roman(byte: u8) -> char {
+    if byte < 0x80 {
+        char::from(byte)
+    } else {
+        MAC_ROMAN.chars().nth(byte as usize - 0x80).unwrap()
+    }
+}
+
+#[cfg(test)]
+mod test_name {
+    use super::*;
+
+    fn record(
+        platform: u16,
+        encoding: u16,
+        language: u16,
+        name: u16,
+        len: u16,
+        offset: u16,
+    ) -> Vec<u8> {
+        [platform, encoding, language, name, len, offset]
+            .iter()
+            .flat_map(|field| field.to_be_bytes().to_vec())
+            .collect()
+    }
+
+    fn utf16_be(s: &str) -> Vec<u8> {
+        s.encode_utf16()
+            .flat_map(|unit| unit.to_be_bytes().to_vec())
+            .collect()
+    }
+
+    /// A format 1 table with a family name in Macintosh Roman, Windows
+    /// German and Windows US English, and a language tag.
+    fn table() -> Vec<u8> {
+        let storage: Vec<u8> = [
+            &b"Caf\x8e"[..],
+            &utf16_be("Schrift"),
+            &utf16_be("Font"),
+            &utf16_be("de-CH"),
+        ]
+        .concat();
+        let storage_offset = 6u16 + 4 * 12 + 2 + 4;
+        let mut data = Vec::new();
+        data.extend_from_slice(&1u16.to_be_bytes());
+        data.extend_from_slice(&4u16.to_be_bytes());
+        data.extend_from_slice(&storage_offset.to_be_bytes());
+        data.extend(record(1, 0, 0, 1, 4, 0));
+        data.extend(record(3, 1, 0x0407, 1, 14, 4));
+        data.extend(record(3, 1, 0x0409, 1, 8, 18));
+        data.extend(record(3, 1, 0x8000, 300, 8, 18));
+        data.extend_from_slice(&1u16.to_be_bytes());
+        data.extend_from_slice(&[0, 10, 0, 26]);
+        data.extend(storage);
+        data
+    }
+
+    #[test]
+    fn test_records() {
+        let data = table();
+        let table = NameTable::parse(&data).unwrap();
+        let records: Vec<_> = table.records().collect();
+        assert_eq!(records.len(), 4);
+        assert_eq!(records[0].decode().as_deref(), Some("Café"));
+        assert_eq!(records[1].decode().as_deref(), Some("Schrift"));
+        assert_eq!(records[1].string_id(), Some(FontStringId::FamilyName));
+        assert_eq!(records[3].string_id(), None);
+        assert_eq!(
+            table.language_tag(records[3].language_id).as_deref(),
+            Some("de-CH")
+        );
+        assert_eq!(table.language_tag(0x0409), None);
+    }
+
+    #[test]
+    fn test_get() {
+        let data = table();
+        let table = NameTable::parse(&data).unwrap();
+        assert_eq!(table.get(FontStringId::FamilyName).as_deref(), Some("Font"));
+        assert_eq!(table.get(FontStringId::FullName), None);
+    }
+
+    #[test]
+    fn test_invalid() {
+        let mut data = table();
+        assert!(NameTable::parse(&data[..40]).is_err());
+        data.pop();
+        assert!(NameTable::parse(&data).is_err());
+        data[1] = 2;
+        assert!(NameTable::parse(&data).is_err());
+    }
+
+    #[test]
+    fn test_mac_roman() {
+        assert_eq!(MAC_ROMAN.chars().count(), 128);
+        assert_eq!(mac_roman(b'A'), 'A');
+        assert_eq!(mac_roman(0xA5), '•');
+        assert_eq!(mac_roman(0xDB), '€');
+        assert_eq!(mac_roman(0xFF), 'ˇ');
+    }
+}
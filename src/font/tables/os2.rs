@@ -0,0 +1,155 @@
+use std::convert::TryInto;
+
+use crate::error::Result;
+use crate::font_defs::{FontPanose, FontUnicodeCoverage};
+use crate::Tag;
+
+use super::{bytes_at, i16_at, u16_at, u32_at};
+
+/// The `OS/2` table, holding the metrics and classification of a font-face
+/// used by Windows.
+///
+/// Fields that were added by later versions of the table are `None` for
+/// older versions.
+#[derive(Clone, Debug, PartialEq)]
+pub struct Os2Table {
+    pub version: u16,
+    pub avg_char_width: i16,
+    /// The weight, from 1 to 1000 with 400 being normal and 700 bold.
+    pub weight_class: u16,
+    /// The width, from 1 (ultra-condensed) to 9 (ultra-expanded) with 5
+    /// being normal.
+    pub width_class: u16,
+    /// The embedding licensing rights.
+    pub fs_type: u16,
+    pub strikeout_size: i16,
+    pub strikeout_position: i16,
+    pub family_class: i16,
+    pub panose: FontPanose,
+    /// The unicode ranges the face is functional for.
+    pub unicode_coverage: FontUnicodeCoverage,
+    /// The identifier of the font vendor.
+    pub vendor_id: Tag,
+    /// The selection flags, like italic (bit 0) or bold (bit 5).
+    pub fs_selection: u16,
+    pub first_char_index: u16,
+    pub last_char_index: u16,
+    pub typo_ascender: i16,
+    pub typo_descender: i16,
+    pub typo_line_gap: i16,
+    pub win_ascent: u16,
+    pub win_descent: u16,
+    /// The code pages the face is functional for, since version 1.
+    pub code_page_range: Option<[u32; 2]>,
+    /// Since version 2.
+    pub x_height: Option<i16>,
+    /// Since version 2.
+    pub cap_height: Option<i16>,
+}
+
+impl Os2Table {
+    /// Parses the data of an `OS/2` table.
+    pub fn parse(data: &[u8]) -> Result<Self> {
+        let version = u16_at(data, 0)?;
+        let panose: [u8; 10] = bytes_at(data, 32, 10)?.try_into().unwrap();
+        let mut unicode_coverage = FontUnicodeCoverage::default();
+        for (i, range) in unicode_coverage.data.iter_mut().enumerate() {
+            *range = u32_at(data, 42 + i * 4)?;
+        }
+        let (code_page_range, x_height, cap_height) = if version >= 2 {
+            (
+                Some([u32_at(data, 78)?, u32_at(data, 82)?]),
+                Some(i16_at(data, 86)?),
+                Some(i16_at(data, 88)?),
+            )
+        } else if version == 1 {
+            (Some([u32_at(data, 78)?, u32_at(data, 82)?]), None, None)
+        } else {
+            (None, None, None)
+        };
+        Ok(Os2Table {
+            version,
+            avg_char_width: i16_at(data, 2)?,
+            weight_class: u16_at(data, 4)?,
+            width_class: u16_at(data, 6)?,
+            fs_type: u16_at(data, 8)?,
+            strikeout_size: i16_at(data, 26)?,
+            strikeout_position: i16_at(data, 28)?,
+            family_class: i16_at(data, 30)?,
+            panose: panose.into(),
+            unicode_coverage,
+            vendor_id: Tag::from(u32_at(data, 58)?),
+            fs_selection: u16_at(data, 62)?,
+            first_char_index: u16_at(data, 64)?,
+            last_char_index: u16_at(data, 66)?,
+            typo_ascender: i16_at(data, 68)?,
+            typo_descender: i16_at(data, 70)?,
+            typo_line_gap: i16_at(data, 72)?,
+            win_ascent: u16_at(data, 74)?,
+            win_descent: u16_at(data, 76)?,
+            code_page_range,
+            x_height,
+            cap_height,
+        })
+    }
+
+    /// Tests whether the italic bit of the selection flags is set.
+    #[inline]
+    pub fn is_italic(&self) -> bool {
+        self.fs_selection & 0x0001 != 0
+    }
+
+    /// Tests whether the bold bit of the selection flags is set.
+    #[inline]
+    pub fn is_bold(&self) -> bool {
+        self.fs_selection & 0x0020 != 0
+    }
+}
+
+#[cfg(test)]
+mod test_os2 {
+    use super::*;
+
+    fn table(version: u16, len: usize) -> Vec<u8> {
+        let mut data = vec![0; len];
+        data[..2].copy_from_slice(&version.to_be_bytes());
+        data[4..6].copy_from_slice(&700u16.to_be_bytes());
+        data[6..8].copy_from_slice(&5u16.to_be_bytes());
+        data[32..42].copy_from_slice(&[2, 11, 8, 9, 0, 0, 0, 0, 0, 0]);
+        data[42..46].copy_from_slice(&0b101u32.to_be_bytes());
+        data[54..58].copy_from_slice(&(1u32 << 25).to_be_bytes());
+        data[58..62].copy_from_slice(b"GOOG");
+        data[62..64].copy_from_slice(&0x21u16.to_be_bytes());
+        data
+    }
+
+    #[test]
+    fn test_parse() {
+        let os2 = Os2Table::parse(&table(4, 96)).unwrap();
+        assert_eq!(os2.weight_class, 700);
+        assert_eq!(os2.width_class, 5);
+        assert_eq!(os2.panose.family_kind, 2);
+        assert_eq!(os2.panose.weight, 8);
+        assert!(!os2.panose.is_empty());
+        assert!(os2.unicode_coverage.has_range(0));
+        assert!(!os2.unicode_coverage.has_range(1));
+        assert!(os2.unicode_coverage.has_range(2));
+        assert!(os2.unicode_coverage.has_range(121));
+        assert_eq!(os2.vendor_id, Tag::new(b"GOOG"));
+        assert!(os2.is_italic() && os2.is_bold());
+        assert_eq!(os2.code_page_range, Some([0, 0]));
+        assert_eq!(os2.x_height, Some(0));
+    }
+
+    #[test]
+    fn test_versions() {
+        let os2 = Os2Table::parse(&table(0, 78)).unwrap();
+        assert_eq!(os2.code_page_range, None);
+        assert_eq!(os2.x_height, None);
+        let os2 = Os2Table::parse(&table(1, 86)).unwrap();
+        assert_eq!(os2.code_page_range, Some([0, 0]));
+        assert_eq!(os2.cap_height, None);
+        assert!(Os2Table::parse(&table(1, 78)).is_err());
+        assert!(Os2Table::parse(&table(0, 68)).is_err());
+    }
+}
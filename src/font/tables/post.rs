@@ -0,0 +1,152 @@
+use std::str;
+
+use crate::error::Result;
+use crate::glyph_buffer::GlyphId;
+
+use super::{bytes_at, i16_at, u16_at, u32_at};
+
+/// The names of the standard Macintosh glyph order, used by version 1 tables
+/// and referenced by the glyph names of version 2 tables.
+#[rustfmt::skip]
+const MAC_GLYPH_NAMES: [&str; 258] = [
+    ".notdef", ".null", "nonmarkingreturn", "space", "exclam", "quotedbl", "numbersign", "dollar",
+    "percent", "ampersand", "quotesingle", "parenleft", "parenright", "asterisk", "plus", "comma",
+    "hyphen", "period", "slash", "zero", "one", "two", "three", "four", "five", "six", "seven",
+    "eight", "nine", "colon", "semicolon", "less", "equal", "greater", "question", "at", "A", "B",
+    "C", "D", "E", "F", "G", "H", "I", "J", "K", "L", "M", "N", "O", "P", "Q", "R", "S", "T", "U",
+    "V", "W", "X", "Y", "Z", "bracketleft", "backslash", "bracketright", "asciicircum",
+    "underscore", "grave", "a", "b", "c", "d", "e", "f", "g", "h", "i", "j", "k", "l", "m", "n",
+    "o", "p", "q", "r", "s", "t", "u", "v", "w", "x", "y", "z", "braceleft", "bar", "braceright",
+    "asciitilde", "Adieresis", "Aring", "Ccedilla", "Eacute", "Ntilde", "Odieresis", "Udieresis",
+    "aacute", "agrave", "acircumflex", "adieresis", "atilde", "aring", "ccedilla", "eacute",
+    "egrave", "ecircumflex", "edieresis", "iacute", "igrave", "icircumflex", "idieresis",
+    "ntilde", "oacute", "ograve", "ocircumflex", "odieresis", "otilde", "uacute", "ugrave",
+    "ucircumflex", "udieresis", "dagger", "degree", "cent", "sterling", "section", "bullet",
+    "paragraph", "germandbls", "registered", "copyright", "trademark", "acute", "dieresis",
+    "notequal", "AE", "Oslash", "infinity", "plusminus", "lessequal", "greaterequal", "yen", "mu",
+    "partialdiff", "summation", "product", "pi", "integral", "ordfeminine", "ordmasculine",
+    "Omega", "ae", "oslash", "questiondown", "exclamdown", "logicalnot", "radical", "florin",
+    "approxequal", "Delta", "guillemotleft", "guillemotright", "ellipsis", "nonbreakingspace",
+    "Agrave", "Atilde", "Otilde", "OE", "oe", "endash", "emdash", "quotedblleft", "quotedblright",
+    "quoteleft", "quoteright", "divide", "lozenge", "ydieresis", "Ydieresis", "fraction",
+    "currency", "guilsinglleft", "guilsinglright", "fi", "fl", "daggerdbl", "periodcentered",
+    "quotesinglbase", "quotedblbase", "perthousand", "Acircumflex", "Ecircumflex", "Aacute",
+    "Edieresis", "Egrave", "Iacute", "Icircumflex", "Idieresis", "Igrave", "Oacute",
+    "Ocircumflex", "apple", "Ograve", "Uacute", "Ucircumflex", "Ugrave", "dotlessi", "circumflex",
+    "tilde", "macron", "breve", "dotaccent", "ring", "cedilla", "hungarumlaut", "ogonek", "caron",
+    "Lslash", "lslash", "Scaron", "scaron", "Zcaron", "zcaron", "brokenbar", "Eth", "eth",
+    "Yacute", "yacute", "Thorn", "thorn", "minus", "multiply", "onesuperior", "twosuperior",
+    "threesuperior", "onehalf", "onequarter", "threequarters", "franc", "Gbreve", "gbreve",
+    "Idotaccent", "Scedilla", "scedilla", "Cacute", "cacute", "Ccaron", "ccaron", "dcroat",
+];
+
+/// The `post` table, holding information for PostScript printers and
+/// optionally the names of the glyphs.
+#[derive(Clone, Copy, Debug)]
+pub struct PostTable<'a> {
+    /// The version as a 16.16 fixed point number, `0x00020000` for 2.0.
+    pub version: u32,
+    /// The italic angle in degrees counter-clockwise from the vertical.
+    pub italic_angle: f64,
+    pub underline_position: i16,
+    pub underline_thickness: i16,
+    pub is_fixed_pitch: bool,
+    name_indices: &'a [u8],
+    names: &'a [u8],
+}
+
+impl<'a> PostTable<'a> {
+    /// Parses the data of a `post` table.
+    pub fn parse(data: &'a [u8]) -> Result<Self> {
+        bytes_at(data, 0, 32)?;
+        let version = u32_at(data, 0)?;
+        let (name_indices, names) = if version == 0x0002_0000 {
+            let glyph_count = u16_at(data, 32)? as usize;
+            let name_indices = bytes_at(data, 34, glyph_count * 2)?;
+            (name_indices, &data[34 + glyph_count * 2..])
+        } else {
+            (&[][..], &[][..])
+        };
+        Ok(PostTable {
+            version,
+            italic_angle: u32_at(data, 4)? as i32 as f64 / 65536.0,
+            underline_position: i16_at(data, 8)?,
+            underline_thickness: i16_at(data, 10)?,
+            is_fixed_pitch: u32_at(data, 12)? != 0,
+            name_indices,
+            names,
+        })
+    }
+
+    /// Returns the name of the glyph, which is only available for version
+    /// 1 and 2 tables.
+    ///
+    /// Returns `None` if the glyph has no name or the name is malformed.
+    pub fn glyph_name(&self, glyph_id: GlyphId) -> Option<&'a str> {
+        let index = match self.version {
+            0x0001_0000 => glyph_id,
+            0x0002_0000 => u16_at(self.name_indices, glyph_id as usize * 2).ok()?,
+            _ => return None,
+        } as usize;
+        if index < MAC_GLYPH_NAMES.len() {
+            return Some(MAC_GLYPH_NAMES[index]);
+        }
+        // The remaining names are stored as pascal strings.
+        let mut names = self.names;
+        for _ in MAC_GLYPH_NAMES.len()..index {
+            let len = *names.first()? as usize;
+            names = names.get(1 + len..)?;
+        }
+        let len = *names.first()? as usize;
+        str::from_utf8(names.get(1..1 + len)?).ok()
+    }
+}
+
+#[cfg(test)]
+mod test_post {
+    use super::*;
+
+    fn table(version: u32) -> Vec<u8> {
+        let mut data = vec![0; 32];
+        data[..4].copy_from_slice(&version.to_be_bytes());
+        data[4..8].copy_from_slice(&(-12i32 * 65536 - 32768).to_be_bytes());
+        data[8..10].copy_from_slice(&(-75i16).to_be_bytes());
+        data[10..12].copy_from_slice(&50i16.to_be_bytes());
+        data[12..16].copy_from_slice(&1u32.to_be_bytes());
+        data
+    }
+
+    #[test]
+    fn test_header() {
+        let data = table(0x0003_0000);
+        let post = PostTable::parse(&data).unwrap();
+        assert_eq!(post.italic_angle, -12.5);
+        assert_eq!(post.underline_position, -75);
+        assert_eq!(post.underline_thickness, 50);
+        assert!(post.is_fixed_pitch);
+        assert_eq!(post.glyph_name(0), None);
+        assert!(PostTable::parse(&data[..31]).is_err());
+    }
+
+    #[test]
+    fn test_glyph_names() {
+        let data = table(0x0001_0000);
+        let post = PostTable::parse(&data).unwrap();
+        assert_eq!(post.glyph_name(36), Some("A"));
+        assert_eq!(post.glyph_name(257), Some("dcroat"));
+        assert_eq!(post.glyph_name(258), None);
+
+        let mut data = table(0x0002_0000);
+        data.extend_from_slice(&4u16.to_be_bytes());
+        for &index in &[0u16, 259, 258, 300] {
+            data.extend_from_slice(&index.to_be_bytes());
+        }
+        data.extend_from_slice(b"\x03f_i\x07uni20AC");
+        let post = PostTable::parse(&data).unwrap();
+        assert_eq!(post.glyph_name(0), Some(".notdef"));
+        assert_eq!(post.glyph_name(1), Some("uni20AC"));
+        assert_eq!(post.glyph_name(2), Some("f_i"));
+        assert_eq!(post.glyph_name(3), None);
+        assert_eq!(post.glyph_name(4), None);
+    }
+}
@@ -1,9 +1,11 @@
 use bitflags::bitflags;
 
-use std::fmt;
+use std::marker::PhantomData;
+use std::{fmt, mem};
 
 use crate::{
     geometry::{BoxD, BoxI, PointD, PointI},
+    glyph_buffer::GlyphId,
     Tag,
 };
 
@@ -223,10 +225,10 @@ pub(in crate) struct GlyphInfo {
     reserved: [u32; 2],
 }
 
-#[allow(dead_code)]
+/// The offset and advance of a glyph in design units.
 #[repr(C)]
-#[derive(Debug)]
-pub(in crate) struct GlyphPlacement {
+#[derive(Debug, Copy, Clone, Default, PartialEq)]
+pub struct GlyphPlacement {
     pub placement: PointI,
     pub advance: PointI,
 }
@@ -249,17 +251,55 @@ impl GlyphMappingState {
     }
 }
 
-#[allow(dead_code)]
+/// Information about the glyph whose outlines have just been added to a path
+/// by [`Font::glyph_run_outlines_with_sink`].
+///
+/// [`Font::glyph_run_outlines_with_sink`]: ../font/struct.Font.html#method.glyph_run_outlines_with_sink
 #[repr(C)]
 #[derive(Debug)]
-pub(in crate) struct GlyphOutlineSinkInfo {
+pub struct GlyphOutlineSinkInfo {
+    /// The index of the glyph in the glyph run.
     pub glyph_index: usize,
+    /// The number of contours of the glyph.
     pub contour_count: usize,
 }
 
 // Fixme figure out what glyph run actually does and expose a proper api
 pub struct GlyphRun<'a> {
-    pub(in crate) raw: &'a ffi::BLGlyphRun,
+    pub(in crate) raw: ffi::BLGlyphRun,
+    _marker: PhantomData<&'a ()>,
+}
+
+impl<'a> GlyphRun<'a> {
+    /// Wraps a raw glyph run, whose data has to outlive `'a`.
+    #[inline]
+    pub(in crate) unsafe fn from_raw(raw: ffi::BLGlyphRun) -> Self {
+        GlyphRun {
+            raw,
+            _marker: PhantomData,
+        }
+    }
+
+    /// Creates a glyph run over the given glyphs and their placements.
+    pub(in crate) fn new(
+        glyphs: &'a [GlyphId],
+        placements: &'a [GlyphPlacement],
+        placement_type: GlyphPlacementType,
+    ) -> Self {
+        debug_assert_eq!(glyphs.len(), placements.len());
+        unsafe {
+            Self::from_raw(ffi::BLGlyphRun {
+                glyphData: glyphs.as_ptr() as *mut _,
+                placementData: placements.as_ptr() as *mut _,
+                size: glyphs.len(),
+                glyphSize: mem::size_of::<GlyphId>() as u8,
+                placementType: placement_type as u8,
+                glyphAdvance: mem::size_of::<GlyphId>() as i8,
+                placementAdvance: mem::size_of::<GlyphPlacement>() as i8,
+                flags: 0,
+            })
+        }
+    }
 }
 
 impl fmt::Debug for GlyphRun<'_> {
@@ -286,26 +326,86 @@ pub struct FontTable<'a> {
     pub data: &'a [u8],
 }
 
+/// An OpenType feature and its value, usually 0 to disable and 1 to enable
+/// it.
 #[repr(C)]
-#[derive(Debug)]
+#[derive(Debug, Copy, Clone, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub struct FontFeature {
     pub tag: Tag,
     pub value: u32,
 }
 
+/// A variation axis of a variable font and its value.
 #[repr(C)]
-#[derive(Debug)]
+#[derive(Debug, Copy, Clone, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub struct FontVariation {
     pub tag: Tag,
-    pub value: u32,
+    pub value: f32,
 }
 
 #[repr(C)]
-#[derive(Debug)]
+#[derive(Debug, Copy, Clone, Default, PartialEq, Eq)]
 pub struct FontUnicodeCoverage {
     pub data: [u32; 4],
 }
 
+impl FontUnicodeCoverage {
+    /// Tests whether the unicode range with the given bit index, as assigned
+    /// by the `ulUnicodeRange` fields of the OS/2 table, is covered.
+    #[inline]
+    pub fn has_range(&self, bit: u32) -> bool {
+        bit < 128 && self.data[(bit / 32) as usize] & (1 << (bit % 32)) != 0
+    }
+}
+
+/// The PANOSE classification of a font-face, as stored in the OS/2 table.
+///
+/// The meaning of the fields past `family_kind` depends on the family kind,
+/// the names are those of the Latin Text kind.
+#[repr(C)]
+#[derive(Debug, Copy, Clone, Default, PartialEq, Eq, Hash)]
+pub struct FontPanose {
+    pub family_kind: u8,
+    pub serif_style: u8,
+    pub weight: u8,
+    pub proportion: u8,
+    pub contrast: u8,
+    pub stroke_variation: u8,
+    pub arm_style: u8,
+    pub letterform: u8,
+    pub midline: u8,
+    pub x_height: u8,
+}
+
+impl FontPanose {
+    /// Tests whether the classification is missing, which is the case if
+    /// all fields are zero.
+    #[inline]
+    pub fn is_empty(&self) -> bool {
+        *self == FontPanose::default()
+    }
+}
+
+impl From<[u8; 10]> for FontPanose {
+    #[inline]
+    fn from(data: [u8; 10]) -> Self {
+        FontPanose {
+            family_kind: data[0],
+            serif_style: data[1],
+            weight: data[2],
+            proportion: data[3],
+            contrast: data[4],
+            stroke_variation: data[5],
+            arm_style: data[6],
+            letterform: data[7],
+            midline: data[8],
+            x_height: data[9],
+        }
+    }
+}
+
 #[repr(C)]
 #[derive(Debug)]
 pub struct FontMatrix(pub [f32; 4]);
@@ -177,6 +177,8 @@ pub trait Rect: private::Sealed {
     const CLEAR_RECT: ClearRectFn<Self::FfiType>;
     #[doc(hidden)]
     const BLIT_SCALED_IMAGE: BlitImageFn<Self::FfiType>;
+    #[doc(hidden)]
+    fn to_f64(&self) -> [f64; 4];
 }
 
 impl Rect for RectI {
@@ -188,6 +190,15 @@ impl Rect for RectI {
     const CLEAR_RECT: ClearRectFn<Self::FfiType> = ffi::blContextClearRectI;
     #[doc(hidden)]
     const BLIT_SCALED_IMAGE: BlitImageFn<Self::FfiType> = ffi::blContextBlitScaledImageI;
+    #[doc(hidden)]
+    fn to_f64(&self) -> [f64; 4] {
+        [
+            f64::from(self.x),
+            f64::from(self.y),
+            f64::from(self.w),
+            f64::from(self.h),
+        ]
+    }
 }
 
 impl Rect for RectD {
@@ -199,6 +210,10 @@ impl Rect for RectD {
     const CLEAR_RECT: ClearRectFn<Self::FfiType> = ffi::blContextClearRectD;
     #[doc(hidden)]
     const BLIT_SCALED_IMAGE: BlitImageFn<Self::FfiType> = ffi::blContextBlitScaledImageD;
+    #[doc(hidden)]
+    fn to_f64(&self) -> [f64; 4] {
+        [self.x, self.y, self.w, self.h]
+    }
 }
 
 use ffi::BLGeometryDirection::*;
@@ -273,6 +288,7 @@ bl_enum! {
 
 #[repr(C)]
 #[derive(Copy, Clone, Debug, Default, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub struct PointI {
     pub x: i32,
     pub y: i32,
@@ -280,6 +296,7 @@ pub struct PointI {
 
 #[repr(C)]
 #[derive(Copy, Clone, Debug, Default, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub struct PointD {
     pub x: f64,
     pub y: f64,
@@ -287,6 +304,7 @@ pub struct PointD {
 
 #[repr(C)]
 #[derive(Copy, Clone, Debug, Default, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub struct SizeI {
     pub w: i32,
     pub h: i32,
@@ -294,6 +312,7 @@ pub struct SizeI {
 
 #[repr(C)]
 #[derive(Copy, Clone, Debug, Default, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub struct SizeD {
     pub w: f64,
     pub h: f64,
@@ -301,6 +320,7 @@ pub struct SizeD {
 
 #[repr(C)]
 #[derive(Copy, Clone, Debug, Default, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub struct BoxI {
     pub x0: i32,
     pub y0: i32,
@@ -314,6 +334,7 @@ impl Geometry for BoxI {
 
 #[repr(C)]
 #[derive(Copy, Clone, Debug, Default, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub struct BoxD {
     pub x0: f64,
     pub y0: f64,
@@ -327,6 +348,7 @@ impl Geometry for BoxD {
 
 #[repr(C)]
 #[derive(Copy, Clone, Debug, Default, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub struct RectI {
     pub x: i32,
     pub y: i32,
@@ -340,6 +362,7 @@ impl Geometry for RectI {
 
 #[repr(C)]
 #[derive(Copy, Clone, Debug, Default, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub struct RectD {
     pub x: f64,
     pub y: f64,
@@ -353,6 +376,7 @@ impl Geometry for RectD {
 
 #[repr(C)]
 #[derive(Copy, Clone, Debug, Default, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub struct Line {
     pub x0: f64,
     pub y0: f64,
@@ -366,6 +390,7 @@ impl Geometry for Line {
 
 #[repr(C)]
 #[derive(Copy, Clone, Debug, Default, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub struct Triangle {
     pub x0: f64,
     pub y0: f64,
@@ -381,6 +406,7 @@ impl Geometry for Triangle {
 
 #[repr(C)]
 #[derive(Copy, Clone, Debug, Default, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub struct RoundRect {
     pub x: f64,
     pub y: f64,
@@ -396,6 +422,7 @@ impl Geometry for RoundRect {
 
 #[repr(C)]
 #[derive(Copy, Clone, Debug, Default, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub struct Circle {
     pub cx: f64,
     pub cy: f64,
@@ -408,6 +435,7 @@ impl Geometry for Circle {
 
 #[repr(C)]
 #[derive(Copy, Clone, Debug, Default, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub struct Ellipse {
     pub cx: f64,
     pub cy: f64,
@@ -421,6 +449,7 @@ impl Geometry for Ellipse {
 
 #[repr(C)]
 #[derive(Copy, Clone, Debug, Default, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub struct Arc {
     pub cx: f64,
     pub cy: f64,
@@ -436,6 +465,7 @@ impl Geometry for Arc {
 
 #[repr(C)]
 #[derive(Copy, Clone, Debug, Default, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub struct Chord {
     pub cx: f64,
     pub cy: f64,
@@ -451,6 +481,7 @@ impl Geometry for Chord {
 
 #[repr(C)]
 #[derive(Copy, Clone, Debug, Default, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub struct Pie {
     pub cx: f64,
     pub cy: f64,
@@ -0,0 +1,79 @@
+//! Conversions between the geometry types and the ones of `glam`.
+use glam::{DAffine2, DVec2, IVec2};
+
+use crate::geometry::{PointD, PointI, SizeD};
+use crate::matrix::Matrix2D;
+
+impl From<DVec2> for PointD {
+    #[inline]
+    fn from(v: DVec2) -> Self {
+        PointD { x: v.x, y: v.y }
+    }
+}
+
+impl From<PointD> for DVec2 {
+    #[inline]
+    fn from(p: PointD) -> Self {
+        DVec2::new(p.x, p.y)
+    }
+}
+
+impl From<IVec2> for PointI {
+    #[inline]
+    fn from(v: IVec2) -> Self {
+        PointI { x: v.x, y: v.y }
+    }
+}
+
+impl From<PointI> for IVec2 {
+    #[inline]
+    fn from(p: PointI) -> Self {
+        IVec2::new(p.x, p.y)
+    }
+}
+
+impl From<DVec2> for SizeD {
+    #[inline]
+    fn from(v: DVec2) -> Self {
+        SizeD { w: v.x, h: v.y }
+    }
+}
+
+impl From<SizeD> for DVec2 {
+    #[inline]
+    fn from(s: SizeD) -> Self {
+        DVec2::new(s.w, s.h)
+    }
+}
+
+/// The columns of the affine transform are the rows of [`Matrix2D`], the
+/// last one being the translation.
+impl From<DAffine2> for Matrix2D {
+    #[inline]
+    fn from(a: DAffine2) -> Self {
+        let [m00, m01, m10, m11, m20, m21] = a.to_cols_array();
+        Matrix2D::new(m00, m01, m10, m11, m20, m21)
+    }
+}
+
+impl From<Matrix2D> for DAffine2 {
+    #[inline]
+    fn from(m: Matrix2D) -> Self {
+        DAffine2::from_cols_array(&m.coefficients())
+    }
+}
+
+#[cfg(test)]
+mod test_glam_impls {
+    use super::*;
+
+    #[test]
+    fn test_affine() {
+        let affine = DAffine2::from_angle_translation(0.5, DVec2::new(3.0, -2.0));
+        let m = Matrix2D::from(affine);
+        let p = DVec2::new(1.5, 2.5);
+        let mapped = DVec2::from(m.map_point(&p.into()));
+        assert!(mapped.abs_diff_eq(affine.transform_point2(p), 1e-12));
+        assert_eq!(DAffine2::from(m), affine);
+    }
+}
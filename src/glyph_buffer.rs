@@ -1,7 +1,8 @@
-use std::{fmt, ptr};
+use std::ops::Range;
+use std::{fmt, mem, ptr, slice};
 
 use crate::error::expect_mem_err;
-use crate::font_defs::{GlyphRun, GlyphRunFlags};
+use crate::font_defs::{GlyphInfo, GlyphItem, GlyphPlacement, GlyphRun, GlyphRunFlags};
 use crate::variant::WrappedBlCore;
 
 pub type GlyphId = u16;
@@ -18,9 +19,11 @@ pub type GlyphId = u16;
 /// buffer is either used as a scratch buffer during glyph substitution or hold
 /// glyph positions after the processing is complete and glyph positions were
 /// calculated.
-#[repr(transparent)]
+#[repr(C)]
 pub struct GlyphBuffer {
     pub(in crate) core: ffi::BLGlyphBufferCore,
+    /// The length of the text in code units, clusters are offsets into it.
+    text_len: usize,
 }
 
 unsafe impl WrappedBlCore for GlyphBuffer {
@@ -29,7 +32,7 @@ unsafe impl WrappedBlCore for GlyphBuffer {
 
     #[inline]
     fn from_core(core: Self::Core) -> GlyphBuffer {
-        GlyphBuffer { core }
+        GlyphBuffer { core, text_len: 0 }
     }
 
     /// Retrieves the none version of Self::Core
@@ -46,6 +49,7 @@ impl GlyphBuffer {
             core: ffi::BLGlyphBufferCore {
                 impl_: ptr::null_mut(),
             },
+            text_len: 0,
         };
         unsafe { ffi::blGlyphBufferInit(&mut this.core) };
         this
@@ -60,13 +64,74 @@ impl GlyphBuffer {
 
     #[inline]
     pub fn glyph_run(&self) -> GlyphRun<'_> {
+        unsafe { GlyphRun::from_raw(*ffi::blGlyphBufferGetGlyphRun(self.core())) }
+    }
+
+    /// Returns the id of every glyph.
+    ///
+    /// The ids are only meaningful after the text has been mapped to glyphs,
+    /// see [`has_glyphs`](#method.has_glyphs).
+    pub fn glyph_ids(&self) -> impl ExactSizeIterator<Item = GlyphId> + '_ {
+        self.glyph_items().iter().map(|item| item.glyph_id)
+    }
+
+    /// Returns the cluster of every glyph, which is the offset into the text
+    /// of the first character the glyph has been created from.
+    ///
+    /// The offset is counted in code units of the encoding the text was set
+    /// in, which are bytes for UTF-8 and Latin-1 text. For glyph ids it is the
+    /// index of the glyph.
+    ///
+    /// Glyphs created from the same characters, like the glyphs of a
+    /// decomposed character, share their cluster. A ligature gets the
+    /// cluster of the first of its characters.
+    pub fn clusters(&self) -> impl ExactSizeIterator<Item = usize> + '_ {
+        self.glyph_infos().iter().map(|info| info.cluster as usize)
+    }
+
+    /// Returns the range of the text the glyph at `index` has been created
+    /// from, which ends at the start of the following cluster.
+    ///
+    /// Like the [`clusters`](#method.clusters) the range is counted in code
+    /// units of the text.
+    ///
+    /// # Panics
+    ///
+    /// Panics if `index` is out of bounds.
+    pub fn cluster_range(&self, index: usize) -> Range<usize> {
+        let start = self.glyph_infos()[index].cluster as usize;
+        cluster_range(self.clusters(), start, self.text_len)
+    }
+
+    /// Returns the placement of every glyph, which is empty until the glyphs
+    /// have been positioned.
+    #[inline]
+    pub fn placements(&self) -> &[GlyphPlacement] {
+        unsafe { slice_or_empty(ffi::blGlyphBufferGetPlacementData(self.core()), self.size()) }
+    }
+
+    /// Returns the placement of every glyph mutably, for adjusting the
+    /// positions computed by shaping.
+    #[inline]
+    pub fn placements_mut(&mut self) -> &mut [GlyphPlacement] {
         unsafe {
-            GlyphRun {
-                raw: &*ffi::blGlyphBufferGetGlyphRun(self.core()),
+            let data = ffi::blGlyphBufferGetPlacementData(self.core());
+            if data.is_null() {
+                &mut []
+            } else {
+                slice::from_raw_parts_mut(data as *mut GlyphPlacement, self.size())
             }
         }
     }
 
+    fn glyph_items(&self) -> &[GlyphItem] {
+        unsafe { slice_or_empty(ffi::blGlyphBufferGetContent(self.core()), self.size()) }
+    }
+
+    fn glyph_infos(&self) -> &[GlyphInfo] {
+        unsafe { slice_or_empty(ffi::blGlyphBufferGetInfoData(self.core()), self.size()) }
+    }
+
     #[inline]
     pub fn size(&self) -> usize {
         unsafe { ffi::blGlyphBufferGetSize(self.core()) }
@@ -116,19 +181,89 @@ impl GlyphBuffer {
     #[inline]
     pub fn clear(&mut self) {
         unsafe { ffi::blGlyphBufferClear(self.core_mut()) };
+        self.text_len = 0;
     }
 
     /// Sets text content of this [`GlyphBuffer`].
     #[inline]
     pub fn set_utf8_text(&mut self, text: &str) {
+        self.set_text(text.as_bytes(), ffi::BLTextEncoding::BL_TEXT_ENCODING_UTF8);
+    }
+
+    /// Sets UTF-16 encoded text content of this [`GlyphBuffer`].
+    ///
+    /// Unpaired surrogates are replaced and flagged as
+    /// [`has_invalid_chars`](#method.has_invalid_chars).
+    #[inline]
+    pub fn set_utf16_text(&mut self, text: &[u16]) {
+        self.set_text(text, ffi::BLTextEncoding::BL_TEXT_ENCODING_UTF16);
+    }
+
+    /// Sets UTF-32 encoded text content of this [`GlyphBuffer`].
+    ///
+    /// Invalid code points are replaced and flagged as
+    /// [`has_invalid_chars`](#method.has_invalid_chars).
+    #[inline]
+    pub fn set_utf32_text(&mut self, text: &[u32]) {
+        self.set_text(text, ffi::BLTextEncoding::BL_TEXT_ENCODING_UTF32);
+    }
+
+    /// Sets Latin-1 (ISO 8859-1) encoded text content of this
+    /// [`GlyphBuffer`].
+    #[inline]
+    pub fn set_latin1_text(&mut self, text: &[u8]) {
+        self.set_text(text, ffi::BLTextEncoding::BL_TEXT_ENCODING_LATIN1);
+    }
+
+    /// Sets the content of this [`GlyphBuffer`] to already mapped glyphs,
+    /// skipping character to glyph mapping.
+    #[inline]
+    pub fn set_glyph_ids(&mut self, glyph_ids: &[GlyphId]) {
+        unsafe {
+            expect_mem_err(ffi::blGlyphBufferSetGlyphsFromStruct(
+                self.core_mut(),
+                glyph_ids.as_ptr() as *const _,
+                glyph_ids.len(),
+                mem::size_of::<GlyphId>(),
+                mem::size_of::<GlyphId>() as isize,
+            ))
+        };
+        self.text_len = glyph_ids.len();
+    }
+
+    fn set_text<T>(&mut self, text: &[T], encoding: ffi::BLTextEncoding::Type) {
         unsafe {
             expect_mem_err(ffi::blGlyphBufferSetText(
                 self.core_mut(),
-                text.as_bytes().as_ptr() as *const _,
+                text.as_ptr() as *const _,
                 text.len(),
-                ffi::BLTextEncoding::BL_TEXT_ENCODING_UTF8 as u32,
+                encoding as u32,
             ))
         };
+        self.text_len = text.len();
+    }
+}
+
+/// Returns the range from `start` to the smallest cluster after it, or to the
+/// end of the text. The clusters of right-to-left text are in descending
+/// order.
+fn cluster_range<I>(clusters: I, start: usize, text_len: usize) -> Range<usize>
+where
+    I: IntoIterator<Item = usize>,
+{
+    let end = clusters
+        .into_iter()
+        .filter(|&cluster| cluster > start)
+        .min()
+        .unwrap_or(text_len);
+    start..end.max(start)
+}
+
+unsafe fn slice_or_empty<'a, T, U>(data: *const T, len: usize) -> &'a [U] {
+    if data.is_null() {
+        &[]
+    } else {
+        slice::from_raw_parts(data as *const U, len)
     }
 }
 
@@ -150,3 +285,82 @@ impl fmt::Debug for GlyphBuffer {
         f.debug_struct("GlyphBuffer").finish()
     }
 }
+
+#[cfg(test)]
+mod test_glyph_buffer {
+    use super::{cluster_range, GlyphBuffer, GlyphId};
+    use crate::font::{Font, FontFace};
+    use crate::DataAccessFlags;
+
+    fn font() -> Font {
+        let path = concat!(env!("CARGO_MANIFEST_DIR"), "/assets/NotoSans-Regular.ttf");
+        let face = FontFace::from_path(path, DataAccessFlags::READ).unwrap();
+        face.create_font(20.0).unwrap()
+    }
+
+    fn shape(font: &Font, set_text: impl FnOnce(&mut GlyphBuffer)) -> GlyphBuffer {
+        let mut buf = GlyphBuffer::new();
+        set_text(&mut buf);
+        font.shape(&mut buf).unwrap();
+        buf
+    }
+
+    #[test]
+    fn test_cluster_range() {
+        // "ffi" as a ligature followed by "é" decomposed into two glyphs.
+        let clusters = [0, 3, 3];
+        assert_eq!(cluster_range(clusters.iter().copied(), 0, 5), 0..3);
+        assert_eq!(cluster_range(clusters.iter().copied(), 3, 5), 3..5);
+        // Right-to-left text.
+        let clusters = [4, 2, 0];
+        assert_eq!(cluster_range(clusters.iter().copied(), 2, 6), 2..4);
+        assert_eq!(cluster_range(clusters.iter().copied(), 4, 6), 4..6);
+        assert_eq!(cluster_range(clusters.iter().copied(), 0, 6), 0..2);
+    }
+
+    #[test]
+    fn test_clusters() {
+        let font = font();
+        // Characters of one to three bytes, Noto Sans maps "漢" to the
+        // undefined glyph.
+        let text = "a€ü漢b";
+        let buf = shape(&font, |buf| buf.set_utf8_text(text));
+        assert_eq!(buf.size(), 5);
+        assert!(buf.clusters().eq([0, 1, 4, 6, 9].iter().copied()));
+        let chars: Vec<&str> = (0..buf.size())
+            .map(|index| &text[buf.cluster_range(index)])
+            .collect();
+        assert_eq!(chars, ["a", "€", "ü", "漢", "b"]);
+    }
+
+    #[test]
+    fn test_encodings() {
+        let font = font();
+        let text = "Wave façade";
+        let utf8 = shape(&font, |buf| buf.set_utf8_text(text));
+        let utf16: Vec<u16> = text.encode_utf16().collect();
+        let utf16 = shape(&font, |buf| buf.set_utf16_text(&utf16));
+        let utf32: Vec<u32> = text.chars().map(u32::from).collect();
+        let utf32 = shape(&font, |buf| buf.set_utf32_text(&utf32));
+        let latin1: Vec<u8> = text.chars().map(|c| c as u8).collect();
+        let latin1 = shape(&font, |buf| buf.set_latin1_text(&latin1));
+
+        let glyph_ids: Vec<GlyphId> = utf8.glyph_ids().collect();
+        assert_eq!(glyph_ids.len(), text.chars().count());
+        for buf in &[&utf16, &utf32, &latin1] {
+            assert!(buf.glyph_ids().eq(glyph_ids.iter().copied()));
+            assert_eq!(buf.placements(), utf8.placements());
+        }
+        // Clusters are offsets in code units, "ç" takes two bytes in UTF-8.
+        assert_eq!(utf8.cluster_range(7), 7..9);
+        assert_eq!(utf8.cluster_range(8), 9..10);
+        assert_eq!(utf16.cluster_range(7), 7..8);
+        assert_eq!(utf32.cluster_range(7), 7..8);
+        assert_eq!(latin1.cluster_range(7), 7..8);
+
+        let mut buf = GlyphBuffer::new();
+        buf.set_glyph_ids(&glyph_ids);
+        assert!(buf.has_glyphs());
+        assert!(buf.glyph_ids().eq(glyph_ids.iter().copied()));
+    }
+}
@@ -7,10 +7,11 @@ use std::{fmt, mem, ptr, slice};
 
 use ffi::BLGradientValue::*;
 
+use crate::color::{Color, Rgba32, Rgba64};
 use crate::error::{expect_mem_err, OutOfMemory};
 use crate::matrix::{Matrix2D, Matrix2DOp, MatrixTransform};
 use crate::util::range_to_tuple;
-use crate::variant::WrappedBlCore;
+use crate::variant::{BlVariantCore, WrappedBlCore};
 use crate::ExtendMode;
 
 mod private {
@@ -54,15 +55,28 @@ impl GradientType for Conical {
 }
 
 /// An offset with an associated color for a gradient.
+#[repr(C)]
 #[derive(Copy, Clone, Debug, Default, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub struct GradientStop {
     pub offset: f64,
-    pub rgba: u64,
+    pub rgba: Rgba64,
+}
+
+impl GradientStop {
+    #[inline]
+    pub fn new<C: Into<Color>>(offset: f64, color: C) -> Self {
+        GradientStop {
+            offset,
+            rgba: color.into().into(),
+        }
+    }
 }
 
 /// The values that make up a [`LinearGradient`].
 #[repr(C)]
 #[derive(Copy, Clone, Debug, Default, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub struct LinearGradientValues {
     pub x0: f64,
     pub y0: f64,
@@ -73,6 +87,7 @@ pub struct LinearGradientValues {
 /// The values that make up a [`RadialGradient`].
 #[repr(C)]
 #[derive(Copy, Clone, Debug, Default, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub struct RadialGradientValues {
     pub x0: f64,
     pub y0: f64,
@@ -84,6 +99,7 @@ pub struct RadialGradientValues {
 /// The values that make up a [`ConicalGradient`].
 #[repr(C)]
 #[derive(Copy, Clone, Debug, Default, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub struct ConicalGradientValues {
     pub x0: f64,
     pub y0: f64,
@@ -91,13 +107,35 @@ pub struct ConicalGradientValues {
 }
 
 /// A Dynamic Gradient
-#[derive(Debug)]
+#[derive(Clone, Debug, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub enum DynamicGradient {
     Linear(LinearGradient),
     Radial(RadialGradient),
     Conical(ConicalGradient),
 }
 
+impl DynamicGradient {
+    /// Wraps the core in the variant matching its gradient type.
+    pub(in crate) fn from_core(core: ffi::BLGradientCore) -> Self {
+        use ffi::BLGradientType::*;
+        match u32::from(core.impl_().gradientType) {
+            BL_GRADIENT_TYPE_RADIAL => DynamicGradient::Radial(Gradient::from_core(core)),
+            BL_GRADIENT_TYPE_CONICAL => DynamicGradient::Conical(Gradient::from_core(core)),
+            _ => DynamicGradient::Linear(Gradient::from_core(core)),
+        }
+    }
+
+    /// The variant core of the contained gradient.
+    pub(in crate) fn as_variant_core(&self) -> &ffi::BLVariantCore {
+        match self {
+            DynamicGradient::Linear(g) => g.core().as_variant_core(),
+            DynamicGradient::Radial(g) => g.core().as_variant_core(),
+            DynamicGradient::Conical(g) => g.core().as_variant_core(),
+        }
+    }
+}
+
 impl From<LinearGradient> for DynamicGradient {
     fn from(g: LinearGradient) -> Self {
         DynamicGradient::Linear(g)
@@ -401,20 +439,22 @@ impl<T: GradientType> Gradient<T> {
             expect_mem_err(ffi::blGradientAddStopRgba64(
                 self.core_mut(),
                 stop.offset,
-                stop.rgba,
+                stop.rgba.0,
             ))
         };
     }
 
     /// Adds a gradient stop to the buffer.
     #[inline]
-    pub fn add_stop32(&mut self, offset: f64, rgba: u32) {
+    pub fn add_stop32<C: Into<Rgba32>>(&mut self, offset: f64, rgba: C) {
+        let rgba = rgba.into().0;
         unsafe { expect_mem_err(ffi::blGradientAddStopRgba32(self.core_mut(), offset, rgba)) };
     }
 
     /// Adds a gradient stop to the buffer.
     #[inline]
-    pub fn add_stop64(&mut self, offset: f64, rgba: u64) {
+    pub fn add_stop64<C: Into<Rgba64>>(&mut self, offset: f64, rgba: C) {
+        let rgba = rgba.into().0;
         unsafe { expect_mem_err(ffi::blGradientAddStopRgba64(self.core_mut(), offset, rgba)) };
     }
 }
@@ -662,6 +702,7 @@ impl<T: GradientType> Drop for Gradient<T> {
 #[cfg(test)]
 mod test_gradient {
     use crate::{
+        color::Rgba64,
         gradient::{Conical, Gradient, GradientStop, Linear, LinearGradientValues},
         matrix::{Matrix2D, MatrixTransform},
         ExtendMode,
@@ -686,7 +727,7 @@ mod test_gradient {
         };
         let stops = [GradientStop {
             offset: 0.5,
-            rgba: 0xFF_12_34_56,
+            rgba: Rgba64(0xFF_12_34_56),
         }];
         let mat = Matrix2D::scaling(1.0, 2.0);
 
@@ -707,7 +748,7 @@ mod test_gradient {
         };
         let stops = [GradientStop {
             offset: 0.5,
-            rgba: 0xFF_12_34_56,
+            rgba: Rgba64(0xFF_12_34_56),
         }];
         let mat = Matrix2D::scaling(1.0, 2.0);
 
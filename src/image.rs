@@ -1,7 +1,9 @@
 //! Image loading and handling.
 use bitflags::bitflags;
 
+use std::convert::TryInto;
 use std::ffi::CString;
+use std::marker::PhantomData;
 use std::path::Path;
 use std::{fmt, mem, ops, ptr, slice};
 
@@ -9,9 +11,10 @@ use ffi::{self, BLImageCore};
 
 use crate::array::Array;
 use crate::codec::ImageCodec;
-use crate::error::{errcode_to_result, expect_mem_err, Result};
+use crate::color::Rgba32;
+use crate::error::{errcode_to_result, expect_mem_err, Error, Result};
 use crate::geometry::{SizeD, SizeI};
-use crate::variant::WrappedBlCore;
+use crate::variant::{DeepClone, WrappedBlCore};
 
 const IMAGE_SCALE_OPTIONS_ZEROED: ffi::BLImageScaleOptions = ffi::BLImageScaleOptions {
     userFunc: None,
@@ -36,6 +39,97 @@ bl_enum! {
     Default => PRgb32
 }
 
+impl ImageFormat {
+    /// The number of bytes a pixel of this format occupies.
+    #[inline]
+    pub fn bytes_per_pixel(self) -> usize {
+        match self {
+            ImageFormat::PRgb32 | ImageFormat::XRgb32 => 4,
+            ImageFormat::A8 => 1,
+        }
+    }
+}
+
+/// A pixel of a [`PRgb32`] or [`XRgb32`] image, stored as premultiplied
+/// `0xAARRGGBB` in native byte order.
+///
+/// [`PRgb32`]: enum.ImageFormat.html#variant.PRgb32
+/// [`XRgb32`]: enum.ImageFormat.html#variant.XRgb32
+#[repr(transparent)]
+#[derive(Copy, Clone, Debug, Default, PartialEq, Eq, Hash)]
+pub struct PRgb32Pixel(pub u32);
+
+impl PRgb32Pixel {
+    #[inline]
+    pub fn r(self) -> u8 {
+        (self.0 >> 16) as u8
+    }
+
+    #[inline]
+    pub fn g(self) -> u8 {
+        (self.0 >> 8) as u8
+    }
+
+    #[inline]
+    pub fn b(self) -> u8 {
+        self.0 as u8
+    }
+
+    #[inline]
+    pub fn a(self) -> u8 {
+        (self.0 >> 24) as u8
+    }
+}
+
+impl From<Rgba32> for PRgb32Pixel {
+    /// Premultiplies the color.
+    #[inline]
+    fn from(color: Rgba32) -> Self {
+        PRgb32Pixel(color.premultiplied().0)
+    }
+}
+
+impl From<PRgb32Pixel> for Rgba32 {
+    /// Unpremultiplies the pixel.
+    #[inline]
+    fn from(pixel: PRgb32Pixel) -> Self {
+        Rgba32(pixel.0).unpremultiplied()
+    }
+}
+
+mod private {
+    pub trait Sealed {}
+    impl Sealed for super::PRgb32Pixel {}
+    impl Sealed for u8 {}
+}
+
+/// A type the rows of an image can be viewed as, [`PRgb32Pixel`] for
+/// [`PRgb32`] and [`XRgb32`] images or `u8` for [`A8`] images.
+///
+/// [`PRgb32`]: enum.ImageFormat.html#variant.PRgb32
+/// [`XRgb32`]: enum.ImageFormat.html#variant.XRgb32
+/// [`A8`]: enum.ImageFormat.html#variant.A8
+pub trait Pixel: private::Sealed + Copy + 'static {
+    #[doc(hidden)]
+    fn is_pixel_of(format: ImageFormat) -> bool;
+}
+
+impl Pixel for PRgb32Pixel {
+    #[doc(hidden)]
+    #[inline]
+    fn is_pixel_of(format: ImageFormat) -> bool {
+        matches!(format, ImageFormat::PRgb32 | ImageFormat::XRgb32)
+    }
+}
+
+impl Pixel for u8 {
+    #[doc(hidden)]
+    #[inline]
+    fn is_pixel_of(format: ImageFormat) -> bool {
+        format == ImageFormat::A8
+    }
+}
+
 use ffi::BLFormatFlags;
 bitflags! {
     /// Pixel format flags.
@@ -206,31 +300,6 @@ impl Image {
         }
     }
 
-    /* FIXME figure out a solution for the lifetime issue
-    #[inline]
-    pub fn new_external(
-        width: i32,
-        height: i32,
-        format: ImageFormat,
-        data: &'a mut [u8],
-        stride: isize,
-    ) -> Result<Image<'a>> {
-        unsafe {
-            let mut this = Image::from_core(*Self::none());
-            errcode_to_result(ffi::blImageCreateFromData(
-                this.core_mut(),
-                width,
-                height,
-                format.into(),
-                data.as_mut_ptr() as *mut _,
-                stride,
-                None,
-                ptr::null_mut(),
-            ))
-            .map(|_| this)
-        }
-    }*/
-
     /// Attempts to create a new image with the specified dimensions and image
     /// format by decoding the data with the given codec.
     pub fn from_data<R: AsRef<[u8]>>(
@@ -302,7 +371,30 @@ impl Image {
                     data.pixelData as *mut _,
                     (h as isize * data.stride) as usize,
                 ),
-                stride: data.stride as isize / w as isize,
+                stride: data.stride as isize,
+                size: (w, h),
+                format: data.format.into(),
+                flags: ImageInfoFlags::from_bits_truncate(data.flags),
+            }
+        }
+    }
+
+    /// Returns an [`ImageDataMut`] instance allowing to modify this image's
+    /// pixels.
+    ///
+    /// If the pixels are shared with a clone of this image they are copied
+    /// first, so the changes aren't visible through the clone.
+    pub fn data_mut(&mut self) -> ImageDataMut<'_> {
+        unsafe {
+            let mut data = std::mem::zeroed();
+            expect_mem_err(ffi::blImageMakeMutable(self.core_mut(), &mut data));
+            let ffi::BLSizeI { w, h } = data.size;
+            ImageDataMut {
+                data: slice::from_raw_parts_mut(
+                    data.pixelData as *mut _,
+                    (h as isize * data.stride) as usize,
+                ),
+                stride: data.stride as isize,
                 size: (w, h),
                 format: data.format.into(),
                 flags: ImageInfoFlags::from_bits_truncate(data.flags),
@@ -310,6 +402,26 @@ impl Image {
         }
     }
 
+    /// Returns the non-premultiplied color of the pixel at the given
+    /// position, or `None` if it lies outside of this image.
+    ///
+    /// See [`ImageData::get_pixel`](struct.ImageData.html#method.get_pixel).
+    #[inline]
+    pub fn get_pixel(&self, x: i32, y: i32) -> Option<Rgba32> {
+        self.data().get_pixel(x, y)
+    }
+
+    /// Sets the pixel at the given position to the color, returning false if
+    /// it lies outside of this image.
+    ///
+    /// This makes the pixels mutable for every call, use
+    /// [`data_mut`](#method.data_mut) to modify many pixels.
+    /// See [`ImageDataMut::put_pixel`](struct.ImageDataMut.html#method.put_pixel).
+    #[inline]
+    pub fn put_pixel<C: Into<Rgba32>>(&mut self, x: i32, y: i32, color: C) -> bool {
+        self.data_mut().put_pixel(x, y, color)
+    }
+
     pub fn convert(&mut self, format: ImageFormat) -> Result<()> {
         unsafe { errcode_to_result(ffi::blImageConvert(self.core_mut(), format.into())) }
     }
@@ -461,16 +573,482 @@ impl Drop for Image {
     }
 }
 
+/// Creates an image that uses the buffer as its pixel data without copying
+/// it, after checking that the buffer holds `height` rows of `stride` bytes.
+unsafe fn image_from_buffer(
+    data: *mut u8,
+    len: usize,
+    width: i32,
+    height: i32,
+    format: ImageFormat,
+    stride: usize,
+) -> Result<Image> {
+    if width <= 0 || height <= 0 || stride > isize::MAX as usize {
+        return Err(Error::InvalidValue);
+    }
+    let row_len = width as usize * format.bytes_per_pixel();
+    match stride.checked_mul(height as usize) {
+        Some(required) if stride >= row_len && len >= required => (),
+        _ => return Err(Error::InvalidValue),
+    }
+    let mut this = Image::from_core(*Image::none());
+    errcode_to_result(ffi::blImageCreateFromData(
+        this.core_mut(),
+        width,
+        height,
+        format.into(),
+        data as *mut _,
+        stride as isize,
+        None,
+        ptr::null_mut(),
+    ))
+    .map(|_| this)
+}
+
+/// Returns the pixel data of an image created by [`image_from_buffer`].
+fn buffer_of(image: &Image) -> (*mut u8, usize) {
+    unsafe {
+        let mut data = std::mem::zeroed();
+        expect_mem_err(ffi::blImageGetData(image.core(), &mut data));
+        (
+            data.pixelData as *mut u8,
+            (data.size.h as isize * data.stride) as usize,
+        )
+    }
+}
+
+/// A read-only image borrowing its pixels from an external buffer.
+///
+/// The pixels are neither copied nor owned by the view, so the buffer stays
+/// borrowed for as long as the view exists:
+///
+/// ```compile_fail
+/// use blend2d::image::{ImageFormat, ImageView};
+///
+/// let view = {
+///     let buffer = vec![0u8; 4 * 4 * 4];
+///     ImageView::new(&buffer, 4, 4, ImageFormat::PRgb32, 16).unwrap()
+/// };
+/// assert_eq!(view.width(), 4);
+/// ```
+///
+/// As a [`Context`] may still read from an image after the call that used it
+/// returned, a view can't be used as the source of a blit or a pattern.
+/// Use [`to_image`](#method.to_image) to copy its pixels into an [`Image`]
+/// for that.
+///
+/// [`Context`]: ../context/struct.Context.html
+pub struct ImageView<'a> {
+    image: Image,
+    _pd: PhantomData<&'a [u8]>,
+}
+
+impl<'a> ImageView<'a> {
+    /// Creates a view of `height` rows of `width` pixels of the given format,
+    /// each row starting `stride` bytes after the previous one.
+    ///
+    /// Returns [`Error::InvalidValue`] if the dimensions are empty, the
+    /// stride is smaller than a row or the buffer is too small.
+    ///
+    /// [`Error::InvalidValue`]: ../error/enum.Error.html#variant.InvalidValue
+    pub fn new(
+        data: &'a [u8],
+        width: i32,
+        height: i32,
+        format: ImageFormat,
+        stride: usize,
+    ) -> Result<ImageView<'a>> {
+        // blend2d only writes to the buffer when rendering to the image,
+        // which this view doesn't allow.
+        let ptr = data.as_ptr() as *mut u8;
+        unsafe { image_from_buffer(ptr, data.len(), width, height, format, stride) }.map(|image| {
+            ImageView {
+                image,
+                _pd: PhantomData,
+            }
+        })
+    }
+
+    /// This view's format.
+    #[inline]
+    pub fn format(&self) -> ImageFormat {
+        self.image.format()
+    }
+
+    /// This view's dimensions.
+    #[inline]
+    pub fn size(&self) -> SizeI {
+        self.image.size()
+    }
+
+    /// This view's width.
+    #[inline]
+    pub fn width(&self) -> i32 {
+        self.image.width()
+    }
+
+    /// This view's height.
+    #[inline]
+    pub fn height(&self) -> i32 {
+        self.image.height()
+    }
+
+    /// The number of bytes between the starts of two rows.
+    #[inline]
+    pub fn stride(&self) -> usize {
+        let (_, len) = buffer_of(&self.image);
+        len / self.height() as usize
+    }
+
+    /// The pixel data of this view, `height` rows of `stride` bytes.
+    #[inline]
+    pub fn data(&self) -> &[u8] {
+        let (data, len) = buffer_of(&self.image);
+        unsafe { slice::from_raw_parts(data, len) }
+    }
+
+    /// Copies the pixels of this view into a new [`Image`].
+    #[inline]
+    pub fn to_image(&self) -> Image {
+        self.image.clone_deep()
+    }
+
+    /// Writes the view to the file at the given path.
+    #[inline]
+    pub fn write_to_file<P: AsRef<Path>>(&self, path: P, codec: &ImageCodec) -> Result<()> {
+        self.image.write_to_file(path, codec)
+    }
+
+    /// Writes the view to the given [`Array`].
+    #[inline]
+    pub fn write_to_data(&self, dst: &mut Array<u8>, codec: &ImageCodec) -> Result<()> {
+        self.image.write_to_data(dst, codec)
+    }
+}
+
+impl fmt::Debug for ImageView<'_> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("ImageView")
+            .field("size", &self.size())
+            .field("format", &self.format())
+            .finish()
+    }
+}
+
+/// An image borrowing its pixels from an external, mutable buffer, like a
+/// window's framebuffer or a memory mapped file.
+///
+/// Unlike an [`ImageView`] a mutable view can be the target of a
+/// [`Context`], which renders straight into the buffer. The buffer stays
+/// borrowed for as long as the view exists and the view stays borrowed for
+/// as long as the context is attached to it:
+///
+/// ```compile_fail
+/// use blend2d::context::Context;
+/// use blend2d::image::{ImageFormat, ImageViewMut};
+///
+/// let mut buffer = vec![0u8; 4 * 4 * 4];
+/// let mut view = ImageViewMut::new(&mut buffer, 4, 4, ImageFormat::PRgb32, 16).unwrap();
+/// let ctx = Context::new(&mut view).unwrap();
+/// buffer[0] = 0xFF;
+/// drop(ctx);
+/// ```
+///
+/// [`Context`]: ../context/struct.Context.html
+pub struct ImageViewMut<'a> {
+    pub(in crate) image: Image,
+    _pd: PhantomData<&'a mut [u8]>,
+}
+
+impl<'a> ImageViewMut<'a> {
+    /// Creates a mutable view of `height` rows of `width` pixels of the given
+    /// format, each row starting `stride` bytes after the previous one.
+    ///
+    /// Returns [`Error::InvalidValue`] if the dimensions are empty, the
+    /// stride is smaller than a row or the buffer is too small.
+    ///
+    /// [`Error::InvalidValue`]: ../error/enum.Error.html#variant.InvalidValue
+    pub fn new(
+        data: &'a mut [u8],
+        width: i32,
+        height: i32,
+        format: ImageFormat,
+        stride: usize,
+    ) -> Result<ImageViewMut<'a>> {
+        unsafe { image_from_buffer(data.as_mut_ptr(), data.len(), width, height, format, stride) }
+            .map(|image| ImageViewMut {
+                image,
+                _pd: PhantomData,
+            })
+    }
+
+    /// This view's format.
+    #[inline]
+    pub fn format(&self) -> ImageFormat {
+        self.image.format()
+    }
+
+    /// This view's dimensions.
+    #[inline]
+    pub fn size(&self) -> SizeI {
+        self.image.size()
+    }
+
+    /// This view's width.
+    #[inline]
+    pub fn width(&self) -> i32 {
+        self.image.width()
+    }
+
+    /// This view's height.
+    #[inline]
+    pub fn height(&self) -> i32 {
+        self.image.height()
+    }
+
+    /// The number of bytes between the starts of two rows.
+    #[inline]
+    pub fn stride(&self) -> usize {
+        let (_, len) = buffer_of(&self.image);
+        len / self.height() as usize
+    }
+
+    /// The pixel data of this view, `height` rows of `stride` bytes.
+    #[inline]
+    pub fn data(&self) -> &[u8] {
+        let (data, len) = buffer_of(&self.image);
+        unsafe { slice::from_raw_parts(data, len) }
+    }
+
+    /// The mutable pixel data of this view, `height` rows of `stride` bytes.
+    #[inline]
+    pub fn data_mut(&mut self) -> &mut [u8] {
+        let (data, len) = buffer_of(&self.image);
+        unsafe { slice::from_raw_parts_mut(data, len) }
+    }
+
+    /// Copies the pixels of this view into a new [`Image`].
+    #[inline]
+    pub fn to_image(&self) -> Image {
+        self.image.clone_deep()
+    }
+
+    /// Writes the view to the file at the given path.
+    #[inline]
+    pub fn write_to_file<P: AsRef<Path>>(&self, path: P, codec: &ImageCodec) -> Result<()> {
+        self.image.write_to_file(path, codec)
+    }
+
+    /// Writes the view to the given [`Array`].
+    #[inline]
+    pub fn write_to_data(&self, dst: &mut Array<u8>, codec: &ImageCodec) -> Result<()> {
+        self.image.write_to_data(dst, codec)
+    }
+}
+
+impl fmt::Debug for ImageViewMut<'_> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("ImageViewMut")
+            .field("size", &self.size())
+            .field("format", &self.format())
+            .finish()
+    }
+}
+
 /// A struct containing information about an image.
 #[derive(Copy, Clone, Debug, PartialEq)]
 pub struct ImageData<'a> {
     pub data: &'a [u8],
+    /// The distance between the starts of two rows in bytes.
     pub stride: isize,
     pub size: (i32, i32),
     pub format: ImageFormat,
     pub flags: ImageInfoFlags,
 }
 
+impl<'a> ImageData<'a> {
+    /// Returns the bytes of the pixels of every row, skipping the padding
+    /// between the rows.
+    pub fn rows(&self) -> impl DoubleEndedIterator<Item = &'a [u8]> + ExactSizeIterator + 'a {
+        let (stride, row_len) = row_layout(self.stride, self.size, self.format);
+        self.data.chunks(stride).map(move |row| &row[..row_len])
+    }
+
+    /// Returns the pixels of every row as `P`, or `None` if `P` isn't the
+    /// pixel type of this image's format.
+    pub fn pixel_rows<P: Pixel>(
+        &self,
+    ) -> Option<impl DoubleEndedIterator<Item = &'a [P]> + ExactSizeIterator + 'a> {
+        if !is_castable::<P>(self.data, self.stride, self.size, self.format) {
+            return None;
+        }
+        Some(self.rows().map(|row| unsafe {
+            slice::from_raw_parts(row.as_ptr() as *const P, row.len() / mem::size_of::<P>())
+        }))
+    }
+
+    /// Returns the non-premultiplied color of the pixel at the given
+    /// position, or `None` if it lies outside of the image.
+    ///
+    /// Pixels of [`XRgb32`] images are opaque and pixels of [`A8`] images are
+    /// black with the pixel's alpha.
+    ///
+    /// [`XRgb32`]: enum.ImageFormat.html#variant.XRgb32
+    /// [`A8`]: enum.ImageFormat.html#variant.A8
+    pub fn get_pixel(&self, x: i32, y: i32) -> Option<Rgba32> {
+        pixel_offset(self.data.len(), self.stride, self.size, self.format, x, y)
+            .map(|offset| read_pixel(self.format, &self.data[offset..]))
+    }
+}
+
+/// A struct containing information about an image and its mutable pixels,
+/// returned by [`Image::data_mut`].
+///
+/// [`Image::data_mut`]: struct.Image.html#method.data_mut
+#[derive(Debug, PartialEq)]
+pub struct ImageDataMut<'a> {
+    pub data: &'a mut [u8],
+    /// The distance between the starts of two rows in bytes.
+    pub stride: isize,
+    pub size: (i32, i32),
+    pub format: ImageFormat,
+    pub flags: ImageInfoFlags,
+}
+
+impl<'a> ImageDataMut<'a> {
+    /// Returns the bytes of the pixels of every row, skipping the padding
+    /// between the rows.
+    pub fn rows(&self) -> impl DoubleEndedIterator<Item = &[u8]> + ExactSizeIterator {
+        let (stride, row_len) = row_layout(self.stride, self.size, self.format);
+        self.data.chunks(stride).map(move |row| &row[..row_len])
+    }
+
+    /// Returns the mutable bytes of the pixels of every row, skipping the
+    /// padding between the rows.
+    pub fn rows_mut(&mut self) -> impl DoubleEndedIterator<Item = &mut [u8]> + ExactSizeIterator {
+        let (stride, row_len) = row_layout(self.stride, self.size, self.format);
+        self.data
+            .chunks_mut(stride)
+            .map(move |row| &mut row[..row_len])
+    }
+
+    /// Returns the pixels of every row as `P`, or `None` if `P` isn't the
+    /// pixel type of this image's format.
+    pub fn pixel_rows<P: Pixel>(
+        &self,
+    ) -> Option<impl DoubleEndedIterator<Item = &[P]> + ExactSizeIterator> {
+        if !is_castable::<P>(self.data, self.stride, self.size, self.format) {
+            return None;
+        }
+        Some(self.rows().map(|row| unsafe {
+            slice::from_raw_parts(row.as_ptr() as *const P, row.len() / mem::size_of::<P>())
+        }))
+    }
+
+    /// Returns the mutable pixels of every row as `P`, or `None` if `P` isn't
+    /// the pixel type of this image's format.
+    pub fn pixel_rows_mut<P: Pixel>(
+        &mut self,
+    ) -> Option<impl DoubleEndedIterator<Item = &mut [P]> + ExactSizeIterator> {
+        if !is_castable::<P>(self.data, self.stride, self.size, self.format) {
+            return None;
+        }
+        Some(self.rows_mut().map(|row| unsafe {
+            slice::from_raw_parts_mut(row.as_mut_ptr() as *mut P, row.len() / mem::size_of::<P>())
+        }))
+    }
+
+    /// Returns the non-premultiplied color of the pixel at the given
+    /// position, or `None` if it lies outside of the image.
+    ///
+    /// See [`ImageData::get_pixel`](struct.ImageData.html#method.get_pixel).
+    pub fn get_pixel(&self, x: i32, y: i32) -> Option<Rgba32> {
+        pixel_offset(self.data.len(), self.stride, self.size, self.format, x, y)
+            .map(|offset| read_pixel(self.format, &self.data[offset..]))
+    }
+
+    /// Sets the pixel at the given position to the color, returning false if
+    /// it lies outside of the image.
+    ///
+    /// The color is premultiplied for [`PRgb32`] images, made opaque for
+    /// [`XRgb32`] images and only its alpha is kept for [`A8`] images.
+    ///
+    /// [`PRgb32`]: enum.ImageFormat.html#variant.PRgb32
+    /// [`XRgb32`]: enum.ImageFormat.html#variant.XRgb32
+    /// [`A8`]: enum.ImageFormat.html#variant.A8
+    pub fn put_pixel<C: Into<Rgba32>>(&mut self, x: i32, y: i32, color: C) -> bool {
+        match pixel_offset(self.data.len(), self.stride, self.size, self.format, x, y) {
+            Some(offset) => {
+                write_pixel(self.format, &mut self.data[offset..], color.into());
+                true
+            },
+            None => false,
+        }
+    }
+}
+
+/// Returns the distance between the starts of two rows and the length of
+/// the pixels of a row in bytes.
+fn row_layout(stride: isize, (w, _): (i32, i32), format: ImageFormat) -> (usize, usize) {
+    let row_len = w.max(0) as usize * format.bytes_per_pixel();
+    ((stride.max(0) as usize).max(row_len).max(1), row_len)
+}
+
+fn is_castable<P: Pixel>(
+    data: &[u8],
+    stride: isize,
+    size: (i32, i32),
+    format: ImageFormat,
+) -> bool {
+    let (stride, _) = row_layout(stride, size, format);
+    P::is_pixel_of(format)
+        && data.as_ptr() as usize & (mem::align_of::<P>() - 1) == 0
+        && stride & (mem::align_of::<P>() - 1) == 0
+}
+
+fn pixel_offset(
+    data_len: usize,
+    stride: isize,
+    size: (i32, i32),
+    format: ImageFormat,
+    x: i32,
+    y: i32,
+) -> Option<usize> {
+    if x < 0 || y < 0 || x >= size.0 || y >= size.1 {
+        return None;
+    }
+    let (stride, _) = row_layout(stride, size, format);
+    let offset = y as usize * stride + x as usize * format.bytes_per_pixel();
+    if offset + format.bytes_per_pixel() <= data_len {
+        Some(offset)
+    } else {
+        None
+    }
+}
+
+/// Returns the non-premultiplied color of the pixel starting at the bytes.
+pub(in crate) fn read_pixel(format: ImageFormat, bytes: &[u8]) -> Rgba32 {
+    match format {
+        ImageFormat::PRgb32 => {
+            PRgb32Pixel(u32::from_ne_bytes(bytes[..4].try_into().unwrap())).into()
+        },
+        ImageFormat::XRgb32 => {
+            Rgba32(u32::from_ne_bytes(bytes[..4].try_into().unwrap())).with_alpha(0xFF)
+        },
+        ImageFormat::A8 => Rgba32::new(0, 0, 0, bytes[0]),
+    }
+}
+
+fn write_pixel(format: ImageFormat, bytes: &mut [u8], color: Rgba32) {
+    match format {
+        ImageFormat::PRgb32 => {
+            bytes[..4].copy_from_slice(&PRgb32Pixel::from(color).0.to_ne_bytes())
+        },
+        ImageFormat::XRgb32 => bytes[..4].copy_from_slice(&color.with_alpha(0xFF).0.to_ne_bytes()),
+        ImageFormat::A8 => bytes[0] = color.a(),
+    }
+}
+
 /// Image information provided by image codecs.
 #[derive(Debug)]
 pub struct ImageInfo {
@@ -494,7 +1072,12 @@ pub struct ImageInfo {
 
 #[cfg(test)]
 mod test_codec {
-    use crate::image::ImageScaleFilter;
+    use crate::color::Rgba32;
+    use crate::error::Error;
+    use crate::image::{
+        ImageData, ImageDataMut, ImageFormat, ImageInfoFlags, ImageScaleFilter, ImageView,
+        ImageViewMut, PRgb32Pixel,
+    };
     use crate::{geometry::SizeI, image::Image, DeepClone};
 
     #[test]
@@ -504,6 +1087,36 @@ mod test_codec {
         assert!(Image::new(0, 0, Default::default()).is_err());
     }
 
+    #[test]
+    fn test_image_view_validates_buffer() {
+        let mut buffer = vec![0u8; 64];
+        let invalid = |width, height, format, stride| {
+            matches!(
+                ImageView::new(&buffer, width, height, format, stride),
+                Err(Error::InvalidValue)
+            )
+        };
+        assert!(invalid(0, 4, ImageFormat::PRgb32, 16));
+        // The stride is smaller than a row.
+        assert!(invalid(4, 4, ImageFormat::PRgb32, 12));
+        // The buffer is too small.
+        assert!(invalid(4, 5, ImageFormat::PRgb32, 16));
+        assert!(invalid(8, 9, ImageFormat::A8, 8));
+        assert!(ImageViewMut::new(&mut buffer, 4, 4, ImageFormat::PRgb32, usize::MAX).is_err());
+    }
+
+    #[test]
+    fn test_image_view() {
+        let buffer: Vec<u8> = (0..64).collect();
+        let view = ImageView::new(&buffer, 6, 8, ImageFormat::A8, 8).unwrap();
+        assert_eq!(view.size(), SizeI { w: 6, h: 8 });
+        assert_eq!(view.stride(), 8);
+        assert_eq!(view.data(), &buffer[..]);
+        let image = view.to_image();
+        assert_eq!(image.size(), view.size());
+        assert_eq!(image.format(), ImageFormat::A8);
+    }
+
     #[test]
     fn test_image_scale() {
         let new_size = SizeI { w: 100, h: 100 };
@@ -535,10 +1148,87 @@ mod test_codec {
     fn test_image_data() {
         let image = Image::new(50, 50, Default::default()).unwrap();
         let image_data = image.data();
-        assert_eq!(image_data.stride, 4);
+        assert_eq!(image_data.stride, 200);
         assert_eq!(
             image_data.data.to_vec().len() as isize,
-            50 * 50 * image_data.stride
+            50 * image_data.stride
+        );
+    }
+
+    #[test]
+    fn test_image_copy_on_write() {
+        let mut image = Image::new(2, 2, ImageFormat::PRgb32).unwrap();
+        for byte in image.data_mut().data.iter_mut() {
+            *byte = 0;
+        }
+        let blue = Rgba32(0xFF00_00FF);
+        let mut clone = image.clone();
+        assert!(clone.put_pixel(0, 0, blue));
+        assert_eq!(clone.get_pixel(0, 0), Some(blue));
+        assert_eq!(image.get_pixel(0, 0), Some(Rgba32(0)));
+
+        let other = clone.clone();
+        clone.data_mut().data[..4].copy_from_slice(&[0xFF; 4]);
+        assert_eq!(clone.get_pixel(0, 0), Some(Rgba32(0xFFFF_FFFF)));
+        assert_eq!(other.get_pixel(0, 0), Some(blue));
+        assert_ne!(clone, other);
+    }
+
+    #[test]
+    fn test_image_data_pixels() {
+        // Rows of 2 pixels padded to 16 bytes.
+        #[repr(align(4))]
+        struct Aligned([u8; 48]);
+        let mut buffer = Aligned([0; 48]);
+        let mut data = ImageDataMut {
+            data: &mut buffer.0,
+            stride: 16,
+            size: (2, 3),
+            format: ImageFormat::PRgb32,
+            flags: ImageInfoFlags::empty(),
+        };
+        assert!(data.put_pixel(1, 2, Rgba32::new(0xFF, 0x00, 0x00, 0x80)));
+        assert!(!data.put_pixel(2, 0, Rgba32(0)));
+        assert_eq!(
+            data.get_pixel(1, 2),
+            Some(Rgba32::new(0xFF, 0x00, 0x00, 0x80))
         );
+        assert_eq!(data.get_pixel(0, -1), None);
+        assert!(data.pixel_rows::<u8>().is_none());
+        let rows: Vec<_> = data.pixel_rows::<PRgb32Pixel>().unwrap().collect();
+        assert_eq!(rows.len(), 3);
+        assert_eq!(rows[2], [PRgb32Pixel(0), PRgb32Pixel(0x8080_0000)]);
+        for row in data.rows_mut() {
+            assert_eq!(row.len(), 8);
+            row.copy_from_slice(&[0xFF; 8]);
+        }
+        assert!(buffer.0.chunks(16).all(|row| row[8..] == [0; 8]));
+    }
+
+    #[test]
+    fn test_image_data_formats() {
+        let mut buffer = [0u8; 8];
+        let mut data = ImageDataMut {
+            data: &mut buffer,
+            stride: 4,
+            size: (3, 2),
+            format: ImageFormat::A8,
+            flags: ImageInfoFlags::empty(),
+        };
+        assert!(data.put_pixel(2, 1, Rgba32(0x40FF_FFFF)));
+        assert_eq!(data.get_pixel(2, 1), Some(Rgba32(0x4000_0000)));
+        assert_eq!(data.pixel_rows_mut::<u8>().unwrap().len(), 2);
+        assert_eq!(buffer, [0, 0, 0, 0, 0, 0, 0x40, 0]);
+
+        let buffer = 0x1122_3344u32.to_ne_bytes();
+        let data = ImageData {
+            data: &buffer,
+            stride: 4,
+            size: (1, 1),
+            format: ImageFormat::XRgb32,
+            flags: ImageInfoFlags::empty(),
+        };
+        assert_eq!(data.get_pixel(0, 0), Some(Rgba32(0xFF22_3344)));
+        assert_eq!(data.rows().next(), Some(&buffer[..]));
     }
 }
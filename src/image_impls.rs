@@ -0,0 +1,253 @@
+//! Conversions between [`Image`] and the buffers of the `image` crate.
+//!
+//! Blend2D stores colors premultiplied by alpha while the `image` crate
+//! stores them straight, so converting [`PRgb32`] pixels from and to
+//! [`RgbaImage`] premultiplies and unpremultiplies them. A [`GrayImage`] is
+//! converted from and to an [`A8`] image.
+//!
+//! [`PRgb32`]: ../image/enum.ImageFormat.html#variant.PRgb32
+//! [`A8`]: ../image/enum.ImageFormat.html#variant.A8
+use ::image::{GrayImage, ImageBuffer, Pixel, RgbaImage};
+
+use std::convert::TryFrom;
+use std::ffi::c_void;
+
+use crate::color::Rgba32;
+use crate::error::{errcode_to_result, Error, Result};
+use crate::image::{read_pixel, Image, ImageFormat, PRgb32Pixel};
+use crate::variant::WrappedBlCore;
+
+/// Returns the bytes of the premultiplied pixel of the straight RGBA pixel.
+#[inline]
+fn premultiply(pixel: &[u8]) -> [u8; 4] {
+    PRgb32Pixel::from(Rgba32::new(pixel[0], pixel[1], pixel[2], pixel[3]))
+        .0
+        .to_ne_bytes()
+}
+
+fn dimensions<P: Pixel>(image: &ImageBuffer<P, Vec<P::Subpixel>>) -> Result<(i32, i32)> {
+    match (i32::try_from(image.width()), i32::try_from(image.height())) {
+        (Ok(w), Ok(h)) if w > 0 && h > 0 => Ok((w, h)),
+        _ => Err(Error::InvalidValue),
+    }
+}
+
+/// Creates an image of the rows of the buffer, taking it over without
+/// copying it if its pixels are aligned as blend2d requires.
+fn image_from_vec(buffer: Vec<u8>, width: i32, height: i32, format: ImageFormat) -> Result<Image> {
+    let stride = width as usize * format.bytes_per_pixel();
+    if buffer.as_ptr() as usize & (format.bytes_per_pixel() - 1) != 0 {
+        return image_from_rows(buffer.chunks(stride), width, height, format, |src, dst| {
+            dst.copy_from_slice(src)
+        });
+    }
+
+    unsafe extern "C" fn destroy(_impl: *mut c_void, buffer: *mut c_void) {
+        drop(Box::from_raw(buffer as *mut Vec<u8>));
+    }
+
+    let mut buffer = Box::new(buffer);
+    let pixels = buffer.as_mut_ptr();
+    let buffer = Box::into_raw(buffer);
+    unsafe {
+        let mut this = Image::from_core(*Image::none());
+        let result = errcode_to_result(ffi::blImageCreateFromData(
+            this.core_mut(),
+            width,
+            height,
+            format.into(),
+            pixels as *mut _,
+            stride as isize,
+            Some(destroy),
+            buffer as *mut _,
+        ));
+        if result.is_err() {
+            // blend2d only takes over the buffer once the image exists.
+            drop(Box::from_raw(buffer));
+        }
+        result.map(|_| this)
+    }
+}
+
+/// Creates an image by converting every row into the matching row of the
+/// image.
+fn image_from_rows<'a, I, F>(
+    rows: I,
+    width: i32,
+    height: i32,
+    format: ImageFormat,
+    mut convert: F,
+) -> Result<Image>
+where
+    I: Iterator<Item = &'a [u8]>,
+    F: FnMut(&[u8], &mut [u8]),
+{
+    let mut this = Image::new(width, height, format)?;
+    for (src, dst) in rows.zip(this.data_mut().rows_mut()) {
+        convert(src, dst);
+    }
+    Ok(this)
+}
+
+fn premultiply_row(src: &[u8], dst: &mut [u8]) {
+    for (src, dst) in src.chunks_exact(4).zip(dst.chunks_exact_mut(4)) {
+        dst.copy_from_slice(&premultiply(src));
+    }
+}
+
+/// Premultiplies the pixels in place and takes over the buffer without
+/// copying it where possible.
+impl TryFrom<RgbaImage> for Image {
+    type Error = Error;
+
+    fn try_from(image: RgbaImage) -> Result<Self> {
+        let (width, height) = dimensions(&image)?;
+        let mut buffer = image.into_raw();
+        for pixel in buffer.chunks_exact_mut(4) {
+            let premultiplied = premultiply(pixel);
+            pixel.copy_from_slice(&premultiplied);
+        }
+        image_from_vec(buffer, width, height, ImageFormat::PRgb32)
+    }
+}
+
+/// Copies the pixels into a new [`PRgb32`] image, premultiplying them.
+///
+/// [`PRgb32`]: enum.ImageFormat.html#variant.PRgb32
+impl TryFrom<&RgbaImage> for Image {
+    type Error = Error;
+
+    fn try_from(image: &RgbaImage) -> Result<Self> {
+        let (width, height) = dimensions(image)?;
+        let rows = image.chunks_exact(width as usize * 4);
+        image_from_rows(rows, width, height, ImageFormat::PRgb32, premultiply_row)
+    }
+}
+
+/// Takes over the buffer as the pixels of an [`A8`] image without copying
+/// it.
+///
+/// [`A8`]: enum.ImageFormat.html#variant.A8
+impl TryFrom<GrayImage> for Image {
+    type Error = Error;
+
+    fn try_from(image: GrayImage) -> Result<Self> {
+        let (width, height) = dimensions(&image)?;
+        image_from_vec(image.into_raw(), width, height, ImageFormat::A8)
+    }
+}
+
+/// Copies the pixels into a new [`A8`] image.
+///
+/// [`A8`]: enum.ImageFormat.html#variant.A8
+impl TryFrom<&GrayImage> for Image {
+    type Error = Error;
+
+    fn try_from(image: &GrayImage) -> Result<Self> {
+        let (width, height) = dimensions(image)?;
+        let rows = image.chunks_exact(width as usize);
+        image_from_rows(rows, width, height, ImageFormat::A8, |src, dst| {
+            dst.copy_from_slice(src)
+        })
+    }
+}
+
+/// Copies the pixels, unpremultiplying [`PRgb32`] pixels.
+///
+/// Pixels of [`XRgb32`] images become opaque and pixels of [`A8`] images
+/// black with the pixel's alpha.
+///
+/// [`PRgb32`]: enum.ImageFormat.html#variant.PRgb32
+/// [`XRgb32`]: enum.ImageFormat.html#variant.XRgb32
+/// [`A8`]: enum.ImageFormat.html#variant.A8
+impl From<&Image> for RgbaImage {
+    fn from(image: &Image) -> Self {
+        let data = image.data();
+        let bytes_per_pixel = data.format.bytes_per_pixel();
+        let mut buffer = Vec::with_capacity(image.width() as usize * image.height() as usize * 4);
+        for row in data.rows() {
+            for pixel in row.chunks_exact(bytes_per_pixel) {
+                let color = read_pixel(data.format, pixel);
+                buffer.extend_from_slice(&[color.r(), color.g(), color.b(), color.a()]);
+            }
+        }
+        RgbaImage::from_raw(image.width() as u32, image.height() as u32, buffer).unwrap()
+    }
+}
+
+/// Copies the pixels of an [`A8`] image, returning [`Error::InvalidValue`]
+/// for images of other formats.
+///
+/// [`A8`]: enum.ImageFormat.html#variant.A8
+/// [`Error::InvalidValue`]: ../error/enum.Error.html#variant.InvalidValue
+impl TryFrom<&Image> for GrayImage {
+    type Error = Error;
+
+    fn try_from(image: &Image) -> Result<Self> {
+        if image.format() != ImageFormat::A8 {
+            return Err(Error::InvalidValue);
+        }
+        let buffer = image.data().rows().flatten().copied().collect();
+        Ok(GrayImage::from_raw(image.width() as u32, image.height() as u32, buffer).unwrap())
+    }
+}
+
+#[cfg(test)]
+mod test_image_impls {
+    use super::*;
+
+    #[test]
+    fn test_premultiply_roundtrip() {
+        for a in 0..=255u32 {
+            for c in 0..=a {
+                let pixel = PRgb32Pixel(a << 24 | c << 16 | (c / 2) << 8)
+                    .0
+                    .to_ne_bytes();
+                let color = read_pixel(ImageFormat::PRgb32, &pixel);
+                let straight = [color.r(), color.g(), color.b(), color.a()];
+                assert_eq!(premultiply(&straight), pixel);
+            }
+        }
+    }
+
+    #[test]
+    fn test_rgba_roundtrip() {
+        let pixels = [
+            [0xFF, 0x00, 0x00, 0xFF],
+            [0x00, 0x00, 0x00, 0x00],
+            [0xFF, 0x80, 0x00, 0x80],
+            [0x12, 0x34, 0x56, 0xFF],
+            [0x00, 0xFF, 0x00, 0x01],
+            [0x40, 0x40, 0x40, 0x40],
+        ];
+        let rgba = RgbaImage::from_raw(3, 2, pixels.concat()).unwrap();
+        let image = Image::try_from(&rgba).unwrap();
+        assert_eq!(image.format(), ImageFormat::PRgb32);
+        assert_eq!(
+            image.get_pixel(2, 0),
+            Some(Rgba32::new(0xFF, 0x80, 0x00, 0x80))
+        );
+        assert_eq!(RgbaImage::from(&image), rgba);
+        let image = Image::try_from(rgba.clone()).unwrap();
+        assert_eq!(RgbaImage::from(&image), rgba);
+        assert!(GrayImage::try_from(&image).is_err());
+    }
+
+    #[test]
+    fn test_gray_roundtrip() {
+        let gray = GrayImage::from_fn(5, 3, |x, y| ::image::Luma([(x * 50 + y) as u8]));
+        let image = Image::try_from(&gray).unwrap();
+        assert_eq!(image.format(), ImageFormat::A8);
+        assert_eq!(GrayImage::try_from(&image).unwrap(), gray);
+        let image = Image::try_from(gray.clone()).unwrap();
+        assert_eq!(image.get_pixel(4, 2), Some(Rgba32(0xCA00_0000)));
+        assert_eq!(GrayImage::try_from(&image).unwrap(), gray);
+        assert_eq!(RgbaImage::from(&image).get_pixel(4, 2).0, [0, 0, 0, 0xCA]);
+    }
+
+    #[test]
+    fn test_invalid_dimensions() {
+        assert!(Image::try_from(RgbaImage::new(0, 4)).is_err());
+        assert!(Image::try_from(&GrayImage::new(4, 0)).is_err());
+    }
+}
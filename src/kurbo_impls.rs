@@ -0,0 +1,193 @@
+//! Conversions between the geometry types and paths and the ones of
+//! `kurbo`.
+use kurbo::{Affine, BezPath, PathEl, Point, Rect, Size, Vec2};
+
+use crate::geometry::{BoxD, PointD, RectD, SizeD};
+use crate::matrix::Matrix2D;
+use crate::path::{Path, PathSegment};
+
+impl From<Point> for PointD {
+    #[inline]
+    fn from(p: Point) -> Self {
+        PointD { x: p.x, y: p.y }
+    }
+}
+
+impl From<PointD> for Point {
+    #[inline]
+    fn from(p: PointD) -> Self {
+        Point::new(p.x, p.y)
+    }
+}
+
+impl From<Vec2> for PointD {
+    #[inline]
+    fn from(v: Vec2) -> Self {
+        PointD { x: v.x, y: v.y }
+    }
+}
+
+impl From<PointD> for Vec2 {
+    #[inline]
+    fn from(p: PointD) -> Self {
+        Vec2::new(p.x, p.y)
+    }
+}
+
+impl From<Size> for SizeD {
+    #[inline]
+    fn from(s: Size) -> Self {
+        SizeD {
+            w: s.width,
+            h: s.height,
+        }
+    }
+}
+
+impl From<SizeD> for Size {
+    #[inline]
+    fn from(s: SizeD) -> Self {
+        Size::new(s.w, s.h)
+    }
+}
+
+impl From<Rect> for BoxD {
+    #[inline]
+    fn from(r: Rect) -> Self {
+        BoxD {
+            x0: r.x0,
+            y0: r.y0,
+            x1: r.x1,
+            y1: r.y1,
+        }
+    }
+}
+
+impl From<BoxD> for Rect {
+    #[inline]
+    fn from(b: BoxD) -> Self {
+        Rect::new(b.x0, b.y0, b.x1, b.y1)
+    }
+}
+
+impl From<Rect> for RectD {
+    #[inline]
+    fn from(r: Rect) -> Self {
+        RectD {
+            x: r.x0,
+            y: r.y0,
+            w: r.width(),
+            h: r.height(),
+        }
+    }
+}
+
+impl From<RectD> for Rect {
+    #[inline]
+    fn from(r: RectD) -> Self {
+        Rect::new(r.x, r.y, r.x + r.w, r.y + r.h)
+    }
+}
+
+/// Both matrices store their coefficients in the same order.
+impl From<Affine> for Matrix2D {
+    #[inline]
+    fn from(a: Affine) -> Self {
+        let [m00, m01, m10, m11, m20, m21] = a.as_coeffs();
+        Matrix2D::new(m00, m01, m10, m11, m20, m21)
+    }
+}
+
+impl From<Matrix2D> for Affine {
+    #[inline]
+    fn from(m: Matrix2D) -> Self {
+        Affine::new(m.coefficients())
+    }
+}
+
+impl From<&BezPath> for Path {
+    fn from(bez_path: &BezPath) -> Self {
+        let mut path = Path::new();
+        for el in bez_path.elements() {
+            match *el {
+                PathEl::MoveTo(p) => path.move_to(p.x, p.y),
+                PathEl::LineTo(p) => path.line_to(p.x, p.y),
+                PathEl::QuadTo(p1, p2) => path.quad_to(p1.x, p1.y, p2.x, p2.y),
+                PathEl::CurveTo(p1, p2, p3) => path.cubic_to(p1.x, p1.y, p2.x, p2.y, p3.x, p3.y),
+                PathEl::ClosePath => path.close(),
+            }
+        }
+        path
+    }
+}
+
+impl From<BezPath> for Path {
+    #[inline]
+    fn from(bez_path: BezPath) -> Self {
+        Path::from(&bez_path)
+    }
+}
+
+impl From<&Path> for BezPath {
+    fn from(path: &Path) -> Self {
+        path.segments()
+            .map(|segment| match segment {
+                PathSegment::MoveTo(p) => PathEl::MoveTo(p.into()),
+                PathSegment::LineTo(p) => PathEl::LineTo(p.into()),
+                PathSegment::QuadTo(p1, p2) => PathEl::QuadTo(p1.into(), p2.into()),
+                PathSegment::CubicTo(p1, p2, p3) => {
+                    PathEl::CurveTo(p1.into(), p2.into(), p3.into())
+                },
+                PathSegment::Close => PathEl::ClosePath,
+            })
+            .collect()
+    }
+}
+
+impl From<Path> for BezPath {
+    #[inline]
+    fn from(path: Path) -> Self {
+        BezPath::from(&path)
+    }
+}
+
+#[cfg(test)]
+mod test_kurbo_impls {
+    use super::*;
+
+    #[test]
+    fn test_path_roundtrip() {
+        let mut bez_path = BezPath::new();
+        bez_path.move_to((0.0, 0.0));
+        bez_path.line_to((10.0, 0.0));
+        bez_path.quad_to((15.0, 5.0), (10.0, 10.0));
+        bez_path.curve_to((8.0, 12.0), (2.0, 12.0), (0.0, 10.0));
+        bez_path.close_path();
+        bez_path.move_to((20.0, 20.0));
+        bez_path.line_to((30.0, 20.0));
+        let path = Path::from(&bez_path);
+        assert_eq!(path.segments().count(), 7);
+        assert_eq!(
+            path.segments().nth(2),
+            Some(PathSegment::QuadTo(
+                PointD { x: 15.0, y: 5.0 },
+                PointD { x: 10.0, y: 10.0 },
+            ))
+        );
+        assert_eq!(BezPath::from(&path), bez_path);
+    }
+
+    #[test]
+    fn test_affine() {
+        let affine = Affine::rotate(0.5).then_translate(Vec2::new(3.0, -2.0));
+        let m = Matrix2D::from(affine);
+        let p = Point::new(1.5, 2.5);
+        let mapped = m.map_point(&p.into());
+        assert!((mapped.x - (affine * p).x).abs() < 1e-12);
+        assert!((mapped.y - (affine * p).y).abs() < 1e-12);
+        assert_eq!(Affine::from(m), affine);
+        let rect = Rect::new(1.0, 2.0, 4.0, 6.0);
+        assert_eq!(RectD::from(rect).w, 3.0);
+        assert_eq!(Rect::from(BoxD::from(rect)), rect);
+    }
+}
@@ -10,8 +10,11 @@ pub(in crate) mod variant;
 pub use self::variant::DeepClone;
 
 pub mod array;
+pub mod bidi;
 pub mod codec;
+pub mod color;
 pub mod context;
+pub mod display_list;
 pub mod error;
 pub mod font;
 pub mod font_defs;
@@ -25,13 +28,103 @@ pub mod pattern;
 pub mod prelude;
 pub mod region;
 pub mod runtime;
+pub mod style;
+pub mod text_layout;
+
+#[cfg(feature = "euclid")]
+mod euclid_impls;
+#[cfg(feature = "glam")]
+mod glam_impls;
+#[cfg(feature = "image")]
+mod image_impls;
+#[cfg(feature = "kurbo")]
+mod kurbo_impls;
+#[cfg(feature = "mint")]
+mod mint_impls;
+#[cfg(feature = "serde")]
+mod serde_impls;
 
 use bitflags::bitflags;
 
+use std::fmt;
+
+/// A four byte OpenType tag, identifying tables, features and variation axes.
+///
+/// ```
+/// use blend2d::Tag;
+///
+/// let tag = Tag::new(b"liga");
+/// assert_eq!(tag, Tag::from(b"liga"));
+/// assert_eq!(tag.to_string(), "liga");
+/// ```
 #[repr(transparent)]
-#[derive(Debug, Copy, Clone, PartialEq)]
+#[derive(Copy, Clone, PartialEq, Eq, Hash)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub struct Tag(u32);
 
+impl Tag {
+    /// Creates a tag from its four bytes.
+    #[inline]
+    pub const fn new(tag: &[u8; 4]) -> Self {
+        Tag(u32::from_be_bytes(*tag))
+    }
+
+    /// Returns the four bytes of the tag.
+    #[inline]
+    pub const fn to_bytes(self) -> [u8; 4] {
+        self.0.to_be_bytes()
+    }
+}
+
+impl From<&[u8; 4]> for Tag {
+    #[inline]
+    fn from(tag: &[u8; 4]) -> Self {
+        Tag::new(tag)
+    }
+}
+
+impl From<[u8; 4]> for Tag {
+    #[inline]
+    fn from(tag: [u8; 4]) -> Self {
+        Tag::new(&tag)
+    }
+}
+
+impl From<u32> for Tag {
+    #[inline]
+    fn from(tag: u32) -> Self {
+        Tag(tag)
+    }
+}
+
+impl From<Tag> for u32 {
+    #[inline]
+    fn from(tag: Tag) -> Self {
+        tag.0
+    }
+}
+
+impl fmt::Display for Tag {
+    /// Writes the bytes of the tag as characters, escaping bytes that aren't
+    /// printable ascii.
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        for &b in &self.to_bytes() {
+            if b == b' ' || b.is_ascii_graphic() {
+                write!(f, "{}", char::from(b))?;
+            } else {
+                write!(f, "\\x{:02x}", b)?;
+            }
+        }
+        Ok(())
+    }
+}
+
+impl fmt::Debug for Tag {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "Tag(\"{}\")", self)
+    }
+}
+
 use ffi::BLBooleanOp::*;
 bl_enum! {
     pub enum BooleanOp {
@@ -84,3 +177,24 @@ bitflags! {
         const READ_WRITE = BL_DATA_ACCESS_READ as u32;
     }
 }
+
+#[cfg(test)]
+mod test_tag {
+    use crate::Tag;
+
+    #[test]
+    fn test_tag_bytes() {
+        let tag = Tag::from(b"wght");
+        assert_eq!(u32::from(tag), 0x7767_6874);
+        assert_eq!(tag.to_bytes(), *b"wght");
+        assert_eq!(Tag::from(*b"wght"), tag);
+    }
+
+    #[test]
+    fn test_tag_display() {
+        assert_eq!(Tag::new(b"liga").to_string(), "liga");
+        assert_eq!(Tag::new(b"cvt ").to_string(), "cvt ");
+        assert_eq!(Tag::from(0x6b65_7200).to_string(), "ker\\x00");
+        assert_eq!(format!("{:?}", Tag::new(b"kern")), "Tag(\"kern\")");
+    }
+}
@@ -11,6 +11,7 @@ macro_rules! bl_enum {
     ) => {
         $(#[$meta])*
         #[derive(Copy, Clone, Debug, PartialEq, PartialOrd)]
+        #[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
         $vis enum $name {
             $(
                 $(#[$meta_var])*
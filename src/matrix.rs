@@ -1,6 +1,6 @@
 //! 2DMatrix and transforms.
 use crate::error::expect_mem_err;
-use crate::geometry::Point;
+use crate::geometry::{Point, PointD};
 
 pub(in crate) use self::private::Matrix2DOp;
 mod private {
@@ -30,6 +30,7 @@ mod private {
 /// A Row-Major 2d matrix.
 #[repr(transparent)]
 #[derive(Copy, Clone, Debug, Default, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub struct Matrix2D([f64; ffi::BLMatrix2DValue::BL_MATRIX2D_VALUE_COUNT as usize]);
 
 impl Matrix2D {
@@ -39,6 +40,12 @@ impl Matrix2D {
         Matrix2D([m00, m01, m10, m11, m20, m21])
     }
 
+    /// Returns the coefficients in the order taken by [`new`](#method.new).
+    #[inline]
+    pub fn coefficients(&self) -> [f64; 6] {
+        self.0
+    }
+
     /// Creates an identity matrix.
     #[inline]
     pub fn identity() -> Matrix2D {
@@ -189,6 +196,16 @@ impl Matrix2D {
         let dst_p = dst.0.as_ptr() as *mut ffi::BLMatrix2D;
         unsafe{ ffi::blMatrix2DInvert(dst_p, src_p)};
     }
+
+    /// Maps a point through this matrix.
+    #[inline]
+    pub fn map_point(&self, p: &PointD) -> PointD {
+        let [m00, m01, m10, m11, m20, m21] = self.0;
+        PointD {
+            x: p.x * m00 + p.y * m10 + m20,
+            y: p.x * m01 + p.y * m11 + m21,
+        }
+    }
 }
 
 impl MatrixTransform for Matrix2D {
@@ -358,4 +375,11 @@ mod tests {
         m.transform(&m2);
         assert_eq!(m, Matrix2D::identity());
     }
+
+    #[test]
+    fn test_map_point() {
+        let m = Matrix2D::new(2.0, 0.5, -1.0, 3.0, 10.0, 20.0);
+        let p = m.map_point(&PointD { x: 1.0, y: 2.0 });
+        assert_eq!(p, PointD { x: 10.0, y: 26.5 });
+    }
 }
\ No newline at end of file
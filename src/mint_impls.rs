@@ -0,0 +1,92 @@
+//! Conversions between the geometry types and the ones of `mint`.
+use mint::{Point2, RowMatrix3x2, Vector2};
+
+use crate::geometry::{PointD, PointI};
+use crate::matrix::Matrix2D;
+
+impl From<Point2<f64>> for PointD {
+    #[inline]
+    fn from(p: Point2<f64>) -> Self {
+        PointD { x: p.x, y: p.y }
+    }
+}
+
+impl From<PointD> for Point2<f64> {
+    #[inline]
+    fn from(p: PointD) -> Self {
+        Point2 { x: p.x, y: p.y }
+    }
+}
+
+impl From<Point2<i32>> for PointI {
+    #[inline]
+    fn from(p: Point2<i32>) -> Self {
+        PointI { x: p.x, y: p.y }
+    }
+}
+
+impl From<PointI> for Point2<i32> {
+    #[inline]
+    fn from(p: PointI) -> Self {
+        Point2 { x: p.x, y: p.y }
+    }
+}
+
+impl From<Vector2<f64>> for PointD {
+    #[inline]
+    fn from(v: Vector2<f64>) -> Self {
+        PointD { x: v.x, y: v.y }
+    }
+}
+
+impl From<PointD> for Vector2<f64> {
+    #[inline]
+    fn from(p: PointD) -> Self {
+        Vector2 { x: p.x, y: p.y }
+    }
+}
+
+/// The rows of the matrix are the ones of [`Matrix2D`], the last one being
+/// the translation.
+impl From<RowMatrix3x2<f64>> for Matrix2D {
+    #[inline]
+    fn from(m: RowMatrix3x2<f64>) -> Self {
+        Matrix2D::new(m.x.x, m.x.y, m.y.x, m.y.y, m.z.x, m.z.y)
+    }
+}
+
+impl From<Matrix2D> for RowMatrix3x2<f64> {
+    #[inline]
+    fn from(m: Matrix2D) -> Self {
+        let [m00, m01, m10, m11, m20, m21] = m.coefficients();
+        RowMatrix3x2 {
+            x: Vector2 { x: m00, y: m01 },
+            y: Vector2 { x: m10, y: m11 },
+            z: Vector2 { x: m20, y: m21 },
+        }
+    }
+}
+
+#[cfg(test)]
+mod test_mint_impls {
+    use super::*;
+
+    #[test]
+    fn test_matrix() {
+        let m = Matrix2D::new(1.0, 2.0, 3.0, 4.0, 5.0, 6.0);
+        let mint = RowMatrix3x2::from(m);
+        assert_eq!(mint.z, Vector2 { x: 5.0, y: 6.0 });
+        assert_eq!(Matrix2D::from(mint), m);
+        // Mapping a point must agree with the row vector convention of mint.
+        let p = PointD { x: 2.0, y: -1.0 };
+        let mapped = m.map_point(&p);
+        assert_eq!(
+            mapped,
+            PointD {
+                x: p.x * mint.x.x + p.y * mint.y.x + mint.z.x,
+                y: p.x * mint.x.y + p.y * mint.y.y + mint.z.y,
+            }
+        );
+        assert_eq!(PointD::from(Point2::from(mapped)), mapped);
+    }
+}
@@ -1,16 +1,31 @@
 #![allow(clippy::too_many_arguments)]
+mod boolean;
+mod flatten;
+pub use self::flatten::Polyline;
+mod geom;
+mod measure;
+pub use self::measure::PathMeasure;
+mod offset;
+mod segments;
+pub use self::segments::{Figures, PathSegment, Segments};
+mod simplify;
+
+mod svg;
+
 use bitflags::bitflags;
 
-use std::borrow::Borrow;
+use std::borrow::{Borrow, Cow};
 use std::ops::{self, Range};
+use std::sync::atomic::{AtomicUsize, Ordering};
 use std::{fmt, mem, ptr, slice};
 
 use crate::array::Array;
-use crate::error::{errcode_to_result, expect_mem_err, OutOfMemory};
+use crate::error::{errcode_to_result, expect_mem_err, OutOfMemory, Result};
 use crate::geometry::{BoxD, FillRule, Geometry, GeometryDirection, HitTest, Point, PointD, RectD};
 use crate::matrix::Matrix2D;
 use crate::util::bl_range;
-use crate::variant::WrappedBlCore;
+use crate::variant::{DeepClone, WrappedBlCore};
+use crate::BooleanOp;
 
 use ffi::BLPathCmd::*;
 bl_enum! {
@@ -207,6 +222,24 @@ impl StrokeOptions {
         unsafe { u32::from(self.core.__bindgen_anon_1.__bindgen_anon_1.transformOrder).into() }
     }
 
+    #[inline]
+    pub fn set_width(&mut self, width: f64) {
+        self.core.width = width;
+    }
+
+    #[inline]
+    pub fn set_miter_limit(&mut self, miter_limit: f64) {
+        self.core.miterLimit = miter_limit;
+    }
+
+    #[inline]
+    pub fn set_join(&mut self, join: StrokeJoin) {
+        #[allow(unused_unsafe)] // nightly has no problem with copy-union writes, stable does though
+        unsafe {
+            self.core.__bindgen_anon_1.__bindgen_anon_1.join = join as u8;
+        }
+    }
+
     #[inline]
     pub fn set_caps(&mut self, cap: StrokeCap) {
         #[allow(unused_unsafe)] // nightly has no problem with copy-union writes, stable does though
@@ -237,6 +270,19 @@ impl Drop for StrokeOptions {
 }
 
 /// A 2D vector path.
+///
+/// A path can be sent to other threads, but it is not `Sync` as blend2d
+/// caches its info (flags and bounding boxes) without synchronization:
+///
+/// ```
+/// fn assert_send<T: Send>() {}
+/// assert_send::<blend2d::path::Path>();
+/// ```
+///
+/// ```compile_fail
+/// fn assert_sync<T: Sync>() {}
+/// assert_sync::<blend2d::path::Path>();
+/// ```
 #[repr(transparent)]
 pub struct Path {
     core: ffi::BLPathCore,
@@ -331,11 +377,29 @@ impl Path {
         }
     }
 
+    /// Returns this path if blend2d may update its cached info in place, or a
+    /// deep copy of it otherwise.
+    ///
+    /// Blend2D computes the info of a path lazily and stores it in the impl
+    /// without synchronization. Clones share the impl and may be used by other
+    /// threads, so an out-of-date impl is only updated while it is unique.
+    pub(in crate) fn with_info(&self) -> Cow<'_, Path> {
+        let impl_ = self.impl_();
+        let ref_count = unsafe {
+            (*(&impl_.refCount as *const usize as *const AtomicUsize)).load(Ordering::Acquire)
+        };
+        if impl_.flags & PathFlags::DIRTY.bits() == 0 || ref_count == 1 {
+            Cow::Borrowed(self)
+        } else {
+            Cow::Owned(self.clone_deep())
+        }
+    }
+
     /// Returns this path's flags, or `None` if its geometry is invalid.
     pub fn info_flags(&self) -> Option<PathFlags> {
         unsafe {
             let mut flags = 0;
-            errcode_to_result(ffi::blPathGetInfoFlags(self.core(), &mut flags))
+            errcode_to_result(ffi::blPathGetInfoFlags(self.with_info().core(), &mut flags))
                 .map(|_| PathFlags::from_bits_truncate(flags))
                 .ok()
         }
@@ -347,7 +411,7 @@ impl Path {
         unsafe {
             let mut box2d = BoxD::default();
             errcode_to_result(ffi::blPathGetControlBox(
-                self.core(),
+                self.with_info().core(),
                 &mut box2d as *mut _ as *mut _,
             ))
             .map(|_| box2d)
@@ -361,7 +425,7 @@ impl Path {
         unsafe {
             let mut box2d = BoxD::default();
             errcode_to_result(ffi::blPathGetBoundingBox(
-                self.core(),
+                self.with_info().core(),
                 &mut box2d as *mut _ as *mut _,
             ))
             .map(|_| box2d)
@@ -382,6 +446,30 @@ impl Path {
         }
     }
 
+    /// Returns an iterator over the segments of this path.
+    #[inline]
+    pub fn segments(&self) -> Segments<'_> {
+        Segments::new(self.command_data(), self.vertex_data())
+    }
+
+    /// Returns an iterator over the segments in the given range of vertices,
+    /// for example the one returned by [`figure_range`](#method.figure_range).
+    ///
+    /// # Panics
+    ///
+    /// Panics if the range is out of bounds.
+    #[inline]
+    pub fn segments_range<R: ops::RangeBounds<usize>>(&self, range: R) -> Segments<'_> {
+        let range = (range.start_bound().cloned(), range.end_bound().cloned());
+        Segments::new(&self.command_data()[range], &self.vertex_data()[range])
+    }
+
+    /// Returns an iterator over the figures of this path.
+    #[inline]
+    pub fn figures(&self) -> Figures<'_> {
+        Figures::new(self.command_data(), self.vertex_data())
+    }
+
     #[inline]
     pub fn last_vertex(&self) -> Option<PointD> {
         unsafe {
@@ -401,7 +489,7 @@ impl Path {
             let mut idx = 0;
             let mut dout = 0.0;
             errcode_to_result(ffi::blPathGetClosestVertex(
-                self.core(),
+                self.with_info().core(),
                 p as *const _ as *const _,
                 max_distance,
                 &mut idx,
@@ -416,7 +504,8 @@ impl Path {
     #[inline]
     pub fn hit_test(&self, p: &PointD, fill_rule: FillRule) -> HitTest {
         unsafe {
-            ffi::blPathHitTest(self.core(), p as *const _ as *const _, fill_rule as u32).into()
+            let path = self.with_info();
+            ffi::blPathHitTest(path.core(), p as *const _ as *const _, fill_rule as u32).into()
         }
     }
 
@@ -431,6 +520,40 @@ impl Path {
     pub fn set_vertex_at_point(&mut self, index: usize, cmd: PathCommand, point: PointD) {
         unsafe { ffi::blPathSetVertexAt(self.core_mut(), index, cmd as u32, point.x, point.y) };
     }
+
+    /// Returns the box covered by this path if it consists of a single
+    /// axis-aligned rectangle, optionally closed.
+    pub(in crate) fn axis_aligned_rect(&self) -> Option<BoxD> {
+        let mut cmds = self.command_data();
+        let mut vtx = self.vertex_data();
+        if cmds.last() == Some(&(PathCommand::Close as u8)) {
+            cmds = &cmds[..cmds.len() - 1];
+            vtx = &vtx[..vtx.len() - 1];
+        }
+        if vtx.len() == 5 && vtx[4] == vtx[0] {
+            cmds = &cmds[..4];
+            vtx = &vtx[..4];
+        }
+        if cmds.len() != 4
+            || cmds[0] != PathCommand::Move as u8
+            || cmds[1..].iter().any(|&cmd| cmd != PathCommand::On as u8)
+        {
+            return None;
+        }
+        let (p0, p1, p2, p3) = (vtx[0], vtx[1], vtx[2], vtx[3]);
+        let horizontal_first = p0.y == p1.y && p1.x == p2.x && p2.y == p3.y && p3.x == p0.x;
+        let vertical_first = p0.x == p1.x && p1.y == p2.y && p2.x == p3.x && p3.y == p0.y;
+        if horizontal_first || vertical_first {
+            Some(BoxD {
+                x0: p0.x.min(p2.x),
+                y0: p0.y.min(p2.y),
+                x1: p0.x.max(p2.x),
+                y1: p0.y.max(p2.y),
+            })
+        } else {
+            None
+        }
+    }
 }
 
 impl Path {
@@ -716,7 +839,7 @@ impl Path {
         unsafe {
             expect_mem_err(ffi::blPathAddPath(
                 self.core_mut(),
-                other.core(),
+                other.with_info().core(),
                 ptr::null(),
             ))
         };
@@ -728,7 +851,7 @@ impl Path {
         unsafe {
             expect_mem_err(ffi::blPathAddPath(
                 self.core_mut(),
-                other.core(),
+                other.with_info().core(),
                 &bl_range(range),
             ))
         };
@@ -771,7 +894,7 @@ impl Path {
         unsafe {
             expect_mem_err(ffi::blPathAddTransformedPath(
                 self.core_mut(),
-                other.core(),
+                other.with_info().core(),
                 ptr::null(),
                 m as *const _ as *const _,
             ))
@@ -789,7 +912,7 @@ impl Path {
         unsafe {
             expect_mem_err(ffi::blPathAddTransformedPath(
                 self.core_mut(),
-                other.core(),
+                other.with_info().core(),
                 &bl_range(range),
                 m as *const _ as *const _,
             ))
@@ -950,6 +1073,205 @@ impl Path {
     }
 }
 
+impl Path {
+    /// Parses SVG path data, the contents of the `d` attribute of a `<path>`
+    /// element, into a new path.
+    ///
+    /// The full SVG 1.1 path grammar is supported. Smooth curves (`S` and
+    /// `T`) reflect the control point of the preceding curve as specified,
+    /// arcs are converted by [`elliptic_arc_to`](#method.elliptic_arc_to).
+    ///
+    /// # Errors
+    ///
+    /// Returns an [`Error::SvgPath`] with the byte position at which the
+    /// malformed input was detected.
+    ///
+    /// [`Error::SvgPath`]: ../error/enum.Error.html#variant.SvgPath
+    pub fn from_svg_path_data(data: &str) -> Result<Path> {
+        let mut path = Path::new();
+        svg::parse_path_data(&mut path, data)?;
+        Ok(path)
+    }
+
+    /// Serializes this path into SVG path data.
+    ///
+    /// Every command and vertex is written out exactly, with absolute
+    /// coordinates, so that [`from_svg_path_data`](#method.from_svg_path_data)
+    /// reproduces the same command and vertex data.
+    pub fn to_svg_path_data(&self) -> String {
+        svg::write_path_data(self)
+    }
+}
+
+impl Path {
+    /// Combines this path with another one, returning the outline of the area
+    /// that results from the boolean operation.
+    ///
+    /// This path is the operand `A` and `other` is the operand `B` of the
+    /// [`BooleanOp`](../enum.BooleanOp.html), and the area of each is
+    /// determined by `fill_rule`. Curves are flattened with the default
+    /// [`ApproximationOptions`](struct.ApproximationOptions.html), so the
+    /// result consists of closed polygons only.
+    ///
+    /// Coincident edges of both operands are merged, and touching figures are
+    /// kept as separate figures of the result. Figures with non-finite
+    /// vertices are ignored.
+    ///
+    /// The running time is quadratic in the number of edges of the flattened
+    /// operands.
+    #[inline]
+    pub fn combine(&self, other: &Path, op: BooleanOp, fill_rule: FillRule) -> Path {
+        self.combine_with_options(other, op, fill_rule, &ApproximationOptions::default())
+    }
+
+    /// Like [`combine`](#method.combine), but flattens curves with
+    /// [`ApproximationOptions::flatten_tolerance`] of the given options.
+    ///
+    /// [`ApproximationOptions::flatten_tolerance`]: struct.ApproximationOptions.html#structfield.flatten_tolerance
+    pub fn combine_with_options(
+        &self,
+        other: &Path,
+        op: BooleanOp,
+        fill_rule: FillRule,
+        approx: &ApproximationOptions,
+    ) -> Path {
+        boolean::combine(self, other, op, fill_rule, approx.flatten_tolerance)
+    }
+}
+
+impl Path {
+    /// Flattens every figure of this path into a polyline, approximating
+    /// curves by lines that deviate at most
+    /// [`ApproximationOptions::flatten_tolerance`] from them.
+    ///
+    /// Figures without any points are left out.
+    ///
+    /// [`ApproximationOptions::flatten_tolerance`]: struct.ApproximationOptions.html#structfield.flatten_tolerance
+    #[inline]
+    pub fn flatten(&self, approx: &ApproximationOptions) -> Vec<Polyline> {
+        flatten::flatten_path(self, approx.flatten_tolerance)
+    }
+
+    /// Replaces every curve of this path by lines, as [`flatten`](#method.flatten)
+    /// does, keeping closed figures closed.
+    pub fn flatten_in_place(&mut self, approx: &ApproximationOptions) {
+        let polylines = self.flatten(approx);
+        self.clear();
+        for polyline in polylines {
+            self.move_to_point(&polyline.points[0]);
+            self.poly_to(&polyline.points[1..]);
+            if polyline.closed {
+                self.close();
+            }
+        }
+    }
+}
+
+impl Path {
+    /// Reduces the number of vertices of this path, using the
+    /// Ramer-Douglas-Peucker algorithm to leave out every vertex that lies
+    /// within `tolerance` of the simplified figure.
+    ///
+    /// Curves are flattened with the default
+    /// [`ApproximationOptions`](struct.ApproximationOptions.html) first, so
+    /// the result consists of lines only. Closed figures stay closed, figures
+    /// that collapse into a single point are left out.
+    #[inline]
+    pub fn simplify(&self, tolerance: f64) -> Path {
+        let approx = ApproximationOptions {
+            simplyify_tolerance: tolerance,
+            ..Default::default()
+        };
+        self.simplify_with_options(&approx)
+    }
+
+    /// Like [`simplify`](#method.simplify), but flattens curves with
+    /// [`ApproximationOptions::flatten_tolerance`] and simplifies with
+    /// [`ApproximationOptions::simplyify_tolerance`] of the given options.
+    ///
+    /// [`ApproximationOptions::flatten_tolerance`]: struct.ApproximationOptions.html#structfield.flatten_tolerance
+    /// [`ApproximationOptions::simplyify_tolerance`]: struct.ApproximationOptions.html#structfield.simplyify_tolerance
+    pub fn simplify_with_options(&self, approx: &ApproximationOptions) -> Path {
+        simplify::simplify(self, approx.flatten_tolerance, approx.simplyify_tolerance)
+    }
+
+    /// Returns the outline of the area of this path grown by `distance`, or
+    /// shrunk if `distance` is negative.
+    ///
+    /// The area is the one filled with [`FillRule::NonZero`], so open figures
+    /// count as closed. Corners that move outwards are joined with `join`,
+    /// with `miter_limit` applying to the miter joins.
+    ///
+    /// The offset outline is created by blend2d's stroker, which uses the
+    /// [`OffsetMode`](enum.OffsetMode.html) and `offset_parameter` of the
+    /// default [`ApproximationOptions`](struct.ApproximationOptions.html).
+    /// The result is computed by [`combine`](#method.combine) and consists of
+    /// closed polygons only.
+    ///
+    /// [`FillRule::NonZero`]: ../geometry/enum.FillRule.html#variant.NonZero
+    #[inline]
+    pub fn offset(&self, distance: f64, join: StrokeJoin, miter_limit: f64) -> Path {
+        self.offset_with_options(
+            distance,
+            join,
+            miter_limit,
+            &ApproximationOptions::default(),
+        )
+    }
+
+    /// Like [`offset`](#method.offset), but uses the given options for
+    /// offsetting and flattening curves.
+    pub fn offset_with_options(
+        &self,
+        distance: f64,
+        join: StrokeJoin,
+        miter_limit: f64,
+        approx: &ApproximationOptions,
+    ) -> Path {
+        offset::offset(self, distance, join, miter_limit, approx)
+    }
+}
+
+impl Path {
+    /// Measures the arc length of this path, flattening its curves with the
+    /// given options.
+    ///
+    /// Use this instead of [`length`](#method.length),
+    /// [`point_at`](#method.point_at) and [`segment`](#method.segment) when
+    /// querying the same path repeatedly.
+    #[inline]
+    pub fn measure(&self, approx: &ApproximationOptions) -> PathMeasure {
+        PathMeasure::new(self, approx)
+    }
+
+    /// Returns the length of this path.
+    ///
+    /// See [`PathMeasure::length`](struct.PathMeasure.html#method.length).
+    #[inline]
+    pub fn length(&self) -> f64 {
+        self.measure(&ApproximationOptions::default()).length()
+    }
+
+    /// Returns the point at the given arc length and the unit tangent of this
+    /// path at that point.
+    ///
+    /// See [`PathMeasure::point_at`](struct.PathMeasure.html#method.point_at).
+    #[inline]
+    pub fn point_at(&self, distance: f64) -> Option<(PointD, PointD)> {
+        self.measure(&ApproximationOptions::default())
+            .point_at(distance)
+    }
+
+    /// Returns the part of this path between the arc lengths `from` and `to`.
+    ///
+    /// See [`PathMeasure::segment`](struct.PathMeasure.html#method.segment).
+    #[inline]
+    pub fn segment(&self, from: f64, to: f64) -> Path {
+        self.measure(&ApproximationOptions::default())
+            .segment(from, to)
+    }
+}
+
 impl PartialEq for Path {
     #[inline]
     fn eq(&self, other: &Self) -> bool {
@@ -0,0 +1,546 @@
+//! Boolean operations on flattened paths.
+//!
+//! Both operands are flattened into polygons whose edges are split at every
+//! intersection and at every vertex that touches another edge, with nearby
+//! vertices snapped together. This turns the edges into a planar graph in
+//! which coincident edges collapse into one. Every graph edge is then kept if
+//! the result of the operation differs on its two sides, which is decided by
+//! the winding numbers of both operands at points just beside the edge.
+//! Finally the kept edges are linked into contours that have the inside of
+//! the result on their left.
+//!
+//! Every pass compares each edge against every other edge, so the cost grows
+//! quadratically with the number of edges after flattening. This is fine for
+//! the shapes of user interfaces and icons, but large inputs should rather be
+//! combined with a coarser flatten tolerance.
+use std::collections::{HashMap, HashSet};
+
+use crate::geometry::{FillRule, PointD};
+use crate::path::flatten::flatten_path;
+use crate::path::geom::{cross, distance_to_segment, dot, norm, sub};
+use crate::path::Path;
+use crate::BooleanOp;
+
+#[derive(Copy, Clone, Debug, PartialEq, Eq)]
+enum Operand {
+    A,
+    B,
+}
+
+/// Deduplicates points that lie within a grid cell of each other.
+struct VertexPool {
+    grid: f64,
+    points: Vec<PointD>,
+    cells: HashMap<(i64, i64), usize>,
+}
+
+impl VertexPool {
+    fn new(grid: f64) -> Self {
+        VertexPool {
+            grid,
+            points: Vec::new(),
+            cells: HashMap::new(),
+        }
+    }
+
+    fn insert(&mut self, p: PointD) -> usize {
+        let cx = (p.x / self.grid).round() as i64;
+        let cy = (p.y / self.grid).round() as i64;
+        for dx in -1..=1 {
+            for dy in -1..=1 {
+                if let Some(&id) = self.cells.get(&(cx + dx, cy + dy)) {
+                    let q = self.points[id];
+                    if (q.x - p.x).abs() <= self.grid && (q.y - p.y).abs() <= self.grid {
+                        return id;
+                    }
+                }
+            }
+        }
+        let id = self.points.len();
+        self.points.push(p);
+        self.cells.insert((cx, cy), id);
+        id
+    }
+}
+
+struct Segment {
+    from: PointD,
+    to: PointD,
+    operand: Operand,
+    /// The points at which this segment has to be split, including its ends.
+    splits: Vec<PointD>,
+}
+
+fn collect_segments(path: &Path, operand: Operand, tolerance: f64, out: &mut Vec<Segment>) {
+    for polyline in flatten_path(path, tolerance) {
+        // Non-finite vertices don't describe an area.
+        let points = &polyline.points;
+        if points.iter().any(|p| !p.x.is_finite() || !p.y.is_finite()) {
+            continue;
+        }
+        // Filling implicitly closes every figure.
+        for (i, &from) in points.iter().enumerate() {
+            let to = points[(i + 1) % points.len()];
+            if from != to {
+                out.push(Segment {
+                    from,
+                    to,
+                    operand,
+                    splits: vec![from, to],
+                });
+            }
+        }
+    }
+}
+
+/// Records the points at which two segments touch or cross in both of them.
+fn intersect(a: &mut Segment, b: &mut Segment, eps: f64) {
+    if a.from.x.min(a.to.x) > b.from.x.max(b.to.x) + eps
+        || b.from.x.min(b.to.x) > a.from.x.max(a.to.x) + eps
+        || a.from.y.min(a.to.y) > b.from.y.max(b.to.y) + eps
+        || b.from.y.min(b.to.y) > a.from.y.max(a.to.y) + eps
+    {
+        return;
+    }
+    // Endpoints that lie on the other segment, which also covers collinear
+    // overlaps.
+    for &p in &[b.from, b.to] {
+        if distance_to_segment(p, a.from, a.to) <= eps {
+            a.splits.push(p);
+        }
+    }
+    for &p in &[a.from, a.to] {
+        if distance_to_segment(p, b.from, b.to) <= eps {
+            b.splits.push(p);
+        }
+    }
+    // Proper crossings.
+    let r = sub(a.to, a.from);
+    let s = sub(b.to, b.from);
+    let denom = cross(r, s);
+    if denom == 0.0 {
+        return;
+    }
+    let qp = sub(b.from, a.from);
+    let t = cross(qp, s) / denom;
+    let u = cross(qp, r) / denom;
+    if t > 0.0 && t < 1.0 && u > 0.0 && u < 1.0 {
+        let p = PointD {
+            x: a.from.x + r.x * t,
+            y: a.from.y + r.y * t,
+        };
+        a.splits.push(p);
+        b.splits.push(p);
+    }
+}
+
+/// The winding number of `p` with respect to the directed edges of one
+/// operand, using a ray towards positive x.
+fn winding(
+    p: PointD,
+    points: &[PointD],
+    edges: &[(usize, usize, Operand)],
+    operand: Operand,
+) -> i32 {
+    let mut winding = 0;
+    for &(from, to, _) in edges.iter().filter(|e| e.2 == operand) {
+        let (a, b) = (points[from], points[to]);
+        if (a.y <= p.y) != (b.y <= p.y) {
+            let x = a.x + (p.y - a.y) / (b.y - a.y) * (b.x - a.x);
+            if x > p.x {
+                winding += if b.y > a.y { 1 } else { -1 };
+            }
+        }
+    }
+    winding
+}
+
+fn is_inside(winding: i32, fill_rule: FillRule) -> bool {
+    match fill_rule {
+        FillRule::NonZero => winding != 0,
+        FillRule::EvenOdd => winding % 2 != 0,
+    }
+}
+
+fn apply_op(op: BooleanOp, a: bool, b: bool) -> bool {
+    match op {
+        BooleanOp::Copy => b,
+        BooleanOp::And => a && b,
+        BooleanOp::Or => a || b,
+        BooleanOp::Xor => a != b,
+        BooleanOp::Sub => a && !b,
+    }
+}
+
+pub(in crate) fn combine(
+    a: &Path,
+    b: &Path,
+    op: BooleanOp,
+    fill_rule: FillRule,
+    tolerance: f64,
+) -> Path {
+    let mut segments = Vec::new();
+    collect_segments(a, Operand::A, tolerance, &mut segments);
+    collect_segments(b, Operand::B, tolerance, &mut segments);
+
+    let extent = segments
+        .iter()
+        .flat_map(|s| vec![s.from, s.to])
+        .fold(1.0f64, |acc, p| acc.max(p.x.abs()).max(p.y.abs()));
+    let eps = extent * 1e-10;
+
+    for i in 0..segments.len() {
+        let (head, tail) = segments.split_at_mut(i + 1);
+        let a = &mut head[i];
+        for b in tail {
+            intersect(a, b, eps);
+        }
+    }
+
+    // Split the segments into the directed edges of the planar graph.
+    let mut pool = VertexPool::new(eps);
+    let mut edges = Vec::new();
+    for segment in &mut segments {
+        let dir = sub(segment.to, segment.from);
+        let from = segment.from;
+        segment
+            .splits
+            .sort_by(|p, q| dot(sub(*p, from), dir).total_cmp(&dot(sub(*q, from), dir)));
+        let ids: Vec<usize> = segment.splits.iter().map(|&p| pool.insert(p)).collect();
+        for pair in ids.windows(2) {
+            if pair[0] != pair[1] {
+                edges.push((pair[0], pair[1], segment.operand));
+            }
+        }
+    }
+    let points = pool.points;
+
+    let mut unique = Vec::new();
+    let mut seen = HashSet::new();
+    for &(from, to, _) in &edges {
+        if seen.insert((from.min(to), from.max(to))) {
+            unique.push((from, to));
+        }
+    }
+
+    // Keep the edges that separate the inside from the outside of the result,
+    // oriented so that the inside is on their left.
+    let mut kept = Vec::new();
+    for &(from, to) in &unique {
+        let (p, q) = (points[from], points[to]);
+        let dir = sub(q, p);
+        let len = norm(dir);
+        let mid = PointD {
+            x: (p.x + q.x) * 0.5,
+            y: (p.y + q.y) * 0.5,
+        };
+        // Stay closer to this edge than to any other one.
+        let mut offset = len * 0.25;
+        for &(e_from, e_to) in &unique {
+            if (e_from, e_to) != (from, to) {
+                let d = distance_to_segment(mid, points[e_from], points[e_to]);
+                offset = offset.min(d * 0.5);
+            }
+        }
+        let normal = PointD {
+            x: -dir.y / len * offset,
+            y: dir.x / len * offset,
+        };
+        let left = PointD {
+            x: mid.x + normal.x,
+            y: mid.y + normal.y,
+        };
+        let right = PointD {
+            x: mid.x - normal.x,
+            y: mid.y - normal.y,
+        };
+        let inside = |s: PointD| {
+            apply_op(
+                op,
+                is_inside(winding(s, &points, &edges, Operand::A), fill_rule),
+                is_inside(winding(s, &points, &edges, Operand::B), fill_rule),
+            )
+        };
+        match (inside(left), inside(right)) {
+            (true, false) => kept.push((from, to)),
+            (false, true) => kept.push((to, from)),
+            _ => (),
+        }
+    }
+
+    let mut outgoing: HashMap<usize, Vec<usize>> = HashMap::new();
+    for (i, &(from, _)) in kept.iter().enumerate() {
+        outgoing.entry(from).or_default().push(i);
+    }
+    let mut used = vec![false; kept.len()];
+    let mut result = Path::new();
+    for start in 0..kept.len() {
+        if used[start] {
+            continue;
+        }
+        used[start] = true;
+        let mut contour = vec![points[kept[start].0]];
+        let mut current = start;
+        loop {
+            let (from, to) = kept[current];
+            let dir = sub(points[to], points[from]);
+            // Take the leftmost turn, which keeps contours that only touch
+            // at a vertex apart.
+            let next = outgoing
+                .get(&to)
+                .into_iter()
+                .flatten()
+                .copied()
+                .filter(|&e| !used[e])
+                .max_by(|&e1, &e2| {
+                    let turn = |e: usize| {
+                        let out = sub(points[kept[e].1], points[kept[e].0]);
+                        cross(dir, out).atan2(dot(dir, out))
+                    };
+                    turn(e1).total_cmp(&turn(e2))
+                });
+            match next {
+                Some(next) => {
+                    used[next] = true;
+                    contour.push(points[to]);
+                    current = next;
+                },
+                None => break,
+            }
+        }
+        add_contour(&mut result, &contour);
+    }
+    result
+}
+
+/// Adds the contour as a closed figure, leaving out vertices that lie on a
+/// straight line between their neighbors.
+fn add_contour(path: &mut Path, contour: &[PointD]) {
+    let n = contour.len();
+    let points: Vec<PointD> = (0..n)
+        .filter(|&i| {
+            let prev = contour[(i + n - 1) % n];
+            let next = contour[(i + 1) % n];
+            let (d0, d1) = (sub(contour[i], prev), sub(next, contour[i]));
+            cross(d0, d1).abs() > 1e-12 * norm(d0) * norm(d1) || dot(d0, d1) < 0.0
+        })
+        .map(|i| contour[i])
+        .collect();
+    if points.len() < 3 {
+        return;
+    }
+    path.move_to_point(&points[0]);
+    path.poly_to(&points[1..]);
+    path.close();
+}
+
+#[cfg(test)]
+mod test_boolean {
+    use crate::geometry::{FillRule, PointD};
+    use crate::path::{ApproximationOptions, Path, PathSegment};
+    use crate::BooleanOp;
+
+    fn rect(x0: f64, y0: f64, x1: f64, y1: f64) -> Path {
+        let mut path = Path::new();
+        path.move_to(x0, y0);
+        path.line_to(x1, y0);
+        path.line_to(x1, y1);
+        path.line_to(x0, y1);
+        path.close();
+        path
+    }
+
+    /// The vertices of every figure of a polygonal path.
+    fn figures(path: &Path) -> Vec<Vec<PointD>> {
+        path.figures()
+            .map(|figure| {
+                figure
+                    .filter_map(|segment| match segment {
+                        PathSegment::MoveTo(p) | PathSegment::LineTo(p) => Some(p),
+                        _ => None,
+                    })
+                    .collect()
+            })
+            .collect()
+    }
+
+    /// The signed area of a polygonal path, positive for figures whose
+    /// inside is on their left.
+    fn area(path: &Path) -> f64 {
+        figures(path)
+            .iter()
+            .map(|points| {
+                let n = points.len();
+                (0..n)
+                    .map(|i| {
+                        let (p, q) = (points[i], points[(i + 1) % n]);
+                        p.x * q.y - q.x * p.y
+                    })
+                    .sum::<f64>()
+                    / 2.0
+            })
+            .sum()
+    }
+
+    fn assert_area(path: &Path, expected: f64) {
+        let area = area(path);
+        assert!(
+            (area - expected).abs() < 1e-9,
+            "area {} != {}",
+            area,
+            expected
+        );
+    }
+
+    #[test]
+    fn test_overlapping_squares() {
+        let a = rect(0.0, 0.0, 2.0, 2.0);
+        let b = rect(1.0, 1.0, 3.0, 3.0);
+        let combine = |op| a.combine(&b, op, FillRule::NonZero);
+        assert_area(&combine(BooleanOp::Or), 7.0);
+        assert_area(&combine(BooleanOp::And), 1.0);
+        assert_area(&combine(BooleanOp::Xor), 6.0);
+        assert_area(&combine(BooleanOp::Sub), 3.0);
+        assert_area(&combine(BooleanOp::Copy), 4.0);
+        assert_eq!(figures(&combine(BooleanOp::Or)).len(), 1);
+        assert_eq!(figures(&combine(BooleanOp::Or))[0].len(), 8);
+        assert_eq!(figures(&combine(BooleanOp::Xor)).len(), 2);
+    }
+
+    #[test]
+    fn test_orientation_does_not_matter() {
+        let a = rect(0.0, 0.0, 2.0, 2.0);
+        let b = rect(3.0, 3.0, 1.0, 1.0);
+        assert_area(&a.combine(&b, BooleanOp::Or, FillRule::NonZero), 7.0);
+        assert_area(&b.combine(&a, BooleanOp::Sub, FillRule::EvenOdd), 3.0);
+    }
+
+    #[test]
+    fn test_shared_edge() {
+        let a = rect(0.0, 0.0, 1.0, 1.0);
+        let b = rect(1.0, 0.0, 2.0, 1.0);
+        let union = a.combine(&b, BooleanOp::Or, FillRule::NonZero);
+        assert_area(&union, 2.0);
+        assert_eq!(figures(&union).len(), 1);
+        assert_eq!(figures(&union)[0].len(), 4);
+        assert!(a.combine(&b, BooleanOp::And, FillRule::NonZero).is_empty());
+        assert_area(&a.combine(&b, BooleanOp::Sub, FillRule::NonZero), 1.0);
+    }
+
+    #[test]
+    fn test_identical() {
+        let a = rect(0.0, 0.0, 1.0, 1.0);
+        assert_area(&a.combine(&a, BooleanOp::Or, FillRule::NonZero), 1.0);
+        assert_area(&a.combine(&a, BooleanOp::And, FillRule::NonZero), 1.0);
+        assert!(a.combine(&a, BooleanOp::Xor, FillRule::NonZero).is_empty());
+        assert!(a.combine(&a, BooleanOp::Sub, FillRule::NonZero).is_empty());
+    }
+
+    #[test]
+    fn test_partially_coincident_edges() {
+        let a = rect(0.0, 0.0, 2.0, 1.0);
+        let b = rect(1.0, 0.0, 3.0, 1.0);
+        let union = a.combine(&b, BooleanOp::Or, FillRule::NonZero);
+        assert_area(&union, 3.0);
+        assert_eq!(figures(&union)[0].len(), 4);
+        assert_area(&a.combine(&b, BooleanOp::And, FillRule::NonZero), 1.0);
+        assert_area(&a.combine(&b, BooleanOp::Xor, FillRule::NonZero), 2.0);
+    }
+
+    #[test]
+    fn test_touching_vertex() {
+        let a = rect(0.0, 0.0, 1.0, 1.0);
+        let b = rect(1.0, 1.0, 2.0, 2.0);
+        let union = a.combine(&b, BooleanOp::Or, FillRule::NonZero);
+        assert_area(&union, 2.0);
+        assert_eq!(figures(&union).len(), 2);
+        assert!(figures(&union).iter().all(|f| f.len() == 4));
+        assert!(a.combine(&b, BooleanOp::And, FillRule::NonZero).is_empty());
+    }
+
+    #[test]
+    fn test_empty_and_degenerate() {
+        let a = rect(0.0, 0.0, 1.0, 1.0);
+        let empty = Path::new();
+        let mut degenerate = Path::new();
+        degenerate.move_to(0.5, 0.5);
+        degenerate.line_to(2.0, 0.5);
+        degenerate.line_to(0.5, 0.5);
+        degenerate.move_to(3.0, 3.0);
+
+        for b in &[&empty, &degenerate] {
+            assert_area(&a.combine(b, BooleanOp::Or, FillRule::NonZero), 1.0);
+            assert_area(&b.combine(&a, BooleanOp::Or, FillRule::NonZero), 1.0);
+            assert_area(&a.combine(b, BooleanOp::Sub, FillRule::NonZero), 1.0);
+            assert!(a.combine(b, BooleanOp::And, FillRule::NonZero).is_empty());
+            assert!(a.combine(b, BooleanOp::Copy, FillRule::NonZero).is_empty());
+        }
+        assert!(empty
+            .combine(&empty, BooleanOp::Or, FillRule::NonZero)
+            .is_empty());
+    }
+
+    #[test]
+    fn test_non_finite() {
+        let a = rect(0.0, 0.0, 1.0, 1.0);
+        let mut b = rect(2.0, 0.0, 3.0, 1.0);
+        b.move_to(f64::NAN, 0.0);
+        b.line_to(1.0, f64::INFINITY);
+        b.line_to(0.5, 0.5);
+        let union = a.combine(&b, BooleanOp::Or, FillRule::NonZero);
+        assert_area(&union, 2.0);
+        assert_eq!(figures(&union).len(), 2);
+    }
+
+    #[test]
+    fn test_fill_rule() {
+        // Two nested squares with the same orientation.
+        let mut a = rect(0.0, 0.0, 4.0, 4.0);
+        a.add_path(&rect(1.0, 1.0, 3.0, 3.0));
+        let empty = Path::new();
+        assert_area(&a.combine(&empty, BooleanOp::Or, FillRule::NonZero), 16.0);
+        let ring = a.combine(&empty, BooleanOp::Or, FillRule::EvenOdd);
+        assert_area(&ring, 12.0);
+        assert_eq!(figures(&ring).len(), 2);
+
+        // A self-intersecting bowtie.
+        let mut bowtie = Path::new();
+        bowtie.move_to(0.0, 0.0);
+        bowtie.line_to(2.0, 2.0);
+        bowtie.line_to(2.0, 0.0);
+        bowtie.line_to(0.0, 2.0);
+        bowtie.close();
+        let result = bowtie.combine(&empty, BooleanOp::Or, FillRule::NonZero);
+        assert_area(&result, 2.0);
+        assert_eq!(figures(&result).len(), 2);
+    }
+
+    #[test]
+    fn test_curves() {
+        let r = 100.0;
+        let mut circle = Path::new();
+        circle.move_to(r, 0.0);
+        circle.arc_quadrant_to(r, r, 0.0, r);
+        circle.arc_quadrant_to(-r, r, -r, 0.0);
+        circle.arc_quadrant_to(-r, -r, 0.0, -r);
+        circle.arc_quadrant_to(r, -r, r, 0.0);
+        circle.close();
+        let square = rect(0.0, 0.0, 2.0 * r, 2.0 * r);
+
+        let expected = std::f64::consts::PI * r * r;
+        let union = circle.combine(&Path::new(), BooleanOp::Or, FillRule::NonZero);
+        assert!((area(&union) - expected).abs() < expected * 0.01);
+        let quarter = circle.combine(&square, BooleanOp::And, FillRule::NonZero);
+        assert!((area(&quarter) - expected / 4.0).abs() < expected * 0.01);
+        let rest = circle.combine(&square, BooleanOp::Sub, FillRule::NonZero);
+        assert!((area(&rest) + area(&quarter) - area(&union)).abs() < 1e-6);
+
+        let approx = ApproximationOptions {
+            flatten_tolerance: 1.0,
+            ..Default::default()
+        };
+        let coarse =
+            circle.combine_with_options(&Path::new(), BooleanOp::Or, FillRule::NonZero, &approx);
+        assert!(coarse.len() < union.len());
+    }
+}
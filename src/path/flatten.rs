@@ -0,0 +1,196 @@
+//! Flattening of paths into polylines.
+use crate::geometry::PointD;
+use crate::path::geom::{lerp, norm};
+use crate::path::{Path, PathSegment};
+
+/// The maximum number of lines a single curve is flattened into.
+const MAX_CURVE_STEPS: usize = 1 << 12;
+
+/// A figure of a flattened path, as returned by
+/// [`Path::flatten`](struct.Path.html#method.flatten).
+#[derive(Clone, Debug, Default, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct Polyline {
+    /// The points of the figure. A closed figure doesn't repeat its first
+    /// point at the end.
+    pub points: Vec<PointD>,
+    /// Whether the figure ends with a
+    /// [`Close`](enum.PathSegment.html#variant.Close).
+    pub closed: bool,
+}
+
+/// The number of lines needed to keep the chords of a curve with the given
+/// maximum second derivative within `tolerance`.
+pub(in crate) fn curve_steps(max_dd: f64, tolerance: f64) -> usize {
+    let steps = (max_dd / (8.0 * tolerance)).sqrt().ceil();
+    if steps.is_nan() {
+        1
+    } else {
+        (steps as usize).clamp(1, MAX_CURVE_STEPS)
+    }
+}
+
+fn push_point(points: &mut Vec<PointD>, p: PointD) {
+    if points.last() != Some(&p) {
+        points.push(p);
+    }
+}
+
+fn flatten_quad(points: &mut Vec<PointD>, p0: PointD, p1: PointD, p2: PointD, tolerance: f64) {
+    let dd = norm(PointD {
+        x: 2.0 * (p0.x - 2.0 * p1.x + p2.x),
+        y: 2.0 * (p0.y - 2.0 * p1.y + p2.y),
+    });
+    let steps = curve_steps(dd, tolerance);
+    for i in 1..steps {
+        let t = i as f64 / steps as f64;
+        push_point(points, lerp(lerp(p0, p1, t), lerp(p1, p2, t), t));
+    }
+    push_point(points, p2);
+}
+
+fn flatten_cubic(
+    points: &mut Vec<PointD>,
+    p0: PointD,
+    p1: PointD,
+    p2: PointD,
+    p3: PointD,
+    tolerance: f64,
+) {
+    let dd0 = norm(PointD {
+        x: p0.x - 2.0 * p1.x + p2.x,
+        y: p0.y - 2.0 * p1.y + p2.y,
+    });
+    let dd1 = norm(PointD {
+        x: p1.x - 2.0 * p2.x + p3.x,
+        y: p1.y - 2.0 * p2.y + p3.y,
+    });
+    let steps = curve_steps(6.0 * dd0.max(dd1), tolerance);
+    for i in 1..steps {
+        let t = i as f64 / steps as f64;
+        let a = lerp(lerp(p0, p1, t), lerp(p1, p2, t), t);
+        let b = lerp(lerp(p1, p2, t), lerp(p2, p3, t), t);
+        push_point(points, lerp(a, b, t));
+    }
+    push_point(points, p3);
+}
+
+/// Flattens every figure of the path into a polyline whose points deviate
+/// at most `tolerance` from the curves.
+pub(in crate) fn flatten_path(path: &Path, tolerance: f64) -> Vec<Polyline> {
+    let mut polylines = Vec::new();
+    for figure in path.figures() {
+        let mut polyline = Polyline::default();
+        for segment in figure {
+            let current = polyline.points.last().copied();
+            match (segment, current) {
+                (PathSegment::MoveTo(p), _) | (PathSegment::LineTo(p), _) => {
+                    push_point(&mut polyline.points, p)
+                },
+                (PathSegment::QuadTo(p1, p2), Some(p0)) => {
+                    flatten_quad(&mut polyline.points, p0, p1, p2, tolerance)
+                },
+                (PathSegment::CubicTo(p1, p2, p3), Some(p0)) => {
+                    flatten_cubic(&mut polyline.points, p0, p1, p2, p3, tolerance)
+                },
+                (PathSegment::QuadTo(_, p), None) | (PathSegment::CubicTo(_, _, p), None) => {
+                    push_point(&mut polyline.points, p)
+                },
+                (PathSegment::Close, _) => polyline.closed = true,
+            }
+        }
+        // A closed figure doesn't repeat its start point.
+        if polyline.closed
+            && polyline.points.len() > 1
+            && polyline.points.first() == polyline.points.last()
+        {
+            polyline.points.pop();
+        }
+        if !polyline.points.is_empty() {
+            polylines.push(polyline);
+        }
+    }
+    polylines
+}
+
+#[cfg(test)]
+mod test_flatten {
+    use crate::geometry::PointD;
+    use crate::path::{ApproximationOptions, Path, PathSegment, Polyline};
+
+    fn approx(flatten_tolerance: f64) -> ApproximationOptions {
+        ApproximationOptions {
+            flatten_tolerance,
+            ..Default::default()
+        }
+    }
+
+    #[test]
+    fn test_flatten_lines() {
+        let mut path = Path::new();
+        path.move_to(0.0, 0.0);
+        path.line_to(1.0, 0.0);
+        path.line_to(1.0, 1.0);
+        path.line_to(0.0, 0.0);
+        path.close();
+        path.move_to(5.0, 5.0);
+        path.line_to(6.0, 5.0);
+        let pt = |x, y| PointD { x, y };
+        assert_eq!(
+            path.flatten(&ApproximationOptions::default()),
+            [
+                Polyline {
+                    points: vec![pt(0.0, 0.0), pt(1.0, 0.0), pt(1.0, 1.0)],
+                    closed: true,
+                },
+                Polyline {
+                    points: vec![pt(5.0, 5.0), pt(6.0, 5.0)],
+                    closed: false,
+                },
+            ]
+        );
+    }
+
+    #[test]
+    fn test_flatten_tolerance() {
+        // The parabola y = 2x - x^2 for x in [0, 2].
+        let mut path = Path::new();
+        path.move_to(0.0, 0.0);
+        path.quad_to(1.0, 2.0, 2.0, 0.0);
+        let curve = |x: f64| 2.0 * x - x * x;
+        for &tolerance in &[1.0, 0.1, 0.001] {
+            let polylines = path.flatten(&approx(tolerance));
+            assert_eq!(polylines.len(), 1);
+            let points = &polylines[0].points;
+            assert_eq!(points.first(), Some(&PointD { x: 0.0, y: 0.0 }));
+            assert_eq!(points.last(), Some(&PointD { x: 2.0, y: 0.0 }));
+            for pair in points.windows(2) {
+                let mid = (pair[0].x + pair[1].x) / 2.0;
+                let chord = (pair[0].y + pair[1].y) / 2.0;
+                assert!((curve(pair[0].x) - pair[0].y).abs() < 1e-12);
+                assert!(curve(mid) - chord <= tolerance);
+            }
+        }
+        assert!(
+            path.flatten(&approx(1.0))[0].points.len() < path.flatten(&approx(0.1))[0].points.len()
+        );
+    }
+
+    #[test]
+    fn test_flatten_in_place() {
+        let mut path = Path::new();
+        path.move_to(0.0, 0.0);
+        path.cubic_to(1.0, 1.0, 2.0, 1.0, 3.0, 0.0);
+        path.close();
+        path.move_to(5.0, 5.0);
+        path.quad_to(6.0, 6.0, 7.0, 5.0);
+
+        let polylines = path.flatten(&approx(0.01));
+        path.flatten_in_place(&approx(0.01));
+        assert!(path.segments().all(|segment| matches!(
+            segment,
+            PathSegment::MoveTo(_) | PathSegment::LineTo(_) | PathSegment::Close
+        )));
+        assert_eq!(path.flatten(&approx(0.01)), polylines);
+    }
+}
@@ -0,0 +1,46 @@
+//! Vector helpers shared by the path algorithms.
+use crate::geometry::PointD;
+
+#[inline]
+pub(in crate) fn sub(a: PointD, b: PointD) -> PointD {
+    PointD {
+        x: a.x - b.x,
+        y: a.y - b.y,
+    }
+}
+
+#[inline]
+pub(in crate) fn dot(a: PointD, b: PointD) -> f64 {
+    a.x * b.x + a.y * b.y
+}
+
+#[inline]
+pub(in crate) fn cross(a: PointD, b: PointD) -> f64 {
+    a.x * b.y - a.y * b.x
+}
+
+/// The length of the vector `p`.
+#[inline]
+pub(in crate) fn norm(p: PointD) -> f64 {
+    dot(p, p).sqrt()
+}
+
+#[inline]
+pub(in crate) fn lerp(a: PointD, b: PointD, t: f64) -> PointD {
+    PointD {
+        x: a.x + (b.x - a.x) * t,
+        y: a.y + (b.y - a.y) * t,
+    }
+}
+
+/// The distance of `p` to the closest point of the segment from `a` to `b`.
+pub(in crate) fn distance_to_segment(p: PointD, a: PointD, b: PointD) -> f64 {
+    let ab = sub(b, a);
+    let len_sq = dot(ab, ab);
+    let t = if len_sq > 0.0 {
+        (dot(sub(p, a), ab) / len_sq).clamp(0.0, 1.0)
+    } else {
+        0.0
+    };
+    norm(sub(p, lerp(a, b, t)))
+}
@@ -0,0 +1,433 @@
+//! Arc length measurement of paths.
+use crate::geometry::PointD;
+use crate::path::flatten::curve_steps;
+use crate::path::geom::{lerp, norm, sub};
+use crate::path::{ApproximationOptions, Path, PathSegment};
+
+/// Splits the control polygon of a bezier curve at `t`, returning the first
+/// part and leaving the second one in `points`.
+fn split(points: &mut [PointD], t: f64) -> Vec<PointD> {
+    let n = points.len();
+    let mut first = Vec::with_capacity(n);
+    first.push(points[0]);
+    for level in 1..n {
+        for i in 0..n - level {
+            points[i] = lerp(points[i], points[i + 1], t);
+        }
+        first.push(points[0]);
+    }
+    first
+}
+
+/// A line or curve with its start point.
+#[derive(Copy, Clone, Debug)]
+enum Curve {
+    Line(PointD, PointD),
+    Quad(PointD, PointD, PointD),
+    Cubic(PointD, PointD, PointD, PointD),
+}
+
+impl Curve {
+    fn points(&self) -> Vec<PointD> {
+        match *self {
+            Curve::Line(p0, p1) => vec![p0, p1],
+            Curve::Quad(p0, p1, p2) => vec![p0, p1, p2],
+            Curve::Cubic(p0, p1, p2, p3) => vec![p0, p1, p2, p3],
+        }
+    }
+
+    fn point_at(&self, t: f64) -> PointD {
+        let mut points = self.points();
+        split(&mut points, t);
+        points[0]
+    }
+
+    /// The derivative at `t`, up to a positive factor.
+    fn direction_at(&self, t: f64) -> PointD {
+        let mut points = self.points();
+        let n = points.len();
+        // The last level of de Casteljau's algorithm spans the tangent.
+        for level in 1..n - 1 {
+            for i in 0..n - level {
+                points[i] = lerp(points[i], points[i + 1], t);
+            }
+        }
+        PointD {
+            x: points[1].x - points[0].x,
+            y: points[1].y - points[0].y,
+        }
+    }
+
+    /// The part of the curve between the parameters `t0` and `t1`.
+    fn part(&self, t0: f64, t1: f64) -> Vec<PointD> {
+        let mut points = self.points();
+        let mut part = split(&mut points, t1);
+        if t1 > 0.0 {
+            split(&mut part, t0 / t1);
+        }
+        part
+    }
+
+    fn steps(&self, tolerance: f64) -> usize {
+        let dd = |a: PointD, b: PointD, c: PointD| {
+            norm(PointD {
+                x: a.x - 2.0 * b.x + c.x,
+                y: a.y - 2.0 * b.y + c.y,
+            })
+        };
+        match *self {
+            Curve::Line(..) => 1,
+            Curve::Quad(p0, p1, p2) => curve_steps(2.0 * dd(p0, p1, p2), tolerance),
+            Curve::Cubic(p0, p1, p2, p3) => {
+                curve_steps(6.0 * dd(p0, p1, p2).max(dd(p1, p2, p3)), tolerance)
+            },
+        }
+    }
+}
+
+#[derive(Clone, Debug)]
+struct Piece {
+    curve: Curve,
+    /// The figure this piece belongs to.
+    figure: usize,
+    /// The arc length at the start of the piece.
+    start: f64,
+    /// The arc length at evenly spaced parameters, relative to `start`.
+    lengths: Vec<f64>,
+}
+
+impl Piece {
+    #[inline]
+    fn length(&self) -> f64 {
+        *self.lengths.last().unwrap()
+    }
+
+    /// Maps an arc length relative to the start of the piece to the curve
+    /// parameter, interpolating between the measured points.
+    fn parameter_at(&self, distance: f64) -> f64 {
+        let steps = self.lengths.len() - 1;
+        let i = self
+            .lengths
+            .partition_point(|&len| len < distance)
+            .clamp(1, steps);
+        let (l0, l1) = (self.lengths[i - 1], self.lengths[i]);
+        let f = if l1 > l0 {
+            ((distance - l0) / (l1 - l0)).clamp(0.0, 1.0)
+        } else {
+            0.0
+        };
+        (i as f64 - 1.0 + f) / steps as f64
+    }
+}
+
+/// Arc length measurements of a [`Path`](struct.Path.html).
+///
+/// The path is measured once on creation, by flattening its curves with the
+/// `flatten_tolerance` of the [`ApproximationOptions`] given to
+/// [`Path::measure`]. Points and sub-paths are evaluated on the original
+/// curves, so only the mapping between arc length and curve parameter is
+/// approximated.
+///
+/// Closed figures include their closing line, and moves don't add to the
+/// length, so distances run through all figures of the path in order.
+/// Segments with non-finite vertices are left out.
+///
+/// [`ApproximationOptions`]: struct.ApproximationOptions.html
+/// [`Path::measure`]: struct.Path.html#method.measure
+#[derive(Clone, Debug)]
+pub struct PathMeasure {
+    pieces: Vec<Piece>,
+    length: f64,
+}
+
+impl PathMeasure {
+    pub(in crate) fn new(path: &Path, approx: &ApproximationOptions) -> Self {
+        let tolerance = approx.flatten_tolerance;
+        let mut measure = PathMeasure {
+            pieces: Vec::new(),
+            length: 0.0,
+        };
+        let mut figure = 0;
+        let mut index = 0;
+        while let Some(range) = path.figure_range(index) {
+            index = range.end;
+            let mut start = None;
+            let mut current = None;
+            for segment in path.segments_range(range) {
+                let curve = match (segment, current) {
+                    (PathSegment::MoveTo(p), _) => {
+                        start = Some(p);
+                        current = Some(p);
+                        continue;
+                    },
+                    (PathSegment::LineTo(p1), Some(p0)) => Curve::Line(p0, p1),
+                    (PathSegment::QuadTo(p1, p2), Some(p0)) => Curve::Quad(p0, p1, p2),
+                    (PathSegment::CubicTo(p1, p2, p3), Some(p0)) => Curve::Cubic(p0, p1, p2, p3),
+                    (PathSegment::Close, Some(p0)) => match start {
+                        Some(p1) if p1 != p0 => Curve::Line(p0, p1),
+                        _ => continue,
+                    },
+                    // A figure without a move starts at its first vertex.
+                    (segment, None) => {
+                        start = segment.end_point();
+                        current = start;
+                        continue;
+                    },
+                };
+                current = curve.points().last().copied();
+                measure.push(curve, figure, tolerance);
+            }
+            figure += 1;
+        }
+        measure
+    }
+
+    fn push(&mut self, curve: Curve, figure: usize, tolerance: f64) {
+        let steps = curve.steps(tolerance);
+        let mut lengths = Vec::with_capacity(steps + 1);
+        let mut length = 0.0;
+        let mut prev = curve.point_at(0.0);
+        lengths.push(length);
+        for i in 1..=steps {
+            let p = curve.point_at(i as f64 / steps as f64);
+            length += norm(sub(p, prev));
+            lengths.push(length);
+            prev = p;
+        }
+        // Curves with non-finite points have no measurable length.
+        if !length.is_finite() {
+            return;
+        }
+        self.pieces.push(Piece {
+            curve,
+            figure,
+            start: self.length,
+            lengths,
+        });
+        self.length += length;
+    }
+
+    /// The index of the piece that contains the arc length.
+    fn piece_at(&self, distance: f64) -> usize {
+        self.pieces
+            .partition_point(|piece| piece.start + piece.length() < distance)
+            .min(self.pieces.len() - 1)
+    }
+
+    /// Returns the total length of the path.
+    #[inline]
+    pub fn length(&self) -> f64 {
+        self.length
+    }
+
+    /// Returns the point at the given arc length and the unit tangent of the
+    /// path at that point.
+    ///
+    /// The distance is clamped to the length of the path. Returns `None` if
+    /// the path has no segments or the distance is NaN.
+    pub fn point_at(&self, distance: f64) -> Option<(PointD, PointD)> {
+        if self.pieces.is_empty() || distance.is_nan() {
+            return None;
+        }
+        let distance = distance.clamp(0.0, self.length);
+        let piece = &self.pieces[self.piece_at(distance)];
+        let t = piece.parameter_at(distance - piece.start);
+        let mut dir = piece.curve.direction_at(t);
+        if dir.x == 0.0 && dir.y == 0.0 {
+            // Coincident control points, fall back to the chord.
+            let points = piece.curve.points();
+            dir = sub(points[points.len() - 1], points[0]);
+        }
+        let len = norm(dir);
+        let tangent = if len > 0.0 {
+            PointD {
+                x: dir.x / len,
+                y: dir.y / len,
+            }
+        } else {
+            PointD::default()
+        };
+        Some((piece.curve.point_at(t), tangent))
+    }
+
+    /// Returns the part of the path between the arc lengths `from` and `to`.
+    ///
+    /// Both distances are clamped to the length of the path, the result is
+    /// empty if `from` isn't less than `to`. Every figure the part runs
+    /// through becomes an open figure of the result, with curves split at
+    /// the exact parameters.
+    pub fn segment(&self, from: f64, to: f64) -> Path {
+        let mut path = Path::new();
+        let from = from.max(0.0);
+        let to = to.min(self.length);
+        if self.pieces.is_empty() || from >= to {
+            return path;
+        }
+        let mut figure = None;
+        for piece in &self.pieces[self.piece_at(from)..=self.piece_at(to)] {
+            let t0 = if from > piece.start {
+                piece.parameter_at(from - piece.start)
+            } else {
+                0.0
+            };
+            let t1 = if to < piece.start + piece.length() {
+                piece.parameter_at(to - piece.start)
+            } else {
+                1.0
+            };
+            if t0 >= t1 {
+                continue;
+            }
+            let points = piece.curve.part(t0, t1);
+            if figure != Some(piece.figure) {
+                figure = Some(piece.figure);
+                path.move_to_point(&points[0]);
+            }
+            match points[1..] {
+                [p1] => path.line_to_point(&p1),
+                [p1, p2] => path.quad_to(p1.x, p1.y, p2.x, p2.y),
+                [p1, p2, p3] => path.cubic_to_points(&p1, &p2, &p3),
+                _ => unreachable!(),
+            }
+        }
+        path
+    }
+}
+
+#[cfg(test)]
+mod test_measure {
+    use crate::geometry::PointD;
+    use crate::path::{ApproximationOptions, Path, PathSegment};
+
+    fn assert_near(a: PointD, b: PointD) {
+        assert!(
+            (a.x - b.x).abs() < 1e-9 && (a.y - b.y).abs() < 1e-9,
+            "{:?} != {:?}",
+            a,
+            b
+        );
+    }
+
+    fn square() -> Path {
+        let mut path = Path::new();
+        path.move_to(0.0, 0.0);
+        path.line_to(1.0, 0.0);
+        path.line_to(1.0, 1.0);
+        path.line_to(0.0, 1.0);
+        path.close();
+        path
+    }
+
+    #[test]
+    fn test_lines() {
+        let mut path = square();
+        assert_eq!(path.length(), 4.0);
+        let (p, tangent) = path.point_at(2.5).unwrap();
+        assert_near(p, PointD { x: 0.5, y: 1.0 });
+        assert_near(tangent, PointD { x: -1.0, y: 0.0 });
+        // On the closing line, and clamped to the end of the path.
+        assert_near(path.point_at(3.5).unwrap().0, PointD { x: 0.0, y: 0.5 });
+        assert_near(path.point_at(10.0).unwrap().0, PointD { x: 0.0, y: 0.0 });
+
+        // Moves don't add to the length.
+        path.move_to(10.0, 10.0);
+        path.line_to(10.0, 12.0);
+        assert_eq!(path.length(), 6.0);
+        assert_near(path.point_at(5.0).unwrap().0, PointD { x: 10.0, y: 11.0 });
+    }
+
+    #[test]
+    fn test_curves() {
+        let mut path = Path::new();
+        path.move_to(0.0, 0.0);
+        path.quad_to(1.0, 1.0, 2.0, 0.0);
+        let measure = path.measure(&ApproximationOptions::default());
+        let (p, tangent) = measure.point_at(measure.length() / 2.0).unwrap();
+        assert_near(p, PointD { x: 1.0, y: 0.5 });
+        assert_near(tangent, PointD { x: 1.0, y: 0.0 });
+        let (_, tangent) = measure.point_at(0.0).unwrap();
+        let d = 0.5f64.sqrt();
+        assert_near(tangent, PointD { x: d, y: d });
+
+        let r = 100.0;
+        let mut circle = Path::new();
+        circle.move_to(r, 0.0);
+        circle.arc_quadrant_to(r, r, 0.0, r);
+        circle.arc_quadrant_to(-r, r, -r, 0.0);
+        circle.arc_quadrant_to(-r, -r, 0.0, -r);
+        circle.arc_quadrant_to(r, -r, r, 0.0);
+        let expected = 2.0 * std::f64::consts::PI * r;
+        assert!((circle.length() - expected).abs() < expected * 1e-3);
+        // A tighter tolerance measures a longer polyline.
+        let approx = ApproximationOptions {
+            flatten_tolerance: 0.001,
+            ..Default::default()
+        };
+        assert!(circle.measure(&approx).length() > circle.length());
+    }
+
+    #[test]
+    fn test_segment() {
+        let path = square();
+        let part = path.segment(0.5, 2.5);
+        let segments: Vec<_> = part.segments().collect();
+        assert_eq!(
+            segments,
+            [
+                PathSegment::MoveTo(PointD { x: 0.5, y: 0.0 }),
+                PathSegment::LineTo(PointD { x: 1.0, y: 0.0 }),
+                PathSegment::LineTo(PointD { x: 1.0, y: 1.0 }),
+                PathSegment::LineTo(PointD { x: 0.5, y: 1.0 }),
+            ]
+        );
+        assert_eq!(part.length(), 2.0);
+        assert!(path.segment(2.0, 1.0).is_empty());
+        assert_eq!(path.segment(-1.0, 10.0).length(), 4.0);
+
+        // The full extent of a curve reproduces it.
+        let mut curve = Path::new();
+        curve.move_to(0.0, 0.0);
+        curve.cubic_to(1.0, 2.0, 3.0, 2.0, 4.0, 0.0);
+        let full: Vec<_> = curve.segment(0.0, curve.length()).segments().collect();
+        assert_eq!(full.len(), 2);
+        match full[1] {
+            PathSegment::CubicTo(p1, p2, p3) => {
+                assert_near(p1, PointD { x: 1.0, y: 2.0 });
+                assert_near(p2, PointD { x: 3.0, y: 2.0 });
+                assert_near(p3, PointD { x: 4.0, y: 0.0 });
+            },
+            segment => panic!("expected a cubic, got {:?}", segment),
+        }
+        let half = curve.segment(0.0, curve.length() / 2.0);
+        assert_near(half.last_vertex().unwrap(), PointD { x: 2.0, y: 1.5 });
+
+        // Each figure becomes a figure of the result.
+        let mut path = square();
+        path.move_to(10.0, 10.0);
+        path.line_to(10.0, 12.0);
+        assert_eq!(path.segment(3.0, 5.0).figures().count(), 2);
+    }
+
+    #[test]
+    fn test_empty() {
+        let path = Path::new();
+        assert_eq!(path.length(), 0.0);
+        assert!(path.point_at(0.0).is_none());
+        assert!(path.segment(0.0, 1.0).is_empty());
+    }
+
+    #[test]
+    fn test_non_finite() {
+        let mut path = Path::new();
+        path.move_to(0.0, 0.0);
+        path.line_to(3.0, 4.0);
+        path.line_to(f64::NAN, 0.0);
+        path.move_to(0.0, 0.0);
+        path.line_to(f64::INFINITY, 0.0);
+        let measure = path.measure(&ApproximationOptions::default());
+        assert_eq!(measure.length(), 5.0);
+        assert_near(measure.point_at(10.0).unwrap().0, PointD { x: 3.0, y: 4.0 });
+        assert!(measure.point_at(f64::NAN).is_none());
+        assert_eq!(measure.segment(f64::NAN, 2.5).length(), 2.5);
+    }
+}
@@ -0,0 +1,138 @@
+//! Offsetting of the area of a path.
+use crate::geometry::FillRule;
+use crate::path::{ApproximationOptions, Path, PathSegment, StrokeCap, StrokeJoin, StrokeOptions};
+use crate::BooleanOp;
+
+/// Returns a copy of the path in which every figure is closed, so that its
+/// stroke follows the outline of the filled area.
+fn close_figures(path: &Path) -> Path {
+    let mut closed = Path::new();
+    for figure in path.figures() {
+        let mut open = false;
+        for segment in figure {
+            match segment {
+                PathSegment::MoveTo(p) => closed.move_to_point(&p),
+                PathSegment::LineTo(p) => closed.line_to_point(&p),
+                PathSegment::QuadTo(p1, p2) => closed.quad_to(p1.x, p1.y, p2.x, p2.y),
+                PathSegment::CubicTo(p1, p2, p3) => closed.cubic_to_points(&p1, &p2, &p3),
+                PathSegment::Close => (),
+            }
+            open = true;
+        }
+        if open {
+            closed.close();
+        }
+    }
+    closed
+}
+
+pub(in crate) fn offset(
+    path: &Path,
+    distance: f64,
+    join: StrokeJoin,
+    miter_limit: f64,
+    approx: &ApproximationOptions,
+) -> Path {
+    // The stroke covers everything within `distance` of the outline, adding
+    // it to the area grows the area and removing it shrinks the area.
+    let mut options = StrokeOptions::new();
+    options.set_width(2.0 * distance.abs());
+    options.set_join(join);
+    options.set_miter_limit(miter_limit);
+    options.set_caps(StrokeCap::Butt);
+    let mut stroke = Path::new();
+    stroke.add_stroked_path(&close_figures(path), &options, approx);
+
+    let op = if distance < 0.0 {
+        BooleanOp::Sub
+    } else {
+        BooleanOp::Or
+    };
+    path.combine_with_options(&stroke, op, FillRule::NonZero, approx)
+}
+
+#[cfg(test)]
+mod test_offset {
+    use crate::path::{Path, PathSegment, StrokeJoin};
+
+    fn square(size: f64) -> Path {
+        let mut path = Path::new();
+        path.move_to(0.0, 0.0);
+        path.line_to(size, 0.0);
+        path.line_to(size, size);
+        path.line_to(0.0, size);
+        path.close();
+        path
+    }
+
+    fn area(path: &Path) -> f64 {
+        path.flatten(&Default::default())
+            .iter()
+            .map(|polyline| {
+                let points = &polyline.points;
+                let n = points.len();
+                (0..n)
+                    .map(|i| {
+                        let (p, q) = (points[i], points[(i + 1) % n]);
+                        p.x * q.y - q.x * p.y
+                    })
+                    .sum::<f64>()
+                    / 2.0
+            })
+            .sum()
+    }
+
+    #[test]
+    fn test_outset_joins() {
+        let path = square(10.0);
+        let miter = path.offset(1.0, StrokeJoin::MiterBevel, 4.0);
+        assert!((area(&miter) - 144.0).abs() < 1e-9);
+        assert_eq!(
+            miter
+                .segments()
+                .filter(|s| *s == PathSegment::Close)
+                .count(),
+            1
+        );
+        // A 90 degree corner exceeds a miter limit below its ratio of sqrt(2).
+        let limited = path.offset(1.0, StrokeJoin::MiterBevel, 1.2);
+        assert!((area(&limited) - 142.0).abs() < 1e-9);
+        let bevel = path.offset(1.0, StrokeJoin::Bevel, 4.0);
+        assert!((area(&bevel) - 142.0).abs() < 1e-9);
+        let round = square(100.0).offset(10.0, StrokeJoin::Round, 4.0);
+        let expected = 14000.0 + 100.0 * std::f64::consts::PI;
+        assert!((area(&round) - expected).abs() < expected * 1e-3);
+    }
+
+    #[test]
+    fn test_inset() {
+        let path = square(10.0);
+        for &join in &[StrokeJoin::MiterBevel, StrokeJoin::Bevel, StrokeJoin::Round] {
+            assert!((area(&path.offset(-1.0, join, 4.0)) - 64.0).abs() < 1e-9);
+        }
+        assert!(path.offset(-6.0, StrokeJoin::Round, 4.0).is_empty());
+
+        // The concave corner of an L shape gets a miter when shrinking.
+        let mut l = Path::new();
+        l.move_to(0.0, 0.0);
+        l.line_to(20.0, 0.0);
+        l.line_to(20.0, 10.0);
+        l.line_to(10.0, 10.0);
+        l.line_to(10.0, 20.0);
+        l.line_to(0.0, 20.0);
+        l.close();
+        let inset = l.offset(-1.0, StrokeJoin::MiterBevel, 4.0);
+        assert!((area(&inset) - (18.0 * 8.0 + 8.0 * 10.0)).abs() < 1e-9);
+    }
+
+    #[test]
+    fn test_open_figures_are_closed() {
+        let mut open = Path::new();
+        open.move_to(0.0, 0.0);
+        open.line_to(10.0, 0.0);
+        open.line_to(10.0, 10.0);
+        open.line_to(0.0, 10.0);
+        let offset = open.offset(1.0, StrokeJoin::MiterBevel, 4.0);
+        assert!((area(&offset) - 144.0).abs() < 1e-9);
+    }
+}
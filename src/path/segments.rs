@@ -0,0 +1,281 @@
+//! Iteration over the segments and figures of a path.
+use std::iter::FusedIterator;
+
+use crate::geometry::PointD;
+use crate::matrix::Matrix2D;
+use crate::path::PathCommand;
+
+/// A segment of a [`Path`](struct.Path.html), decoded from its command and
+/// vertex data.
+#[derive(Copy, Clone, Debug, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub enum PathSegment {
+    /// Starts a new figure at the point.
+    MoveTo(PointD),
+    /// A line to the point.
+    LineTo(PointD),
+    /// A quadratic curve with a control point and an end point.
+    QuadTo(PointD, PointD),
+    /// A cubic curve with two control points and an end point.
+    CubicTo(PointD, PointD, PointD),
+    /// Closes the current figure.
+    Close,
+}
+
+impl PathSegment {
+    /// The point this segment ends at, `None` for [`Close`](#variant.Close).
+    #[inline]
+    pub fn end_point(&self) -> Option<PointD> {
+        match *self {
+            PathSegment::MoveTo(p)
+            | PathSegment::LineTo(p)
+            | PathSegment::QuadTo(_, p)
+            | PathSegment::CubicTo(_, _, p) => Some(p),
+            PathSegment::Close => None,
+        }
+    }
+
+    /// Returns this segment with every point mapped through the matrix.
+    pub fn transformed(&self, m: &Matrix2D) -> Self {
+        match *self {
+            PathSegment::MoveTo(p) => PathSegment::MoveTo(m.map_point(&p)),
+            PathSegment::LineTo(p) => PathSegment::LineTo(m.map_point(&p)),
+            PathSegment::QuadTo(p1, p2) => PathSegment::QuadTo(m.map_point(&p1), m.map_point(&p2)),
+            PathSegment::CubicTo(p1, p2, p3) => {
+                PathSegment::CubicTo(m.map_point(&p1), m.map_point(&p2), m.map_point(&p3))
+            },
+            PathSegment::Close => PathSegment::Close,
+        }
+    }
+
+    /// Decodes the segment at the start of the data, returning it and the
+    /// number of vertices it spans.
+    fn decode(commands: &[u8], vertices: &[PointD]) -> Option<(Self, usize)> {
+        let cmd = |i: usize| commands.get(i).map(|&c| PathCommand::from(u32::from(c)));
+        match cmd(0)? {
+            PathCommand::Move => Some((PathSegment::MoveTo(vertices[0]), 1)),
+            PathCommand::On => Some((PathSegment::LineTo(vertices[0]), 1)),
+            PathCommand::Quad if cmd(1) == Some(PathCommand::On) => {
+                Some((PathSegment::QuadTo(vertices[0], vertices[1]), 2))
+            },
+            PathCommand::Cubic
+                if cmd(1) == Some(PathCommand::Cubic) && cmd(2) == Some(PathCommand::On) =>
+            {
+                Some((
+                    PathSegment::CubicTo(vertices[0], vertices[1], vertices[2]),
+                    3,
+                ))
+            },
+            PathCommand::Close => Some((PathSegment::Close, 1)),
+            _ => None,
+        }
+    }
+}
+
+/// An iterator over the [`PathSegment`]s of a path.
+///
+/// Created by [`Path::segments`] and [`Path::segments_range`]. Iteration
+/// stops early if the data contains an incomplete curve.
+///
+/// [`Path::segments`]: struct.Path.html#method.segments
+/// [`Path::segments_range`]: struct.Path.html#method.segments_range
+#[derive(Clone, Debug)]
+pub struct Segments<'a> {
+    commands: &'a [u8],
+    vertices: &'a [PointD],
+    matrix: Option<Matrix2D>,
+}
+
+impl<'a> Segments<'a> {
+    #[inline]
+    pub(in crate) fn new(commands: &'a [u8], vertices: &'a [PointD]) -> Self {
+        Segments {
+            commands,
+            vertices,
+            matrix: None,
+        }
+    }
+
+    /// Maps every yielded segment through the matrix.
+    #[inline]
+    pub fn with_matrix(mut self, m: &Matrix2D) -> Self {
+        self.matrix = Some(*m);
+        self
+    }
+}
+
+impl Iterator for Segments<'_> {
+    type Item = PathSegment;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        match PathSegment::decode(self.commands, self.vertices) {
+            Some((segment, n)) => {
+                self.commands = &self.commands[n..];
+                self.vertices = &self.vertices[n..];
+                Some(match &self.matrix {
+                    Some(m) => segment.transformed(m),
+                    None => segment,
+                })
+            },
+            None => {
+                self.commands = &[];
+                self.vertices = &[];
+                None
+            },
+        }
+    }
+
+    #[inline]
+    fn size_hint(&self) -> (usize, Option<usize>) {
+        (0, Some(self.commands.len()))
+    }
+}
+
+impl FusedIterator for Segments<'_> {}
+
+/// An iterator over the figures of a path, yielding the [`Segments`] of each.
+///
+/// A figure starts at a [`MoveTo`](enum.PathSegment.html#variant.MoveTo),
+/// ends with a [`Close`](enum.PathSegment.html#variant.Close) if it has one and
+/// spans the same vertices as
+/// [`Path::figure_range`](struct.Path.html#method.figure_range).
+#[derive(Clone, Debug)]
+pub struct Figures<'a> {
+    commands: &'a [u8],
+    vertices: &'a [PointD],
+    matrix: Option<Matrix2D>,
+}
+
+impl<'a> Figures<'a> {
+    #[inline]
+    pub(in crate) fn new(commands: &'a [u8], vertices: &'a [PointD]) -> Self {
+        Figures {
+            commands,
+            vertices,
+            matrix: None,
+        }
+    }
+
+    /// Maps the segments of every yielded figure through the matrix.
+    #[inline]
+    pub fn with_matrix(mut self, m: &Matrix2D) -> Self {
+        self.matrix = Some(*m);
+        self
+    }
+}
+
+impl<'a> Iterator for Figures<'a> {
+    type Item = Segments<'a>;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        if self.commands.is_empty() {
+            return None;
+        }
+        // A figure ends before the next move or after a close.
+        let move_cmd = PathCommand::Move as u8;
+        let close_cmd = PathCommand::Close as u8;
+        let end = self.commands[1..]
+            .iter()
+            .position(|&c| c == move_cmd || c == close_cmd)
+            .map_or(self.commands.len(), |pos| {
+                if self.commands[pos + 1] == close_cmd {
+                    pos + 2
+                } else {
+                    pos + 1
+                }
+            });
+        let (commands, rest_commands) = self.commands.split_at(end);
+        let (vertices, rest_vertices) = self.vertices.split_at(end);
+        self.commands = rest_commands;
+        self.vertices = rest_vertices;
+        Some(Segments {
+            commands,
+            vertices,
+            matrix: self.matrix,
+        })
+    }
+}
+
+impl FusedIterator for Figures<'_> {}
+
+#[cfg(test)]
+mod test_segments {
+    use crate::geometry::PointD;
+    use crate::matrix::Matrix2D;
+    use crate::path::{Path, PathSegment};
+
+    fn pt(x: f64, y: f64) -> PointD {
+        PointD { x, y }
+    }
+
+    fn test_path() -> Path {
+        let mut path = Path::new();
+        path.move_to(0.0, 0.0);
+        path.line_to(10.0, 0.0);
+        path.quad_to(20.0, 0.0, 20.0, 10.0);
+        path.close();
+        path.move_to(5.0, 5.0);
+        path.cubic_to(6.0, 5.0, 7.0, 6.0, 7.0, 7.0);
+        path
+    }
+
+    #[test]
+    fn test_segments() {
+        let path = test_path();
+        let segments: Vec<_> = path.segments().collect();
+        assert_eq!(
+            segments,
+            [
+                PathSegment::MoveTo(pt(0.0, 0.0)),
+                PathSegment::LineTo(pt(10.0, 0.0)),
+                PathSegment::QuadTo(pt(20.0, 0.0), pt(20.0, 10.0)),
+                PathSegment::Close,
+                PathSegment::MoveTo(pt(5.0, 5.0)),
+                PathSegment::CubicTo(pt(6.0, 5.0), pt(7.0, 6.0), pt(7.0, 7.0)),
+            ]
+        );
+        // A range that ends inside of a curve stops before it.
+        assert_eq!(path.segments_range(..3).count(), 2);
+    }
+
+    #[test]
+    fn test_figures_match_figure_range() {
+        let path = test_path();
+        let figures: Vec<Vec<_>> = path.figures().map(Iterator::collect).collect();
+        assert_eq!(figures.len(), 2);
+        let mut start = 0;
+        for figure in &figures {
+            let range = path.figure_range(start).unwrap();
+            assert_eq!(range.start, start);
+            start = range.end;
+            assert_eq!(*figure, path.segments_range(range).collect::<Vec<_>>());
+        }
+        assert_eq!(start, path.len());
+        assert_eq!(figures[1][0], PathSegment::MoveTo(pt(5.0, 5.0)));
+        // The figure containing a vertex.
+        assert_eq!(path.figure_range(2), Some(0..5));
+
+        // A close also ends a figure that isn't followed by a move.
+        let mut path = Path::new();
+        path.move_to(0.0, 0.0);
+        path.line_to(1.0, 0.0);
+        path.close();
+        path.line_to(0.0, 1.0);
+        assert_eq!(path.figures().count(), 2);
+        assert_eq!(path.figure_range(3), Some(3..4));
+    }
+
+    #[test]
+    fn test_segments_with_matrix() {
+        let path = test_path();
+        let m = Matrix2D::scaling(2.0, 3.0);
+        let mut transformed = path.clone();
+        transformed.transform(&m);
+        assert!(path.segments().with_matrix(&m).eq(transformed.segments()));
+        assert!(path
+            .figures()
+            .with_matrix(&m)
+            .flatten()
+            .eq(transformed.segments()));
+    }
+}
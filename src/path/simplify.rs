@@ -0,0 +1,183 @@
+//! Vertex reduction of paths.
+use crate::geometry::PointD;
+use crate::path::flatten::flatten_path;
+use crate::path::geom::distance_to_segment;
+use crate::path::Path;
+
+/// Marks the points between the first and the last one of `points` that
+/// have to be kept.
+fn rdp(points: &[PointD], keep: &mut [bool], tolerance: f64) {
+    let n = points.len();
+    if n < 3 {
+        return;
+    }
+    let (first, last) = (points[0], points[n - 1]);
+    let (index, max) = points[1..n - 1]
+        .iter()
+        .map(|&p| distance_to_segment(p, first, last))
+        .enumerate()
+        .fold(
+            (0, -1.0),
+            |acc, (i, d)| if d > acc.1 { (i + 1, d) } else { acc },
+        );
+    if max > tolerance {
+        keep[index] = true;
+        rdp(&points[..=index], &mut keep[..=index], tolerance);
+        rdp(&points[index..], &mut keep[index..], tolerance);
+    }
+}
+
+fn simplify_polyline(points: &[PointD], closed: bool, tolerance: f64) -> Vec<PointD> {
+    let ring;
+    let points = if closed {
+        ring = [points, &points[..1]].concat();
+        &ring[..]
+    } else {
+        points
+    };
+    let n = points.len();
+    let mut keep = vec![false; n];
+    keep[0] = true;
+    keep[n - 1] = true;
+    if closed {
+        // A ring is split at the point farthest from its start, both halves
+        // are simplified like open polylines.
+        let start = points[0];
+        let dist = |p: PointD| (p.x - start.x).powi(2) + (p.y - start.y).powi(2);
+        let far = (1..n - 1).max_by(|&i, &j| dist(points[i]).total_cmp(&dist(points[j])));
+        if let Some(far) = far {
+            keep[far] = true;
+            rdp(&points[..=far], &mut keep[..=far], tolerance);
+            rdp(&points[far..], &mut keep[far..], tolerance);
+        }
+    } else {
+        rdp(points, &mut keep, tolerance);
+    }
+    let mut simplified: Vec<PointD> = points
+        .iter()
+        .zip(keep)
+        .filter(|&(_, keep)| keep)
+        .map(|(&p, _)| p)
+        .collect();
+    if closed {
+        simplified.pop();
+    }
+    simplified
+}
+
+pub(in crate) fn simplify(path: &Path, flatten_tolerance: f64, tolerance: f64) -> Path {
+    let mut result = Path::new();
+    for polyline in flatten_path(path, flatten_tolerance) {
+        let points = simplify_polyline(&polyline.points, polyline.closed, tolerance);
+        if points.len() < 2 || (polyline.closed && points.len() < 3) {
+            continue;
+        }
+        result.move_to_point(&points[0]);
+        result.poly_to(&points[1..]);
+        if polyline.closed {
+            result.close();
+        }
+    }
+    result
+}
+
+#[cfg(test)]
+mod test_simplify {
+    use crate::geometry::PointD;
+    use crate::path::{ApproximationOptions, Path, PathSegment};
+
+    fn pt(x: f64, y: f64) -> PointD {
+        PointD { x, y }
+    }
+
+    #[test]
+    fn test_simplify_open() {
+        let mut path = Path::new();
+        path.move_to(0.0, 0.0);
+        path.line_to(1.0, 0.05);
+        path.line_to(2.0, -0.05);
+        path.line_to(3.0, 0.0);
+        path.line_to(3.0, 3.0);
+        let segments: Vec<_> = path.simplify(0.1).segments().collect();
+        assert_eq!(
+            segments,
+            [
+                PathSegment::MoveTo(pt(0.0, 0.0)),
+                PathSegment::LineTo(pt(3.0, 0.0)),
+                PathSegment::LineTo(pt(3.0, 3.0)),
+            ]
+        );
+        // Nothing lies within a smaller tolerance.
+        assert_eq!(path.simplify(0.01).len(), path.len());
+    }
+
+    #[test]
+    fn test_simplify_closed() {
+        // A square with extra points on its edges, starting in the middle of
+        // one of them.
+        let mut path = Path::new();
+        path.move_to(0.0, 0.5);
+        path.line_to(0.0, 0.0);
+        path.line_to(0.5, 0.01);
+        path.line_to(1.0, 0.0);
+        path.line_to(1.0, 1.0);
+        path.line_to(0.0, 1.0);
+        path.close();
+        // A figure that collapses into a point.
+        path.move_to(5.0, 5.0);
+        path.line_to(5.0, 5.01);
+        path.close();
+        let segments: Vec<_> = path.simplify(0.1).segments().collect();
+        assert_eq!(
+            segments,
+            [
+                PathSegment::MoveTo(pt(0.0, 0.5)),
+                PathSegment::LineTo(pt(0.0, 0.0)),
+                PathSegment::LineTo(pt(1.0, 0.0)),
+                PathSegment::LineTo(pt(1.0, 1.0)),
+                PathSegment::LineTo(pt(0.0, 1.0)),
+                PathSegment::Close,
+            ]
+        );
+    }
+
+    #[test]
+    fn test_simplify_non_finite() {
+        let mut path = Path::new();
+        path.move_to(0.0, 0.0);
+        path.line_to(f64::NAN, 1.0);
+        path.line_to(1.0, 1.0);
+        path.line_to(1.0, 0.0);
+        path.close();
+        path.move_to(2.0, 0.0);
+        path.line_to(3.0, 0.0);
+        path.line_to(4.0, 0.0);
+        let segments: Vec<_> = path.simplify(0.1).segments().collect();
+        assert_eq!(
+            segments[segments.len() - 2..],
+            [
+                PathSegment::MoveTo(pt(2.0, 0.0)),
+                PathSegment::LineTo(pt(4.0, 0.0)),
+            ]
+        );
+    }
+
+    #[test]
+    fn test_simplify_curves() {
+        let mut path = Path::new();
+        path.move_to(0.0, 0.0);
+        path.quad_to(50.0, 50.0, 100.0, 0.0);
+        let approx = ApproximationOptions {
+            flatten_tolerance: 0.01,
+            simplyify_tolerance: 1.0,
+            ..Default::default()
+        };
+        let flattened = path.flatten(&approx)[0].points.len();
+        let simplified = path.simplify_with_options(&approx);
+        assert!(simplified.len() < flattened);
+        assert!(simplified
+            .segments()
+            .all(|segment| matches!(segment, PathSegment::MoveTo(_) | PathSegment::LineTo(_))));
+        assert_eq!(simplified.last_vertex(), Some(pt(100.0, 0.0)));
+    }
+}
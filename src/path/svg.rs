@@ -0,0 +1,360 @@
+//! SVG path data parsing and serialization.
+//!
+//! Implements the path data grammar of
+//! [SVG 1.1](https://www.w3.org/TR/SVG11/paths.html#PathDataBNF).
+use std::fmt::Write;
+
+use crate::error::{SvgPathError, SvgPathErrorKind};
+use crate::geometry::PointD;
+use crate::path::{Path, PathCommand};
+
+struct Parser<'a> {
+    data: &'a str,
+    pos: usize,
+}
+
+impl<'a> Parser<'a> {
+    #[inline]
+    fn peek(&self) -> Option<u8> {
+        self.data.as_bytes().get(self.pos).copied()
+    }
+
+    #[inline]
+    fn peek_is(&self, f: impl FnOnce(u8) -> bool) -> bool {
+        self.peek().is_some_and(f)
+    }
+
+    fn error_at(&self, position: usize, kind: SvgPathErrorKind) -> SvgPathError {
+        SvgPathError { position, kind }
+    }
+
+    /// Creates the error for whatever is found at the current position.
+    fn unexpected(&self) -> SvgPathError {
+        let kind = match self.data[self.pos..].chars().next() {
+            Some(c) => SvgPathErrorKind::UnexpectedCharacter(c),
+            None => SvgPathErrorKind::UnexpectedEnd,
+        };
+        self.error_at(self.pos, kind)
+    }
+
+    fn skip_whitespace(&mut self) {
+        while self.peek_is(|b| matches!(b, b' ' | b'\t' | b'\n' | b'\x0C' | b'\r')) {
+            self.pos += 1;
+        }
+    }
+
+    /// Skips whitespace with at most one comma in it.
+    fn skip_comma_whitespace(&mut self) {
+        self.skip_whitespace();
+        if self.peek() == Some(b',') {
+            self.pos += 1;
+            self.skip_whitespace();
+        }
+    }
+
+    fn at_number(&self) -> bool {
+        self.peek_is(|b| b.is_ascii_digit() || matches!(b, b'.' | b'+' | b'-'))
+    }
+
+    fn skip_digits(&mut self) -> usize {
+        let start = self.pos;
+        while self.peek_is(|b| b.is_ascii_digit()) {
+            self.pos += 1;
+        }
+        self.pos - start
+    }
+
+    fn number(&mut self) -> Result<f64, SvgPathError> {
+        let start = self.pos;
+        if self.peek_is(|b| b == b'+' || b == b'-') {
+            self.pos += 1;
+        }
+        let mut digits = self.skip_digits();
+        if self.peek() == Some(b'.') {
+            self.pos += 1;
+            digits += self.skip_digits();
+        }
+        if digits == 0 {
+            self.pos = start;
+            return Err(self.unexpected());
+        }
+        if self.peek_is(|b| b == b'e' || b == b'E') {
+            self.pos += 1;
+            if self.peek_is(|b| b == b'+' || b == b'-') {
+                self.pos += 1;
+            }
+            if self.skip_digits() == 0 {
+                return Err(self.error_at(start, SvgPathErrorKind::InvalidNumber));
+            }
+        }
+        match self.data[start..self.pos].parse::<f64>() {
+            Ok(val) if val.is_finite() => Ok(val),
+            _ => Err(self.error_at(start, SvgPathErrorKind::InvalidNumber)),
+        }
+    }
+
+    fn flag(&mut self) -> Result<bool, SvgPathError> {
+        let flag = match self.peek() {
+            Some(b'0') => false,
+            Some(b'1') => true,
+            _ => return Err(self.unexpected()),
+        };
+        self.pos += 1;
+        Ok(flag)
+    }
+
+    /// Parses a coordinate pair, offset by `base` for relative commands.
+    fn point(&mut self, base: PointD) -> Result<PointD, SvgPathError> {
+        let x = self.number()?;
+        self.skip_comma_whitespace();
+        let y = self.number()?;
+        Ok(PointD {
+            x: base.x + x,
+            y: base.y + y,
+        })
+    }
+
+    /// Parses a coordinate pair preceded by a separator.
+    fn next_point(&mut self, base: PointD) -> Result<PointD, SvgPathError> {
+        self.skip_comma_whitespace();
+        self.point(base)
+    }
+}
+
+pub(super) fn parse_path_data(path: &mut Path, data: &str) -> Result<(), SvgPathError> {
+    let mut p = Parser { data, pos: 0 };
+    let mut current = PointD::default();
+    let mut start = PointD::default();
+    let mut prev_cmd = None;
+
+    p.skip_whitespace();
+    while let Some(c) = p.peek() {
+        let cmd_pos = p.pos;
+        let cmd = if c.is_ascii_alphabetic() {
+            p.pos += 1;
+            p.skip_whitespace();
+            c
+        } else {
+            // Additional argument groups repeat the previous command, with a
+            // moveto turning into a lineto.
+            match prev_cmd {
+                Some(b'M') if p.at_number() => b'L',
+                Some(b'm') if p.at_number() => b'l',
+                Some(prev) if prev != b'Z' && prev != b'z' && p.at_number() => prev,
+                _ => return Err(p.unexpected()),
+            }
+        };
+        if prev_cmd.is_none() && cmd != b'M' && cmd != b'm' {
+            return Err(p.error_at(cmd_pos, SvgPathErrorKind::MissingMoveTo));
+        }
+        // A figure that follows a closepath without a moveto starts at the
+        // initial point of the closed one.
+        if (prev_cmd == Some(b'Z') || prev_cmd == Some(b'z')) && cmd != b'M' && cmd != b'm' {
+            path.move_to_point(&start);
+        }
+
+        let base = if cmd.is_ascii_lowercase() {
+            current
+        } else {
+            PointD::default()
+        };
+        match cmd.to_ascii_uppercase() {
+            b'M' => {
+                current = p.point(base)?;
+                start = current;
+                path.move_to_point(&current);
+            },
+            b'L' => {
+                current = p.point(base)?;
+                path.line_to_point(&current);
+            },
+            b'H' => {
+                current.x = base.x + p.number()?;
+                path.line_to_point(&current);
+            },
+            b'V' => {
+                current.y = base.y + p.number()?;
+                path.line_to_point(&current);
+            },
+            b'C' => {
+                let p1 = p.point(base)?;
+                let p2 = p.next_point(base)?;
+                current = p.next_point(base)?;
+                path.cubic_to_points(&p1, &p2, &current);
+            },
+            b'S' => {
+                let p2 = p.point(base)?;
+                current = p.next_point(base)?;
+                path.smooth_cubic_to_points(&p2, &current);
+            },
+            b'Q' => {
+                let p1 = p.point(base)?;
+                current = p.next_point(base)?;
+                path.quad_to(p1.x, p1.y, current.x, current.y);
+            },
+            b'T' => {
+                current = p.point(base)?;
+                path.smooth_quad_to_point(&current);
+            },
+            b'A' => {
+                let rx = p.number()?.abs();
+                p.skip_comma_whitespace();
+                let ry = p.number()?.abs();
+                p.skip_comma_whitespace();
+                let x_axis_rotation = p.number()?.to_radians();
+                p.skip_comma_whitespace();
+                let large_arc_flag = p.flag()?;
+                p.skip_comma_whitespace();
+                let sweep_flag = p.flag()?;
+                current = p.next_point(base)?;
+                path.elliptic_arc_to(
+                    rx,
+                    ry,
+                    x_axis_rotation,
+                    large_arc_flag,
+                    sweep_flag,
+                    current.x,
+                    current.y,
+                );
+            },
+            b'Z' => {
+                current = start;
+                path.close();
+            },
+            _ => {
+                return Err(p.error_at(
+                    cmd_pos,
+                    SvgPathErrorKind::UnexpectedCharacter(char::from(cmd)),
+                ))
+            },
+        }
+        prev_cmd = Some(cmd);
+
+        p.skip_whitespace();
+        // A comma may only separate argument groups of the same command.
+        if p.peek() == Some(b',') && cmd != b'Z' && cmd != b'z' {
+            p.pos += 1;
+            p.skip_whitespace();
+            if !p.at_number() {
+                return Err(p.unexpected());
+            }
+        }
+    }
+    Ok(())
+}
+
+pub(super) fn write_path_data(path: &Path) -> String {
+    let commands = path.command_data();
+    let vertices = path.vertex_data();
+    let mut out = String::with_capacity(commands.len() * 16);
+
+    let mut i = 0;
+    while i < commands.len() {
+        let (cmd, n) = match PathCommand::from(u32::from(commands[i])) {
+            PathCommand::Move => ('M', 1),
+            PathCommand::On => ('L', 1),
+            PathCommand::Quad => ('Q', 2),
+            PathCommand::Cubic => ('C', 3),
+            PathCommand::Close => ('Z', 0),
+        };
+        let points = match vertices.get(i..i + n) {
+            Some(points) => points,
+            None => break,
+        };
+        if !out.is_empty() {
+            out.push(' ');
+        }
+        out.push(cmd);
+        for (idx, p) in points.iter().enumerate() {
+            let sep = if idx == 0 { "" } else { " " };
+            // Display for f64 prints the shortest representation that parses
+            // back to the same value.
+            let _ = write!(out, "{}{} {}", sep, p.x, p.y);
+        }
+        i += n.max(1);
+    }
+    out
+}
+
+#[cfg(test)]
+mod test_svg {
+    use crate::error::{Error, SvgPathError, SvgPathErrorKind};
+    use crate::path::Path;
+
+    fn parse_err(data: &str) -> SvgPathError {
+        match Path::from_svg_path_data(data) {
+            Err(Error::SvgPath(err)) => err,
+            res => panic!("expected an svg path error, got {:?}", res),
+        }
+    }
+
+    #[test]
+    fn test_parse_relative_and_repeats() {
+        let path = Path::from_svg_path_data("m10 20 5 5 l5-5,5 5 h10 v-10 H0 z L0 10").unwrap();
+        let mut expected = Path::new();
+        expected.move_to(10.0, 20.0);
+        expected.line_to(15.0, 25.0);
+        expected.line_to(20.0, 20.0);
+        expected.line_to(25.0, 25.0);
+        expected.line_to(35.0, 25.0);
+        expected.line_to(35.0, 15.0);
+        expected.line_to(0.0, 15.0);
+        expected.close();
+        expected.move_to(10.0, 20.0);
+        expected.line_to(0.0, 10.0);
+        assert_eq!(path, expected);
+    }
+
+    #[test]
+    fn test_parse_curves() {
+        let path = Path::from_svg_path_data(
+            "M0 0C10 0 20 10 20 20s20 20 20 0Q50 0 60 10t10 10M1e1.5a5 5 30 1 0 10.5.5",
+        )
+        .unwrap();
+        let mut expected = Path::new();
+        expected.move_to(0.0, 0.0);
+        expected.cubic_to(10.0, 0.0, 20.0, 10.0, 20.0, 20.0);
+        expected.smooth_cubic_to(40.0, 40.0, 40.0, 20.0);
+        expected.quad_to(50.0, 0.0, 60.0, 10.0);
+        expected.smooth_quad_to(70.0, 20.0);
+        expected.move_to(10.0, 0.5);
+        expected.elliptic_arc_to(5.0, 5.0, 30f64.to_radians(), true, false, 20.5, 1.0);
+        assert_eq!(path, expected);
+    }
+
+    #[test]
+    fn test_parse_errors() {
+        use SvgPathErrorKind::*;
+        let err = |data| {
+            let err = parse_err(data);
+            (err.position, err.kind)
+        };
+        assert_eq!(err("L10 20"), (0, MissingMoveTo));
+        assert_eq!(err("M10 20 L30"), (10, UnexpectedEnd));
+        assert_eq!(err("M10 20 X1 2"), (7, UnexpectedCharacter('X')));
+        assert_eq!(err("M10 20,L1 1"), (7, UnexpectedCharacter('L')));
+        assert_eq!(err("M1e 2"), (1, InvalidNumber));
+        assert_eq!(err("M0 0 Z 1 1"), (7, UnexpectedCharacter('1')));
+        assert_eq!(err("M0 0 A1 1 0 2 0 5 5"), (12, UnexpectedCharacter('2')));
+        assert!(Path::from_svg_path_data("  ").unwrap().is_empty());
+    }
+
+    #[test]
+    fn test_roundtrip() {
+        let mut path = Path::new();
+        path.move_to(0.5, -1.0);
+        path.line_to(1e-7, 3.0);
+        path.quad_to(1.0, 2.0, 3.0, 4.0);
+        path.cubic_to(1.0 / 3.0, 2.0, 3.0, 4.0, 5.0, 6.0);
+        path.close();
+        path.move_to(10.0, 10.0);
+        path.line_to(20.0, 10.0);
+
+        let data = path.to_svg_path_data();
+        assert_eq!(
+            data,
+            "M0.5 -1 L0.0000001 3 Q1 2 3 4 C0.3333333333333333 2 3 4 5 6 Z M10 10 L20 10"
+        );
+        assert_eq!(Path::from_svg_path_data(&data).unwrap(), path);
+    }
+}
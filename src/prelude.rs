@@ -1,6 +1,7 @@
 pub use crate::{
     array::{Array, ArrayType},
     codec::{ImageCodec, ImageCodecFeatures},
+    color::{Color, Rgba, Rgba32, Rgba64},
     context::{ClipMode, CompOp, Context, ContextCreateInfo},
     image::{Image, ImageFormat},
     matrix::MatrixTransform,
@@ -0,0 +1,457 @@
+//! `Serialize` and `Deserialize` implementations for the refcounted blend2d
+//! types.
+use serde::de::{self, Deserialize, Deserializer, SeqAccess, Visitor};
+use serde::ser::{self, Serialize, SerializeStruct, Serializer};
+
+use std::{fmt, slice};
+
+use crate::array::Array;
+use crate::font::{Font, FontData, FontFace};
+use crate::font_defs::{FontFeature, FontVariation};
+use crate::geometry::{BoxI, RectI};
+use crate::gradient::{Gradient, GradientStop, GradientType};
+use crate::image::{Image, ImageFormat};
+use crate::matrix::Matrix2D;
+use crate::path::{Path, PathSegment};
+use crate::pattern::Pattern;
+use crate::region::Region;
+use crate::variant::WrappedBlCore;
+use crate::{ExtendMode, Tag};
+
+struct Bytes<'a>(&'a [u8]);
+
+impl Serialize for Bytes<'_> {
+    fn serialize<S: Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        serializer.serialize_bytes(self.0)
+    }
+}
+
+/// Owned bytes, accepting both byte arrays and sequences of bytes since
+/// self-describing formats like JSON write bytes as the latter.
+struct ByteBuf(Vec<u8>);
+
+impl<'de> Deserialize<'de> for ByteBuf {
+    fn deserialize<D: Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+        struct ByteBufVisitor;
+
+        impl<'de> Visitor<'de> for ByteBufVisitor {
+            type Value = ByteBuf;
+
+            fn expecting(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+                f.write_str("bytes")
+            }
+
+            fn visit_bytes<E: de::Error>(self, v: &[u8]) -> Result<ByteBuf, E> {
+                Ok(ByteBuf(v.to_vec()))
+            }
+
+            fn visit_byte_buf<E: de::Error>(self, v: Vec<u8>) -> Result<ByteBuf, E> {
+                Ok(ByteBuf(v))
+            }
+
+            fn visit_seq<A: SeqAccess<'de>>(self, mut seq: A) -> Result<ByteBuf, A::Error> {
+                let mut bytes = Vec::with_capacity(seq.size_hint().unwrap_or(0).min(4096));
+                while let Some(b) = seq.next_element()? {
+                    bytes.push(b);
+                }
+                Ok(ByteBuf(bytes))
+            }
+        }
+
+        deserializer.deserialize_byte_buf(ByteBufVisitor)
+    }
+}
+
+/// Serializes the segments of the path.
+impl Serialize for Path {
+    fn serialize<S: Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        let mut s = serializer.serialize_struct("Path", 1)?;
+        s.serialize_field("segments", &self.segments().collect::<Vec<_>>())?;
+        s.end()
+    }
+}
+
+impl<'de> Deserialize<'de> for Path {
+    fn deserialize<D: Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+        #[derive(serde::Deserialize)]
+        #[serde(rename = "Path")]
+        struct PathData {
+            segments: Vec<PathSegment>,
+        }
+
+        let PathData { segments } = PathData::deserialize(deserializer)?;
+        let mut path = Path::new();
+        for segment in segments {
+            match segment {
+                PathSegment::MoveTo(p) => path.move_to_point(&p),
+                PathSegment::LineTo(p) => path.line_to_point(&p),
+                PathSegment::QuadTo(p1, p2) => path.quad_to(p1.x, p1.y, p2.x, p2.y),
+                PathSegment::CubicTo(p1, p2, p3) => path.cubic_to_points(&p1, &p2, &p3),
+                PathSegment::Close => path.close(),
+            }
+        }
+        Ok(path)
+    }
+}
+
+/// Serializes the boxes that make up the region.
+impl Serialize for Region {
+    fn serialize<S: Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        let mut s = serializer.serialize_struct("Region", 1)?;
+        s.serialize_field("boxes", self.data())?;
+        s.end()
+    }
+}
+
+impl<'de> Deserialize<'de> for Region {
+    fn deserialize<D: Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+        #[derive(serde::Deserialize)]
+        #[serde(rename = "Region")]
+        struct RegionData {
+            boxes: Vec<BoxI>,
+        }
+
+        let RegionData { boxes } = RegionData::deserialize(deserializer)?;
+        Ok(Region::from(&boxes[..]))
+    }
+}
+
+/// Serializes the size, format and pixel data of the image. Every row of the
+/// pixel data is `stride` bytes long.
+impl Serialize for Image {
+    fn serialize<S: Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        let (stride, pixels) = unsafe {
+            let mut data = std::mem::zeroed::<ffi::BLImageData>();
+            ffi::blImageGetData(self.core(), &mut data);
+            let len = (data.size.h as isize * data.stride) as usize;
+            (
+                data.stride,
+                slice::from_raw_parts(data.pixelData as *const u8, len),
+            )
+        };
+        let mut s = serializer.serialize_struct("Image", 5)?;
+        s.serialize_field("width", &self.width())?;
+        s.serialize_field("height", &self.height())?;
+        s.serialize_field("format", &self.format())?;
+        s.serialize_field("stride", &stride)?;
+        s.serialize_field("data", &Bytes(pixels))?;
+        s.end()
+    }
+}
+
+impl<'de> Deserialize<'de> for Image {
+    fn deserialize<D: Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+        #[derive(serde::Deserialize)]
+        #[serde(rename = "Image")]
+        struct ImageData {
+            width: i32,
+            height: i32,
+            format: ImageFormat,
+            stride: isize,
+            data: ByteBuf,
+        }
+
+        let ImageData {
+            width,
+            height,
+            format,
+            stride,
+            data,
+        } = ImageData::deserialize(deserializer)?;
+        let mut image = Image::new(width, height, format).map_err(de::Error::custom)?;
+        let row_len = width.max(0) as usize * format.bytes_per_pixel();
+        let (stride, rows) = (stride.max(0) as usize, height.max(0) as usize);
+        let len = stride.checked_mul(rows).filter(|&len| len <= data.0.len());
+        if stride < row_len || len.is_none() {
+            return Err(de::Error::invalid_length(
+                data.0.len(),
+                &"`height` rows of `stride` bytes",
+            ));
+        }
+        if row_len > 0 {
+            let src = data.0.chunks(stride);
+            for (dst, src) in image.data_mut().rows_mut().zip(src) {
+                dst.copy_from_slice(&src[..row_len]);
+            }
+        }
+        Ok(image)
+    }
+}
+
+/// Serializes the extend mode, values, stops and matrix of the gradient.
+impl<T: GradientType> Serialize for Gradient<T>
+where
+    T::ValuesType: Serialize,
+{
+    fn serialize<S: Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        let mut s = serializer.serialize_struct("Gradient", 4)?;
+        s.serialize_field("extend_mode", &self.extend_mode())?;
+        s.serialize_field("values", self.values())?;
+        s.serialize_field("stops", self.stops())?;
+        s.serialize_field("matrix", self.matrix())?;
+        s.end()
+    }
+}
+
+impl<'de, T: GradientType> Deserialize<'de> for Gradient<T>
+where
+    T::ValuesType: Deserialize<'de>,
+{
+    fn deserialize<D: Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+        #[derive(serde::Deserialize)]
+        #[serde(rename = "Gradient")]
+        struct GradientData<V> {
+            extend_mode: ExtendMode,
+            values: V,
+            stops: Vec<GradientStop>,
+            matrix: Matrix2D,
+        }
+
+        let GradientData {
+            extend_mode,
+            values,
+            stops,
+            matrix,
+        } = GradientData::<T::ValuesType>::deserialize(deserializer)?;
+        Ok(Gradient::new(&values, extend_mode, stops, &matrix))
+    }
+}
+
+/// Serializes the image, area, extend mode and matrix of the pattern.
+impl Serialize for Pattern {
+    fn serialize<S: Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        let mut s = serializer.serialize_struct("Pattern", 4)?;
+        s.serialize_field("image", self.image())?;
+        s.serialize_field("area", self.area())?;
+        s.serialize_field("extend_mode", &self.extend_mode())?;
+        s.serialize_field("matrix", self.matrix())?;
+        s.end()
+    }
+}
+
+impl<'de> Deserialize<'de> for Pattern {
+    fn deserialize<D: Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+        #[derive(serde::Deserialize)]
+        #[serde(rename = "Pattern")]
+        struct PatternData {
+            image: Image,
+            area: RectI,
+            extend_mode: ExtendMode,
+            matrix: Matrix2D,
+        }
+
+        let PatternData {
+            image,
+            area,
+            extend_mode,
+            matrix,
+        } = PatternData::deserialize(deserializer)?;
+        Ok(Pattern::new(&image, &area, extend_mode, &matrix))
+    }
+}
+
+/// Serializes the font data, size, features and variations of the font.
+///
+/// The font data is an sfnt file made of the tables of the font's face, so
+/// a face of a font collection is serialized on its own.
+impl Serialize for Font {
+    fn serialize<S: Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        let data = face_to_sfnt(self.face()).map_err(ser::Error::custom)?;
+        let mut s = serializer.serialize_struct("Font", 4)?;
+        s.serialize_field("data", &Bytes(&data))?;
+        s.serialize_field("size", &self.size())?;
+        s.serialize_field("features", &self.features()[..])?;
+        s.serialize_field("variations", &self.variations()[..])?;
+        s.end()
+    }
+}
+
+impl<'de> Deserialize<'de> for Font {
+    fn deserialize<D: Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+        #[derive(serde::Deserialize)]
+        #[serde(rename = "Font")]
+        struct FontSettings {
+            data: ByteBuf,
+            size: f32,
+            features: Vec<FontFeature>,
+            variations: Vec<FontVariation>,
+        }
+
+        let FontSettings {
+            data,
+            size,
+            features,
+            variations,
+        } = FontSettings::deserialize(deserializer)?;
+        let data = FontData::from_data_array(&Array::from(data.0)).map_err(de::Error::custom)?;
+        let face = FontFace::from_data(&data, 0).map_err(de::Error::custom)?;
+        let mut font = face.create_font(size).map_err(de::Error::custom)?;
+        for feature in features {
+            font.set_feature(feature.tag, feature.value)
+                .map_err(de::Error::custom)?;
+        }
+        for variation in variations {
+            font.set_variation(variation.tag, variation.value)
+                .map_err(de::Error::custom)?;
+        }
+        Ok(font)
+    }
+}
+
+/// Writes the tables of the face into a new sfnt file.
+fn face_to_sfnt(face: &FontFace) -> crate::error::Result<Vec<u8>> {
+    const CFF: Tag = Tag::new(b"CFF ");
+
+    let data = face.font_data();
+    let index = face.face_index();
+    let mut tags = data.list_tags(index)?.to_vec();
+    tags.sort_by_key(|&tag| u32::from(tag));
+    let tables: Vec<(Tag, &[u8])> = tags
+        .iter()
+        .map(|&tag| (tag, data.query_table(index, tag).0.data))
+        .collect();
+
+    let num_tables = tables.len() as u16;
+    let entry_selector = 15 - num_tables.max(1).leading_zeros() as u16;
+    let search_range = 16 << entry_selector;
+    let range_shift = (num_tables * 16).saturating_sub(search_range);
+    let version = if tags.contains(&CFF) {
+        *b"OTTO"
+    } else {
+        0x0001_0000u32.to_be_bytes()
+    };
+
+    let mut sfnt = version.to_vec();
+    for v in &[num_tables, search_range, entry_selector, range_shift] {
+        sfnt.extend_from_slice(&v.to_be_bytes());
+    }
+    let mut offset = 12 + 16 * tables.len();
+    for &(tag, table) in &tables {
+        let checksum = table.chunks(4).fold(0u32, |sum, chunk| {
+            let mut word = [0; 4];
+            word[..chunk.len()].copy_from_slice(chunk);
+            sum.wrapping_add(u32::from_be_bytes(word))
+        });
+        sfnt.extend_from_slice(&tag.to_bytes());
+        sfnt.extend_from_slice(&checksum.to_be_bytes());
+        sfnt.extend_from_slice(&(offset as u32).to_be_bytes());
+        sfnt.extend_from_slice(&(table.len() as u32).to_be_bytes());
+        offset += (table.len() + 3) & !3;
+    }
+    for &(_, table) in &tables {
+        sfnt.extend_from_slice(table);
+        sfnt.resize((sfnt.len() + 3) & !3, 0);
+    }
+    Ok(sfnt)
+}
+
+#[cfg(test)]
+mod test_serde {
+    use crate::context::Context;
+    use crate::display_list::DisplayList;
+    use crate::font::{Font, FontFace};
+    use crate::geometry::{BoxI, PointD, PointI, RectI};
+    use crate::gradient::{GradientStop, LinearGradient, LinearGradientValues};
+    use crate::image::{Image, ImageFormat};
+    use crate::matrix::Matrix2D;
+    use crate::path::Path;
+    use crate::pattern::Pattern;
+    use crate::region::Region;
+    use crate::style::Style;
+    use crate::{DataAccessFlags, ExtendMode, Tag};
+
+    fn roundtrip<T>(value: &T) -> T
+    where
+        T: serde::Serialize + serde::de::DeserializeOwned,
+    {
+        serde_json::from_str(&serde_json::to_string(value).unwrap()).unwrap()
+    }
+
+    fn render(list: &DisplayList) -> Image {
+        let mut img = Image::new(64, 64, ImageFormat::PRgb32).unwrap();
+        let mut ctx = Context::new(&mut img).unwrap();
+        list.replay(&mut ctx).unwrap();
+        ctx.end().unwrap();
+        img
+    }
+
+    #[test]
+    fn test_path_roundtrip() {
+        let mut path = Path::new();
+        path.move_to(1.0, 2.0);
+        path.line_to(3.0, 4.0);
+        path.quad_to(5.0, 6.0, 7.0, 8.0);
+        path.cubic_to(9.0, 10.0, 11.0, 12.0, 13.0, 14.0);
+        path.close();
+        assert_eq!(roundtrip(&path), path);
+    }
+
+    #[test]
+    fn test_display_list_roundtrip() {
+        let path = concat!(env!("CARGO_MANIFEST_DIR"), "/assets/NotoSans-Regular.ttf");
+        let face = FontFace::from_path(path, DataAccessFlags::READ).unwrap();
+        let mut font = face.create_font(16.0).unwrap();
+        font.set_feature(Tag::new(b"kern"), 0).unwrap();
+
+        let mut image = Image::new(4, 4, ImageFormat::PRgb32).unwrap();
+        let mut ctx = Context::new(&mut image).unwrap();
+        ctx.clear_to_color(0xFF12_3456).unwrap();
+        ctx.set_fill_style_rgba32(0xFFFF_FFFF);
+        ctx.fill_rect(1.0, 1.0, 2.0, 2.0).unwrap();
+        ctx.end().unwrap();
+
+        let values = LinearGradientValues {
+            x0: 0.0,
+            y0: 0.0,
+            x1: 64.0,
+            y1: 64.0,
+        };
+        let stops = [
+            GradientStop::new(0.0, 0xFF00_00FF),
+            GradientStop::new(1.0, 0xFFFF_0000),
+        ];
+        let gradient = LinearGradient::new(&values, ExtendMode::PadXPadY, stops, None);
+        let pattern = Pattern::new(
+            &image,
+            None,
+            ExtendMode::RepeatXRepeatY,
+            &Matrix2D::scaling(2.0, 2.0),
+        );
+
+        let mut list = DisplayList::new();
+        list.set_fill_style_gradient(&gradient);
+        list.fill_all();
+        list.set_fill_style_pattern(&pattern);
+        list.fill_geometry(&Region::from(
+            &[BoxI {
+                x0: 4,
+                y0: 4,
+                x1: 20,
+                y1: 12,
+            }][..],
+        ));
+        list.blit_image(
+            &PointI { x: 40, y: 4 },
+            &image,
+            &RectI {
+                x: 1,
+                y: 1,
+                w: 3,
+                h: 3,
+            },
+        );
+        list.set_stroke_style(Style::None);
+        list.set_fill_style_rgba32(0xFF00_0000);
+        list.fill_utf8_text(PointD { x: 4.0, y: 48.0 }, &font, "AVATAR");
+
+        let back = roundtrip(&list);
+        assert_eq!(back.len(), list.len());
+        assert_eq!(render(&back), render(&list));
+
+        let back_font: Font = roundtrip(&font);
+        assert_eq!(back_font.face().family_name(), face.family_name());
+        assert_eq!(back_font.face().glyph_count(), face.glyph_count());
+        assert_eq!(back_font.size(), 16.0);
+        assert_eq!(back_font.feature(Tag::new(b"kern")), Some(0));
+    }
+}
@@ -0,0 +1,143 @@
+//! Fill and stroke styles.
+use crate::color::{Color, Rgba, Rgba32, Rgba64};
+use crate::gradient::{ConicalGradient, DynamicGradient, LinearGradient, RadialGradient};
+use crate::pattern::Pattern;
+use crate::StyleType;
+
+/// A style used for filling or stroking with a [`Context`].
+///
+/// [`Context`]: ../context/struct.Context.html
+#[derive(Clone, Debug, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub enum Style {
+    /// No style, nothing will be rendered.
+    ///
+    /// A [`Context`] can't be reset to this style, setting it sets a
+    /// transparent solid color instead.
+    None,
+    /// A solid color.
+    Solid(Rgba64),
+    /// A gradient.
+    Gradient(DynamicGradient),
+    /// A pattern.
+    Pattern(Pattern),
+}
+
+impl Style {
+    /// Creates a solid style from a color.
+    #[inline]
+    pub fn solid<C: Into<Color>>(color: C) -> Self {
+        Style::Solid(color.into().into())
+    }
+
+    /// The [`StyleType`] of this style.
+    #[inline]
+    pub fn style_type(&self) -> StyleType {
+        match self {
+            Style::None => StyleType::None,
+            Style::Solid(_) => StyleType::Solid,
+            Style::Gradient(_) => StyleType::Gradient,
+            Style::Pattern(_) => StyleType::Pattern,
+        }
+    }
+}
+
+impl Default for Style {
+    #[inline]
+    fn default() -> Self {
+        Style::None
+    }
+}
+
+impl From<&Style> for Style {
+    #[inline]
+    fn from(style: &Style) -> Self {
+        style.clone()
+    }
+}
+
+macro_rules! impl_from_color {
+    ($($ty:ty),*) => {
+        $(
+            impl From<$ty> for Style {
+                #[inline]
+                fn from(color: $ty) -> Self {
+                    Style::solid(color)
+                }
+            }
+        )*
+    };
+}
+
+impl_from_color!(u32, Color, Rgba32, Rgba64, Rgba);
+
+impl From<DynamicGradient> for Style {
+    #[inline]
+    fn from(gradient: DynamicGradient) -> Self {
+        Style::Gradient(gradient)
+    }
+}
+
+impl From<&DynamicGradient> for Style {
+    #[inline]
+    fn from(gradient: &DynamicGradient) -> Self {
+        Style::Gradient(gradient.clone())
+    }
+}
+
+macro_rules! impl_from_gradient {
+    ($($ty:ident),*) => {
+        $(
+            impl From<$ty> for Style {
+                #[inline]
+                fn from(gradient: $ty) -> Self {
+                    Style::Gradient(gradient.into())
+                }
+            }
+
+            impl From<&$ty> for Style {
+                #[inline]
+                fn from(gradient: &$ty) -> Self {
+                    Style::Gradient(gradient.clone().into())
+                }
+            }
+        )*
+    };
+}
+
+impl_from_gradient!(LinearGradient, RadialGradient, ConicalGradient);
+
+impl From<Pattern> for Style {
+    #[inline]
+    fn from(pattern: Pattern) -> Self {
+        Style::Pattern(pattern)
+    }
+}
+
+impl From<&Pattern> for Style {
+    #[inline]
+    fn from(pattern: &Pattern) -> Self {
+        Style::Pattern(pattern.clone())
+    }
+}
+
+#[cfg(test)]
+mod test_style {
+    use crate::color::{Rgba, Rgba64};
+    use crate::style::Style;
+    use crate::StyleType;
+
+    #[test]
+    fn test_style_type() {
+        assert_eq!(Style::default().style_type(), StyleType::None);
+        assert_eq!(Style::solid(0xFF00_00FF).style_type(), StyleType::Solid);
+        assert_eq!(
+            Style::solid(0xFF00_00FF),
+            Style::from(Rgba64(0xFFFF_0000_0000_FFFF))
+        );
+        assert_eq!(
+            Style::from(Rgba::rgb(0.0, 0.0, 1.0)),
+            Style::solid(Rgba64(0xFFFF_0000_0000_FFFF))
+        );
+    }
+}
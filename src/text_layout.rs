@@ -0,0 +1,725 @@
+//! Multi-line text layout.
+//!
+//! A [`TextLayout`] shapes a text with a [`Font`], breaks it into lines that
+//! fit into a maximum width following the
+//! [Unicode Line Breaking Algorithm](https://www.unicode.org/reports/tr14/)
+//! and positions the lines according to the font metrics. Every line can then
+//! be drawn as a glyph run with
+//! [`Context::fill_glyph_run`](../context/struct.Context.html#method.fill_glyph_run),
+//! or the whole layout at once with
+//! [`Context::fill_text_layout`](../context/struct.Context.html#method.fill_text_layout).
+//!
+//! Lines are laid out left to right, bidirectional text is not reordered. See
+//! the [`bidi`](../bidi/index.html) module for single lines of bidirectional
+//! text.
+use std::ops::Range;
+
+use unicode_linebreak::{linebreaks, BreakOpportunity};
+
+use crate::error::Result;
+use crate::font::Font;
+use crate::font_defs::{GlyphPlacement, GlyphPlacementType, GlyphRun};
+use crate::geometry::{BoxD, PointD};
+use crate::glyph_buffer::{GlyphBuffer, GlyphId};
+
+/// The horizontal alignment of the lines of a [`TextLayout`].
+#[derive(Copy, Clone, Debug, PartialEq, Eq)]
+pub enum TextAlignment {
+    Left,
+    Center,
+    Right,
+    /// Stretches the whitespace of every line but the last one of a
+    /// paragraph to fill the maximum width.
+    Justify,
+}
+
+impl Default for TextAlignment {
+    #[inline]
+    fn default() -> Self {
+        TextAlignment::Left
+    }
+}
+
+/// The options of a [`TextLayout`].
+#[derive(Clone, Debug, PartialEq)]
+pub struct TextLayoutOptions {
+    /// The width lines are broken at, `f64::INFINITY` only breaks lines at
+    /// line terminators.
+    pub max_width: f64,
+    /// The alignment of the lines within `max_width`, or within the widest
+    /// line if there is no maximum width.
+    pub alignment: TextAlignment,
+    /// The factor the line height given by the font metrics is scaled with.
+    pub line_spacing: f64,
+    /// The maximum number of lines. The text is cut off at the end of the
+    /// last line, and `ellipsis` is appended to it.
+    pub max_lines: Option<usize>,
+    /// The text that marks a truncated line.
+    pub ellipsis: String,
+}
+
+impl Default for TextLayoutOptions {
+    fn default() -> Self {
+        TextLayoutOptions {
+            max_width: f64::INFINITY,
+            alignment: TextAlignment::Left,
+            line_spacing: 1.0,
+            max_lines: None,
+            ellipsis: String::from("\u{2026}"),
+        }
+    }
+}
+
+/// A positioned line of a [`TextLayout`].
+#[derive(Clone, Debug)]
+pub struct TextLine {
+    glyphs: Vec<GlyphId>,
+    placements: Vec<GlyphPlacement>,
+    placement_type: GlyphPlacementType,
+    origin: PointD,
+    width: f64,
+    text_range: Range<usize>,
+}
+
+impl TextLine {
+    /// Returns the start of the baseline of this line, relative to the top
+    /// left corner of the layout.
+    #[inline]
+    pub fn origin(&self) -> PointD {
+        self.origin
+    }
+
+    /// Returns the width of this line, not including trailing whitespace.
+    #[inline]
+    pub fn width(&self) -> f64 {
+        self.width
+    }
+
+    /// Returns the byte range of the laid out text this line was created
+    /// from, including its trailing whitespace.
+    #[inline]
+    pub fn text_range(&self) -> Range<usize> {
+        self.text_range.clone()
+    }
+
+    /// Returns the glyphs of this line, to be drawn at its
+    /// [`origin`](#method.origin) with the font of the layout.
+    #[inline]
+    pub fn glyph_run(&self) -> GlyphRun<'_> {
+        GlyphRun::new(&self.glyphs, &self.placements, self.placement_type)
+    }
+}
+
+/// A text broken into positioned lines.
+#[derive(Clone, Debug)]
+pub struct TextLayout {
+    lines: Vec<TextLine>,
+    bounding_box: BoxD,
+    truncated: bool,
+}
+
+impl TextLayout {
+    /// Lays out the text with the font, breaking lines that are wider than
+    /// `max_width`.
+    #[inline]
+    pub fn new(text: &str, font: &Font, max_width: f64) -> Result<Self> {
+        let options = TextLayoutOptions {
+            max_width,
+            ..Default::default()
+        };
+        Self::with_options(text, font, &options)
+    }
+
+    /// Lays out the text with the font and the given options.
+    pub fn with_options(text: &str, font: &Font, options: &TextLayoutOptions) -> Result<Self> {
+        let fm = font.font_metrics();
+        let metrics = LineMetrics {
+            ascent: f64::from(fm.horizontal_ascent),
+            descent: f64::from(fm.horizontal_descent),
+            line_gap: f64::from(fm.line_gap),
+        };
+        let mut buf = GlyphBuffer::new();
+        layout(text, options, &metrics, &mut |text| {
+            ShapedText::shape(font, &mut buf, text)
+        })
+    }
+
+    /// Returns the lines of this layout.
+    #[inline]
+    pub fn lines(&self) -> &[TextLine] {
+        &self.lines
+    }
+
+    /// Returns the box enclosing the lines of this layout, from the ascent of
+    /// the first to the descent of the last line.
+    #[inline]
+    pub fn bounding_box(&self) -> BoxD {
+        self.bounding_box
+    }
+
+    /// Returns true if the text didn't fit into the maximum number of lines.
+    #[inline]
+    pub fn is_truncated(&self) -> bool {
+        self.truncated
+    }
+}
+
+struct LineMetrics {
+    ascent: f64,
+    descent: f64,
+    line_gap: f64,
+}
+
+/// A shaped piece of text.
+struct ShapedText {
+    glyphs: Vec<GlyphId>,
+    placements: Vec<GlyphPlacement>,
+    placement_type: GlyphPlacementType,
+    /// The byte offset of the cluster of every glyph.
+    clusters: Vec<usize>,
+    /// The horizontal advance of every glyph in user units.
+    advances: Vec<f64>,
+    /// Whether every glyph belongs to a whitespace character.
+    whitespace: Vec<bool>,
+    /// The scale from the design units of the placements to user units.
+    scale: f64,
+}
+
+impl ShapedText {
+    fn shape(font: &Font, buf: &mut GlyphBuffer, text: &str) -> Result<Self> {
+        buf.set_utf8_text(text);
+        font.shape(buf)?;
+        let scale = f64::from(font.size()) / f64::from(font.units_per_em().max(1));
+        let placement_type = u32::from(buf.glyph_run().raw.placementType).into();
+        let glyphs: Vec<GlyphId> = buf.glyph_ids().collect();
+        let mut placements = buf.placements().to_vec();
+        placements.resize(glyphs.len(), GlyphPlacement::default());
+        let clusters = buf.clusters().collect();
+        Ok(Self::new(
+            text,
+            glyphs,
+            placements,
+            placement_type,
+            clusters,
+            scale,
+        ))
+    }
+
+    fn new(
+        text: &str,
+        glyphs: Vec<GlyphId>,
+        placements: Vec<GlyphPlacement>,
+        placement_type: GlyphPlacementType,
+        clusters: Vec<usize>,
+        scale: f64,
+    ) -> Self {
+        let advances = placements
+            .iter()
+            .map(|p| f64::from(p.advance.x) * scale)
+            .collect();
+        let whitespace = clusters
+            .iter()
+            .map(|&c| {
+                text.get(c..)
+                    .and_then(|s| s.chars().next())
+                    .is_some_and(char::is_whitespace)
+            })
+            .collect();
+        ShapedText {
+            glyphs,
+            placements,
+            placement_type,
+            clusters,
+            advances,
+            whitespace,
+            scale,
+        }
+    }
+
+    #[inline]
+    fn len(&self) -> usize {
+        self.glyphs.len()
+    }
+
+    /// The index of the first glyph at or after the byte offset.
+    #[inline]
+    fn glyph_at(&self, offset: usize) -> usize {
+        self.clusters.partition_point(|&c| c < offset)
+    }
+
+    /// The end of the cluster starting at the glyph.
+    fn cluster_end(&self, glyph: usize) -> usize {
+        let cluster = self.clusters[glyph];
+        glyph
+            + self.clusters[glyph..]
+                .iter()
+                .take_while(|&&c| c == cluster)
+                .count()
+    }
+
+    /// The end of the glyphs before the trailing whitespace of the range.
+    fn trim_end(&self, glyphs: Range<usize>) -> usize {
+        glyphs.start
+            + self.whitespace[glyphs.clone()]
+                .iter()
+                .rposition(|&ws| !ws)
+                .map_or(0, |i| i + 1)
+    }
+
+    /// The width of the glyphs, not including trailing whitespace.
+    fn width(&self, glyphs: Range<usize>) -> f64 {
+        self.advances[glyphs.start..self.trim_end(glyphs)]
+            .iter()
+            .sum()
+    }
+}
+
+/// Breaks a paragraph into the glyph ranges of its lines.
+fn break_lines(text: &str, shaped: &ShapedText, max_width: f64) -> Vec<Range<usize>> {
+    let mut lines = Vec::new();
+    let mut start = 0;
+    let mut prev = 0;
+    for (offset, _) in linebreaks(text) {
+        let end = shaped.glyph_at(offset);
+        if end <= prev {
+            continue;
+        }
+        if start < prev && shaped.width(start..end) > max_width {
+            lines.push(start..prev);
+            start = prev;
+        }
+        if shaped.width(start..end) > max_width {
+            // Not even the text since the last break opportunity fits, so
+            // break between clusters.
+            let mut glyph = start;
+            while glyph < end {
+                let next = shaped.cluster_end(glyph);
+                if glyph > start && shaped.width(start..next) > max_width {
+                    lines.push(start..glyph);
+                    start = glyph;
+                }
+                glyph = next;
+            }
+        }
+        prev = end;
+    }
+    lines.push(start..shaped.len());
+    lines
+}
+
+/// Splits the text into paragraphs at mandatory line breaks, leaving out the
+/// line terminators.
+fn paragraphs(text: &str) -> Vec<Range<usize>> {
+    if text.is_empty() {
+        return Vec::new();
+    }
+    let mut start = 0;
+    linebreaks(text)
+        .filter(|&(_, opportunity)| opportunity == BreakOpportunity::Mandatory)
+        .map(|(end, _)| {
+            let paragraph = &text[start..end];
+            let trimmed = paragraph.trim_end_matches(|c| {
+                matches!(
+                    c,
+                    '\n' | '\r' | '\u{0B}' | '\u{0C}' | '\u{85}' | '\u{2028}' | '\u{2029}'
+                )
+            });
+            let range = start..start + trimmed.len();
+            start = end;
+            range
+        })
+        .collect()
+}
+
+/// A line before it is positioned.
+struct LineData {
+    glyphs: Vec<GlyphId>,
+    placements: Vec<GlyphPlacement>,
+    whitespace: Vec<bool>,
+    /// The byte offset into the laid out text of the cluster of every glyph.
+    clusters: Vec<usize>,
+    width: f64,
+    text_range: Range<usize>,
+    last_in_paragraph: bool,
+}
+
+fn layout(
+    text: &str,
+    options: &TextLayoutOptions,
+    metrics: &LineMetrics,
+    shape: &mut dyn FnMut(&str) -> Result<ShapedText>,
+) -> Result<TextLayout> {
+    let mut lines = Vec::new();
+    let mut scale = 1.0;
+    let mut placement_type = GlyphPlacementType::None;
+    let mut truncated = false;
+    let max_lines = options.max_lines.unwrap_or(usize::MAX);
+    for paragraph in paragraphs(text) {
+        let offset = paragraph.start;
+        let paragraph_text = &text[paragraph];
+        let shaped = shape(paragraph_text)?;
+        scale = shaped.scale;
+        placement_type = shaped.placement_type;
+        let ranges = break_lines(paragraph_text, &shaped, options.max_width);
+        let count = ranges.len();
+        for (i, glyphs) in ranges.into_iter().enumerate() {
+            if lines.len() == max_lines {
+                truncated = true;
+                break;
+            }
+            let cluster = |glyph: usize| shaped.clusters.get(glyph).copied();
+            let text_start = if i == 0 {
+                0
+            } else {
+                cluster(glyphs.start).unwrap_or(0)
+            };
+            let text_end = cluster(glyphs.end).unwrap_or(paragraph_text.len());
+            lines.push(LineData {
+                glyphs: shaped.glyphs[glyphs.clone()].to_vec(),
+                placements: shaped.placements[glyphs.clone()].to_vec(),
+                whitespace: shaped.whitespace[glyphs.clone()].to_vec(),
+                clusters: shaped.clusters[glyphs.clone()]
+                    .iter()
+                    .map(|c| offset + c)
+                    .collect(),
+                width: shaped.width(glyphs),
+                text_range: offset + text_start..offset + text_end,
+                last_in_paragraph: i + 1 == count,
+            });
+        }
+        if truncated {
+            break;
+        }
+    }
+
+    if truncated {
+        if let Some(line) = lines.last_mut() {
+            let ellipsis = shape(&options.ellipsis)?;
+            truncate(line, &ellipsis, options.max_width);
+        }
+    }
+
+    let width = if options.max_width.is_finite() {
+        options.max_width
+    } else {
+        lines.iter().map(|line| line.width).fold(0.0, f64::max)
+    };
+    let line_height = (metrics.ascent + metrics.descent + metrics.line_gap) * options.line_spacing;
+    let mut bounding_box = BoxD {
+        x0: f64::INFINITY,
+        y0: 0.0,
+        x1: f64::NEG_INFINITY,
+        y1: 0.0,
+    };
+    let lines: Vec<TextLine> = lines
+        .into_iter()
+        .enumerate()
+        .map(|(i, mut line)| {
+            let extra = (width - line.width).max(0.0);
+            let x = match options.alignment {
+                TextAlignment::Left => 0.0,
+                TextAlignment::Center => extra / 2.0,
+                TextAlignment::Right => extra,
+                TextAlignment::Justify => {
+                    if !line.last_in_paragraph && options.max_width.is_finite() {
+                        justify(&mut line, extra, scale);
+                    }
+                    0.0
+                },
+            };
+            let origin = PointD {
+                x,
+                y: metrics.ascent + i as f64 * line_height,
+            };
+            bounding_box.x0 = bounding_box.x0.min(x);
+            bounding_box.x1 = bounding_box.x1.max(x + line.width);
+            bounding_box.y1 = origin.y + metrics.descent;
+            TextLine {
+                glyphs: line.glyphs,
+                placements: line.placements,
+                placement_type,
+                origin,
+                width: line.width,
+                text_range: line.text_range,
+            }
+        })
+        .collect();
+    if lines.is_empty() {
+        bounding_box = BoxD::default();
+    }
+    Ok(TextLayout {
+        lines,
+        bounding_box,
+        truncated,
+    })
+}
+
+/// Distributes the extra width over the whitespace between the words of the
+/// line.
+fn justify(line: &mut LineData, extra: f64, scale: f64) {
+    let end = line
+        .whitespace
+        .iter()
+        .rposition(|&ws| !ws)
+        .map_or(0, |i| i + 1);
+    let gaps = line.whitespace[..end].iter().filter(|&&ws| ws).count();
+    if gaps == 0 || extra <= 0.0 {
+        return;
+    }
+    let per_gap = (extra / gaps as f64 / scale).round() as i32;
+    for (placement, _) in line.placements[..end]
+        .iter_mut()
+        .zip(&line.whitespace)
+        .filter(|(_, &ws)| ws)
+    {
+        placement.advance.x += per_gap;
+    }
+    line.width += f64::from(per_gap) * gaps as f64 * scale;
+}
+
+/// Cuts off whole clusters at the end of the line until the ellipsis fits
+/// into the maximum width, and appends it.
+fn truncate(line: &mut LineData, ellipsis: &ShapedText, max_width: f64) {
+    let scale = ellipsis.scale;
+    let ellipsis_width: f64 = ellipsis.advances.iter().sum();
+    let width = |end: usize| -> f64 {
+        line.placements[..end]
+            .iter()
+            .map(|p| f64::from(p.advance.x) * scale)
+            .sum()
+    };
+    let cluster_start = |end: usize| {
+        let cluster = line.clusters[end - 1];
+        end - line.clusters[..end]
+            .iter()
+            .rev()
+            .take_while(|&&c| c == cluster)
+            .count()
+    };
+    let mut end = line.glyphs.len();
+    while end > 0 && width(end) + ellipsis_width > max_width {
+        end = cluster_start(end);
+    }
+    while end > 0 && line.whitespace[end - 1] {
+        end = cluster_start(end);
+    }
+    let kept_width = width(end);
+    if let Some(&cluster) = line.clusters.get(end) {
+        line.text_range.end = cluster;
+    }
+    line.width = kept_width + ellipsis_width;
+    line.glyphs.truncate(end);
+    line.placements.truncate(end);
+    line.whitespace.truncate(end);
+    line.clusters.truncate(end);
+    line.glyphs.extend_from_slice(&ellipsis.glyphs);
+    line.placements.extend_from_slice(&ellipsis.placements);
+    line.whitespace.extend_from_slice(&ellipsis.whitespace);
+    let text_end = line.text_range.end;
+    line.clusters
+        .extend(ellipsis.clusters.iter().map(|_| text_end));
+    line.last_in_paragraph = true;
+}
+
+#[cfg(test)]
+mod test_text_layout {
+    use super::*;
+    use crate::font::FontFace;
+    use crate::geometry::PointI;
+    use crate::DataAccessFlags;
+
+    const METRICS: LineMetrics = LineMetrics {
+        ascent: 8.0,
+        descent: 2.0,
+        line_gap: 1.0,
+    };
+
+    /// Shapes every character into one glyph with an advance of 10.
+    fn shape_mono(text: &str) -> Result<ShapedText> {
+        let (clusters, glyphs): (Vec<usize>, Vec<GlyphId>) =
+            text.char_indices().map(|(i, c)| (i, c as GlyphId)).unzip();
+        let placement = GlyphPlacement {
+            placement: PointI::default(),
+            advance: PointI { x: 10, y: 0 },
+        };
+        let placements = vec![placement; glyphs.len()];
+        let placement_type = GlyphPlacementType::AdvanceOffset;
+        Ok(ShapedText::new(
+            text,
+            glyphs,
+            placements,
+            placement_type,
+            clusters,
+            1.0,
+        ))
+    }
+
+    fn lay_out(text: &str, options: &TextLayoutOptions) -> TextLayout {
+        layout(text, options, &METRICS, &mut shape_mono).unwrap()
+    }
+
+    fn wrapped(max_width: f64) -> TextLayoutOptions {
+        TextLayoutOptions {
+            max_width,
+            ..Default::default()
+        }
+    }
+
+    fn line_texts<'a>(text: &'a str, layout: &TextLayout) -> Vec<&'a str> {
+        layout
+            .lines()
+            .iter()
+            .map(|line| &text[line.text_range()])
+            .collect()
+    }
+
+    fn line_glyphs(line: &TextLine) -> String {
+        line.glyphs
+            .iter()
+            .filter_map(|&g| char::from_u32(u32::from(g)))
+            .collect()
+    }
+
+    #[test]
+    fn test_break_lines() {
+        let text = "the quick brown fox";
+        let layout = lay_out(text, &wrapped(100.0));
+        assert_eq!(line_texts(text, &layout), ["the quick ", "brown fox"]);
+        let lines = layout.lines();
+        assert_eq!(lines[0].width(), 90.0);
+        assert_eq!(lines[0].origin(), PointD { x: 0.0, y: 8.0 });
+        assert_eq!(lines[1].origin(), PointD { x: 0.0, y: 19.0 });
+        assert_eq!(
+            layout.bounding_box(),
+            BoxD {
+                x0: 0.0,
+                y0: 0.0,
+                x1: 90.0,
+                y1: 21.0
+            }
+        );
+        assert!(!layout.is_truncated());
+
+        // Without a maximum width lines only end at line terminators.
+        let text = "a\n\nb c\r\n";
+        let layout = lay_out(text, &Default::default());
+        assert_eq!(line_texts(text, &layout), ["a", "", "b c"]);
+        assert!(lay_out("", &Default::default()).lines().is_empty());
+    }
+
+    #[test]
+    fn test_break_long_words() {
+        let text = "abcdefghijkl mn";
+        let layout = lay_out(text, &wrapped(50.0));
+        assert_eq!(line_texts(text, &layout), ["abcde", "fghij", "kl mn"]);
+        // At least one cluster is put on every line.
+        let layout = lay_out("ab", &wrapped(5.0));
+        assert_eq!(line_texts("ab", &layout), ["a", "b"]);
+    }
+
+    #[test]
+    fn test_alignment() {
+        let x = |alignment| {
+            let options = TextLayoutOptions {
+                alignment,
+                ..wrapped(100.0)
+            };
+            lay_out("ab cd", &options).lines()[0].origin().x
+        };
+        assert_eq!(x(TextAlignment::Left), 0.0);
+        assert_eq!(x(TextAlignment::Center), 25.0);
+        assert_eq!(x(TextAlignment::Right), 50.0);
+
+        let text = "aa bb cc dd";
+        let options = TextLayoutOptions {
+            alignment: TextAlignment::Justify,
+            ..wrapped(90.0)
+        };
+        let layout = lay_out(text, &options);
+        assert_eq!(line_texts(text, &layout), ["aa bb cc ", "dd"]);
+        let lines = layout.lines();
+        assert_eq!(lines[0].width(), 90.0);
+        let advances: Vec<i32> = lines[0].placements.iter().map(|p| p.advance.x).collect();
+        assert_eq!(advances, [10, 10, 15, 10, 10, 15, 10, 10, 10]);
+        // The last line of a paragraph isn't justified.
+        assert_eq!(lines[1].width(), 20.0);
+    }
+
+    #[test]
+    fn test_line_spacing() {
+        let options = TextLayoutOptions {
+            line_spacing: 2.0,
+            ..Default::default()
+        };
+        let layout = lay_out("a\nb", &options);
+        assert_eq!(layout.lines()[1].origin().y, 30.0);
+        assert_eq!(layout.bounding_box().y1, 32.0);
+    }
+
+    #[test]
+    fn test_ellipsis() {
+        let text = "one two three four";
+        let options = TextLayoutOptions {
+            max_lines: Some(2),
+            ..wrapped(50.0)
+        };
+        let layout = lay_out(text, &options);
+        assert!(layout.is_truncated());
+        assert_eq!(line_texts(text, &layout), ["one ", "two"]);
+        assert_eq!(line_glyphs(&layout.lines()[1]), "two\u{2026}");
+        assert_eq!(layout.lines()[1].width(), 40.0);
+
+        // Clusters are cut off until the ellipsis fits.
+        let text = "abcdefghijkl";
+        let options = TextLayoutOptions {
+            max_lines: Some(1),
+            ellipsis: String::from("..."),
+            ..wrapped(100.0)
+        };
+        let layout = lay_out(text, &options);
+        assert_eq!(line_glyphs(&layout.lines()[0]), "abcdefg...");
+        assert_eq!(line_texts(text, &layout), ["abcdefg"]);
+
+        let options = TextLayoutOptions {
+            max_lines: Some(0),
+            ..Default::default()
+        };
+        assert!(lay_out(text, &options).lines().is_empty());
+        let options = TextLayoutOptions {
+            max_lines: Some(1),
+            ..Default::default()
+        };
+        assert!(!lay_out(text, &options).is_truncated());
+    }
+
+    #[test]
+    fn test_layout_with_font() {
+        let path = concat!(env!("CARGO_MANIFEST_DIR"), "/assets/NotoSans-Regular.ttf");
+        let face = FontFace::from_path(path, DataAccessFlags::READ).unwrap();
+        let font = face.create_font(20.0).unwrap();
+        let text = "Sphinx of black quartz, judge my vow";
+
+        let layout = TextLayout::new(text, &font, f64::INFINITY).unwrap();
+        assert_eq!(line_texts(text, &layout), [text]);
+        let mut buf = GlyphBuffer::from(text);
+        font.shape(&mut buf).unwrap();
+        let glyphs: Vec<GlyphId> = buf.glyph_ids().collect();
+        assert_eq!(layout.lines()[0].glyphs, glyphs);
+        let width = layout.lines()[0].width();
+        assert!(width > 0.0);
+
+        let max_width = width / 2.0;
+        let layout = TextLayout::new(text, &font, max_width).unwrap();
+        let lines = layout.lines();
+        assert!(lines.len() > 1);
+        assert_eq!(line_texts(text, &layout).concat(), text);
+        assert!(lines.iter().all(|line| line.width() <= max_width));
+
+        let fm = font.font_metrics();
+        let ascent = f64::from(fm.horizontal_ascent);
+        let line_height = ascent + f64::from(fm.horizontal_descent) + f64::from(fm.line_gap);
+        assert_eq!(lines[0].origin(), PointD { x: 0.0, y: ascent });
+        assert_eq!(lines[1].origin().y, ascent + line_height);
+        assert!(layout.bounding_box().x1 <= max_width);
+    }
+}
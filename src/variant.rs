@@ -3,6 +3,8 @@ use bitflags::bitflags;
 
 use crate::array::{Array, ArrayType};
 use crate::error::expect_mem_err;
+use crate::font::{Font, FontFace};
+use crate::gradient::{Gradient, GradientType};
 use crate::image::Image;
 use crate::path::Path;
 use crate::pattern::Pattern;
@@ -348,3 +350,90 @@ impl DeepClone for Region {
 impl DeepClone for Pattern {
     const ASSIGN_DEEP: BlAssignDeep<Self::Core> = ffi::blPatternAssignDeep;
 }
+
+// Thread safety
+//
+// Blend2D reference counts its impls atomically and never mutates an impl that
+// is shared: every mutating function first checks whether the impl is
+// mutable (refCount == 1 and not immutable/external) and otherwise works on a
+// fresh copy (copy-on-write). On the rust side every mutating call goes
+// through `&mut self`, while `&self` methods only read from the impl. This
+// makes the plain value types below safe to send to and share between
+// threads, even when several weak clones refer to the same impl.
+//
+// The exception is the info of a path (flags and bounding boxes), which
+// blend2d computes lazily and caches in the impl without synchronization.
+// `Path` therefore only updates the cache of an impl it doesn't share, see
+// `Path::with_info`, and is not `Sync`, as two threads could otherwise update
+// the cache of the same path at once.
+//
+// `Context` is deliberately excluded, its impl is a stateful renderer that
+// must only ever be driven by one thread at a time.
+unsafe impl Send for Image {}
+unsafe impl Sync for Image {}
+unsafe impl Send for Path {}
+unsafe impl<T: GradientType> Send for Gradient<T> {}
+unsafe impl<T: GradientType> Sync for Gradient<T> {}
+unsafe impl Send for Pattern {}
+unsafe impl Sync for Pattern {}
+unsafe impl Send for Region {}
+unsafe impl Sync for Region {}
+unsafe impl<T: ArrayType + Send> Send for Array<T> {}
+unsafe impl<T: ArrayType + Sync> Sync for Array<T> {}
+unsafe impl Send for FontFace {}
+unsafe impl Sync for FontFace {}
+unsafe impl Send for Font {}
+unsafe impl Sync for Font {}
+
+#[cfg(test)]
+mod test_variant {
+    use crate::array::Array;
+    use crate::font::{Font, FontFace};
+    use crate::gradient::{Conical, Gradient, Linear, Radial};
+    use crate::image::Image;
+    use crate::path::Path;
+    use crate::pattern::Pattern;
+    use crate::region::Region;
+
+    fn assert_send<T: Send>() {}
+    fn assert_send_sync<T: Send + Sync>() {}
+
+    #[test]
+    fn test_send_sync() {
+        assert_send_sync::<Image>();
+        assert_send::<Path>();
+        assert_send_sync::<Gradient<Linear>>();
+        assert_send_sync::<Gradient<Radial>>();
+        assert_send_sync::<Gradient<Conical>>();
+        assert_send_sync::<Pattern>();
+        assert_send_sync::<Region>();
+        assert_send_sync::<Array<u8>>();
+        assert_send::<Array<Path>>();
+        assert_send_sync::<FontFace>();
+        assert_send_sync::<Font>();
+    }
+
+    #[test]
+    fn test_path_info_of_shared_impl() {
+        let mut path = Path::new();
+        path.move_to(0.0, 0.0);
+        path.line_to(10.0, 5.0);
+        let clone = path.clone();
+        // The clone shares the out-of-date impl, which must not be updated.
+        let bounding_box = std::thread::spawn(move || clone.bounding_box())
+            .join()
+            .unwrap();
+        assert_eq!(bounding_box, path.bounding_box());
+        assert_eq!(path.control_box().map(|b| (b.x1, b.y1)), Some((10.0, 5.0)));
+    }
+
+    #[test]
+    fn test_send_path_to_thread() {
+        let mut path = Path::new();
+        path.move_to(0.0, 0.0);
+        path.line_to(10.0, 10.0);
+        let clone = path.clone();
+        let len = std::thread::spawn(move || clone.len()).join().unwrap();
+        assert_eq!(len, path.len());
+    }
+}